@@ -0,0 +1,55 @@
+// Cost of deriving one address: fuel-crypto's PublicKey::from, an EcDeriver reused for
+// every key as the search workers do, and a fresh EcDeriver per key, which pays for the
+// secp256k1 context setup every time.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fuel_crypto::SecretKey;
+use fuel_vanity_generator::{fuel_address_bytes, EcDeriver};
+use rand::RngCore;
+
+fn keys() -> Vec<[u8; 32]> {
+    let mut rng = rand::thread_rng();
+    (0..64)
+        .map(|_| {
+            let mut key = [0u8; 32];
+            rng.fill_bytes(&mut key);
+            key
+        })
+        .collect()
+}
+
+fn derivation(c: &mut Criterion) {
+    let keys = keys();
+    let mut group = c.benchmark_group("derive 64 addresses");
+
+    group.bench_function("fuel-crypto", |b| {
+        b.iter(|| {
+            for key in &keys {
+                let secret_key = SecretKey::try_from(&key[..]).unwrap();
+                black_box(fuel_address_bytes(&secret_key));
+            }
+        })
+    });
+
+    let deriver = EcDeriver::new();
+    group.bench_function("EcDeriver, reused", |b| {
+        b.iter(|| {
+            for key in &keys {
+                black_box(deriver.address(key));
+            }
+        })
+    });
+
+    group.bench_function("EcDeriver, one per key", |b| {
+        b.iter(|| {
+            for key in &keys {
+                black_box(EcDeriver::new().address(key));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, derivation);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+// Cost of one match check per candidate address: the byte-level matchers against the hex
+// string versions, which encode the address first.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fuel_vanity_generator::{HexMatcher, Matcher, SuffixMatcher};
+use rand::RngCore;
+
+fn addresses() -> Vec<[u8; 32]> {
+    let mut rng = rand::thread_rng();
+    (0..1024)
+        .map(|_| {
+            let mut address = [0u8; 32];
+            rng.fill_bytes(&mut address);
+            address
+        })
+        .collect()
+}
+
+fn check_all<M: Matcher>(matcher: &M, addresses: &[[u8; 32]]) -> usize {
+    addresses.iter().filter(|address| matcher.matches(black_box(address))).count()
+}
+
+fn suffix(c: &mut Criterion) {
+    let addresses = addresses();
+    let mut group = c.benchmark_group("suffix over 1024 addresses");
+    for pattern in ["beef", "cafe0"] {
+        let bytes = SuffixMatcher::new(pattern).unwrap();
+        let string = HexMatcher::new(pattern, "suffix").unwrap();
+        group.bench_function(format!("SuffixMatcher {}", pattern), |b| b.iter(|| check_all(&bytes, &addresses)));
+        group.bench_function(format!("ends_with {}", pattern), |b| b.iter(|| check_all(&string, &addresses)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, suffix);
+criterion_main!(benches);
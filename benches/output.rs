@@ -0,0 +1,66 @@
+// Formatting cost of the streaming output path: 100k (address, key) lines written into one
+// reused buffer, with push_hex against a hex::encode String per value.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fuel_vanity_generator::push_hex;
+use rand::RngCore;
+
+const RESULTS: usize = 100_000;
+
+fn results() -> Vec<([u8; 32], [u8; 32])> {
+    let mut rng = rand::thread_rng();
+    (0..RESULTS)
+        .map(|_| {
+            let mut address = [0u8; 32];
+            let mut key = [0u8; 32];
+            rng.fill_bytes(&mut address);
+            rng.fill_bytes(&mut key);
+            (address, key)
+        })
+        .collect()
+}
+
+fn stream_results(c: &mut Criterion) {
+    let results = results();
+    let mut group = c.benchmark_group("stream 100k results");
+    group.sample_size(10);
+
+    group.bench_function("push_hex", |b| {
+        let mut line = String::with_capacity(140);
+        b.iter(|| {
+            let mut written = 0;
+            for (address, key) in &results {
+                line.clear();
+                line.push_str("0x");
+                push_hex(&mut line, address);
+                line.push_str("\t0x");
+                push_hex(&mut line, key);
+                line.push('\n');
+                written += black_box(&line).len();
+            }
+            written
+        })
+    });
+
+    group.bench_function("hex::encode", |b| {
+        let mut line = String::with_capacity(140);
+        b.iter(|| {
+            let mut written = 0;
+            for (address, key) in &results {
+                line.clear();
+                line.push_str("0x");
+                line.push_str(&hex::encode(address));
+                line.push_str("\t0x");
+                line.push_str(&hex::encode(key));
+                line.push('\n');
+                written += black_box(&line).len();
+            }
+            written
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, stream_results);
+criterion_main!(benches);
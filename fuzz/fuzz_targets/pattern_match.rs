@@ -0,0 +1,87 @@
+#![no_main]
+
+// Input layout: [flags][32 bytes of key material][pattern bytes...]
+//   flags bits 0-1 select the position, bit 2 turns on case-sensitive matching.
+// Inputs shorter than 33 bytes only exercise the string matcher on raw data.
+
+use fuel_crypto::SecretKey;
+use fuel_vanity_generator::{fuel_address_bytes, matches_pattern, EcDeriver};
+use libfuzzer_sys::fuzz_target;
+
+const POSITIONS: [&str; 4] = ["prefix", "suffix", "anywhere", "unknown"];
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&flags, rest)) = data.split_first() else {
+        return;
+    };
+    let position = POSITIONS[(flags & 0b11) as usize];
+    let case_sensitive = flags & 0b100 != 0;
+
+    if rest.len() < 32 {
+        // Arbitrary text on both sides must never panic
+        let text = String::from_utf8_lossy(rest);
+        let _ = matches_pattern(&text, &text, position, case_sensitive);
+        return;
+    }
+
+    let (key_bytes, pattern_bytes) = rest.split_at(32);
+    let pattern = String::from_utf8_lossy(pattern_bytes);
+
+    // Not every 32-byte string is a valid secp256k1 scalar
+    let Ok(secret_key) = SecretKey::try_from(key_bytes) else {
+        return;
+    };
+
+    let address_bytes = fuel_address_bytes(&secret_key);
+    assert_eq!(address_bytes, fuel_address_bytes(&secret_key), "derivation is not deterministic");
+
+    let key_array: [u8; 32] = key_bytes.try_into().unwrap();
+    assert_eq!(EcDeriver::new().address(&key_array), Some(address_bytes), "EcDeriver disagrees with fuel_address_bytes");
+
+    let address = hex::encode(address_bytes);
+    let with_prefix = format!("0x{}", address);
+
+    let string_match = matches_pattern(&with_prefix, &pattern, position, case_sensitive);
+    assert_eq!(
+        string_match,
+        matches_pattern(&address, &pattern, position, case_sensitive),
+        "the 0x prefix changed the outcome"
+    );
+    assert_eq!(
+        string_match,
+        nibble_match(&address_bytes, &pattern, position, case_sensitive),
+        "string and nibble matchers disagree for pattern {:?} at {}",
+        pattern,
+        position
+    );
+});
+
+// Reference matcher working directly on address nibbles instead of the hex string
+fn nibble_match(address: &[u8; 32], pattern: &str, position: &str, case_sensitive: bool) -> bool {
+    let nibbles: Vec<u8> = address.iter().flat_map(|b| [b >> 4, b & 0xF]).collect();
+
+    // The address is rendered in lowercase, so uppercase only matches when case is ignored
+    let mut wanted = Vec::with_capacity(pattern.len());
+    for c in pattern.chars() {
+        let value = match c {
+            '0'..='9' | 'a'..='f' => c.to_digit(16),
+            'A'..='F' if !case_sensitive => c.to_digit(16),
+            _ => None,
+        };
+        match value {
+            Some(v) => wanted.push(v as u8),
+            None => return false,
+        }
+    }
+
+    if wanted.len() > nibbles.len() {
+        return false;
+    }
+
+    match position {
+        "prefix" => nibbles.starts_with(&wanted),
+        "suffix" => nibbles.ends_with(&wanted),
+        "anywhere" => wanted.is_empty() || nibbles.windows(wanted.len()).any(|w| w == wanted.as_slice()),
+        _ => false,
+    }
+}
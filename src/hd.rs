@@ -0,0 +1,78 @@
+// Hierarchical deterministic keys: BIP-39 mnemonics derived along BIP-32/BIP-44 paths
+use crate::VanityError;
+use fuel_crypto::SecretKey;
+
+// Registered BIP-44 coin type of Fuel
+pub const FUEL_COIN_TYPE: u32 = 1179993420;
+
+// Derive the secret key at `path` (e.g. "m/44'/1179993420'/0'/0/0") for a BIP-39 mnemonic.
+// The path is checked before the mnemonic, whose word list and checksum are verified too.
+pub fn derive_key_at_path(mnemonic: &str, path: &str) -> Result<SecretKey, VanityError> {
+    validate_path(path)?;
+    SecretKey::new_from_mnemonic_phrase_with_path(mnemonic, path).map_err(|e| match e {
+        fuel_crypto::Error::InvalidMnemonic => VanityError::InvalidMnemonic,
+        e => VanityError::Derivation(e),
+    })
+}
+
+// The default Fuel wallet path for an account index
+pub fn fuel_account_path(index: u32) -> String {
+    format!("m/44'/{}'/{}'/0/0", FUEL_COIN_TYPE, index)
+}
+
+// A path is "m" followed by "/"-separated indices below 2^31, each optionally hardened with '
+fn validate_path(path: &str) -> Result<(), VanityError> {
+    let invalid = || VanityError::InvalidPath(path.to_string());
+    let mut components = path.split('/');
+    if components.next() != Some("m") {
+        return Err(invalid());
+    }
+    for component in components {
+        let index = component.strip_suffix('\'').unwrap_or(component);
+        if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        match index.parse::<u32>() {
+            Ok(index) if index < 1 << 31 => {}
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The mnemonic and addresses of the Fuel SDK's own wallet-from-mnemonic test
+    const SDK_PHRASE: &str = "oblige salon price punch saddle immune slogan rare snap desert retire surprise";
+
+    fn address_at(path: &str) -> String {
+        hex::encode(crate::fuel_address_bytes(&derive_key_at_path(SDK_PHRASE, path).unwrap()))
+    }
+
+    #[test]
+    fn derives_the_published_sdk_addresses() {
+        assert_eq!(address_at("m/44'/60'/0'/0/0"), "df9d0e6c6c5f5da6e82e5e1a77974af6642bdb450a10c43f0c6910a212600185");
+        assert_eq!(address_at("m/44'/60'/1'/0/0"), "261191b0164a24fd0fd51566ec5e5b0b9ba8fb2d42dc9cf7dbbd6f23d2742759");
+    }
+
+    #[test]
+    fn fuel_account_path_uses_the_fuel_coin_type() {
+        assert_eq!(fuel_account_path(3), "m/44'/1179993420'/3'/0/0");
+        assert!(validate_path(&fuel_account_path(0)).is_ok());
+    }
+
+    #[test]
+    fn rejects_malformed_paths_and_mnemonics() {
+        for path in ["", "44'/60'", "m/", "m//0", "m/x", "m/0''", "m/2147483648", "m/-1"] {
+            assert!(matches!(derive_key_at_path(SDK_PHRASE, path), Err(VanityError::InvalidPath(_))), "{:?}", path);
+        }
+        assert!(derive_key_at_path(SDK_PHRASE, "m").is_ok());
+
+        // A real word list entry in the wrong place breaks the checksum
+        let bad_checksum = SDK_PHRASE.replace("surprise", "oblige");
+        assert!(matches!(derive_key_at_path(&bad_checksum, "m/44'/60'/0'/0/0"), Err(VanityError::InvalidMnemonic)));
+        assert!(derive_key_at_path("not a mnemonic", "m/44'/60'/0'/0/0").is_err());
+    }
+}
@@ -1,14 +1,72 @@
+use base64::prelude::*;
+use bech32::{FromBase32, ToBase32, Variant};
+use blake2::digest::consts::U32;
+use blake2::Blake2b;
 use fuel_crypto::{PublicKey, SecretKey};
 use rand::rngs::OsRng;
 use rand::RngCore;
+use rayon::prelude::*;
+use secp256k1::{Secp256k1, SignOnly};
+use sha2::Sha256;
 use sha3::{Digest, Keccak256};
-use hex;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
 
+pub mod hd;
+
+// Errors returned by the library's fallible operations
+#[derive(Debug)]
+pub enum VanityError {
+    /// A derivation path that isn't of the form m/44'/1179993420'/0'/0/0
+    InvalidPath(String),
+    /// A mnemonic with an unknown word, a wrong length or a bad checksum
+    InvalidMnemonic,
+    /// A private key that isn't up to 64 hex digits of a valid secp256k1 scalar
+    InvalidKey { input: String },
+    /// Key derivation failed after the inputs were accepted
+    Derivation(fuel_crypto::Error),
+}
+
+impl fmt::Display for VanityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VanityError::InvalidPath(path) => write!(f, "invalid derivation path '{}'", path),
+            VanityError::InvalidMnemonic => write!(f, "invalid mnemonic phrase"),
+            VanityError::InvalidKey { input } => write!(f, "invalid private key '{}'", input),
+            VanityError::Derivation(e) => write!(f, "key derivation failed: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for VanityError {}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VanitySearchResult {
     pub private_key: String,
     pub address: String,
 }
 
+// Tab-separated "address<TAB>private_key", handy for logs and line-based tools
+impl fmt::Display for VanitySearchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{}", self.address, self.private_key)
+    }
+}
+
+// Progress reported by `search_vanity_with_callback`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SearchEvent {
+    /// A candidate key was checked; carries the total number of attempts so far
+    Attempted(u64),
+    /// A candidate key matched the pattern
+    Found(VanitySearchResult),
+}
+
 // Core functionality for generating and validating wallet addresses
 pub fn search_vanity_address(
     pattern: &str, 
@@ -18,6 +76,27 @@ pub fn search_vanity_address(
 ) -> Vec<VanitySearchResult> {
     let mut results = Vec::new();
     
+    search_vanity_with_callback(pattern, position, case_sensitive, max_addresses, |event| {
+        if let SearchEvent::Found(result) = event {
+            results.push(result);
+        }
+        true
+    });
+    
+    results
+}
+
+// Same search as `search_vanity_address`, reporting every attempt and match to `on_event`.
+// The callback runs synchronously inside the search loop on the calling thread, so it does
+// not need to be Send, but a slow callback slows the search down. Returning false stops the
+// search early; otherwise it runs until `max_addresses` matches were reported.
+pub fn search_vanity_with_callback<F: FnMut(SearchEvent) -> bool>(
+    pattern: &str,
+    position: &str,
+    case_sensitive: bool,
+    max_addresses: u32,
+    mut on_event: F
+) {
     let lowercase_pattern = if !case_sensitive {
         pattern.to_lowercase()
     } else {
@@ -25,18 +104,20 @@ pub fn search_vanity_address(
     };
     
     let mut found_count = 0;
+    let mut attempts: u64 = 0;
     
     while found_count < max_addresses {
         // Generate random private key
         let mut key_bytes = [0u8; 32];
         OsRng.fill_bytes(&mut key_bytes);
+        attempts += 1;
         
         if let Ok(secret_key) = SecretKey::try_from(&key_bytes[..]) {
             // Get the public key from the secret key
             let public_key = PublicKey::from(&secret_key);
             
             // Convert to Ethereum-style address
-            let hash = keccak256_hash(&public_key.as_ref());
+            let hash = keccak256_hash(public_key.as_ref());
             let address = format!("0x{}", hex::encode(&hash[12..]));
             
             // Check if the address matches the pattern based on position
@@ -53,38 +134,141 @@ pub fn search_vanity_address(
                 _ => false,
             };
             
+            if !on_event(SearchEvent::Attempted(attempts)) {
+                return;
+            }
+            
             if is_match {
                 // Found a match
-                results.push(VanitySearchResult {
+                found_count += 1;
+                
+                let result = VanitySearchResult {
                     private_key: format!("0x{}", hex::encode(secret_key.as_ref())),
                     address,
-                });
-                
-                found_count += 1;
+                };
+                if !on_event(SearchEvent::Found(result)) {
+                    return;
+                }
             }
         }
     }
+}
+
+// Everything needed to continue a search later: its parameters and the totals so far. With
+// the `serde` feature it can be saved between runs by whatever persistence the embedder uses.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchState {
+    pub pattern: String,
+    pub position: String,
+    pub case_sensitive: bool,
+    pub max_addresses: u32,
+    /// Keys checked over every run so far
+    pub attempts: u64,
+    /// Time spent searching over every run so far, not counting the time in between
+    pub elapsed: Duration,
+    pub results: Vec<VanitySearchResult>,
+}
+
+impl SearchState {
+    // A search that hasn't run yet
+    pub fn new(pattern: &str, position: &str, case_sensitive: bool, max_addresses: u32) -> Self {
+        SearchState {
+            pattern: pattern.to_string(),
+            position: position.to_string(),
+            case_sensitive,
+            max_addresses,
+            attempts: 0,
+            elapsed: Duration::ZERO,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.results.len() >= self.max_addresses as usize
+    }
+
+    // Keys checked per second over every run so far
+    pub fn rate(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 { self.attempts as f64 / seconds } else { 0.0 }
+    }
+}
+
+// Continue the search described by `state` until it has all its matches or `on_event`
+// returns false, adding this run's attempts, matches and time to it. Events report the
+// cumulative attempt count, so progress carries on from where the last run stopped.
+pub fn search_vanity_address_resumable<F: FnMut(SearchEvent) -> bool>(state: &mut SearchState, mut on_event: F) {
+    let remaining = state.max_addresses.saturating_sub(state.results.len() as u32);
+    if remaining == 0 {
+        return;
+    }
     
-    results
+    let start = Instant::now();
+    let previous_attempts = state.attempts;
+    let (pattern, position) = (state.pattern.clone(), state.position.clone());
+    search_vanity_with_callback(&pattern, &position, state.case_sensitive, remaining, |event| {
+        let event = match event {
+            SearchEvent::Attempted(attempts) => {
+                state.attempts = previous_attempts + attempts;
+                SearchEvent::Attempted(state.attempts)
+            }
+            SearchEvent::Found(result) => {
+                state.results.push(result.clone());
+                SearchEvent::Found(result)
+            }
+        };
+        on_event(event)
+    });
+    state.elapsed += start.elapsed();
 }
 
-// Add a method to verify that a given private key produces the expected address
-pub fn verify_key_address_pair(private_key: &str, expected_address: &str) -> bool {
-    // Remove 0x prefix if present
-    let clean_key = private_key.trim_start_matches("0x");
+// Parse a hex private key, with or without 0x. Shorter keys are zero-padded on the left to
+// 64 digits; longer ones are rejected rather than silently truncated. Zero and values at
+// or above the curve order are rejected too. The error carries the input as given.
+// Padded base64 of the 32 key bytes, as written by --key-format base64, is accepted as
+// well; its trailing '=' keeps it apart from hex.
+pub fn parse_private_key(input: &str) -> Result<SecretKey, VanityError> {
+    let invalid = || VanityError::InvalidKey { input: input.to_string() };
+    if input.ends_with('=') {
+        let bytes = BASE64_STANDARD.decode(input).map_err(|_| invalid())?;
+        return SecretKey::try_from(&bytes[..]).map_err(|_| invalid());
+    }
+    let digits = input.strip_prefix("0x").unwrap_or(input);
+    if digits.is_empty() || digits.len() > 64 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
     
+    let mut bytes = [0u8; 32];
+    hex::decode_to_slice(format!("{:0>64}", digits), &mut bytes).map_err(|_| invalid())?;
+    SecretKey::try_from(&bytes[..]).map_err(|_| invalid())
+}
+
+// Add a method to verify that a given private key produces the expected address. A 64-digit
+// address, with or without 0x, is checked as a Fuel address; anything else as an
+// Ethereum-style one.
+pub fn verify_key_address_pair(private_key: &str, expected_address: &str) -> bool {
+    verify_key_address_pair_with_hash(private_key, expected_address, AddressHash::Sha256)
+}
+
+// Same as `verify_key_address_pair`, but a 64-digit address is checked against the given hash
+// of the public key instead of SHA-256
+pub fn verify_key_address_pair_with_hash(private_key: &str, expected_address: &str, hash: AddressHash) -> bool {
     // Parse the private key
-    if let Ok(bytes) = hex::decode(clean_key) {
-        if let Ok(secret_key) = SecretKey::try_from(&bytes[..]) {
-            // Get the public key from the secret key
-            let public_key = PublicKey::from(&secret_key);
-            
-            // Convert to Ethereum-style address
-            let hash = keccak256_hash(&public_key.as_ref());
-            let address = format!("0x{}", hex::encode(&hash[12..]));
-            
-            return address.eq_ignore_ascii_case(expected_address);
+    if let Ok(secret_key) = parse_private_key(private_key) {
+        // Get the public key from the secret key
+        let public_key = PublicKey::from(&secret_key);
+
+        let expected_digits = expected_address.strip_prefix("0x").unwrap_or(expected_address);
+        if expected_digits.len() == 64 {
+            return hex::encode(hash.digest(public_key.as_ref())).eq_ignore_ascii_case(expected_digits);
         }
+        
+        // Convert to Ethereum-style address
+        let hash = keccak256_hash(public_key.as_ref());
+        let address = format!("0x{}", hex::encode(&hash[12..]));
+        
+        return address.eq_ignore_ascii_case(expected_address);
     }
     
     false
@@ -92,10 +276,1112 @@ pub fn verify_key_address_pair(private_key: &str, expected_address: &str) -> boo
 
 // Helper function to calculate keccak256 hash (for Ethereum-style addresses)
 fn keccak256_hash(data: &[u8]) -> [u8; 32] {
-    let mut hasher = Keccak256::new();
-    hasher.update(data);
-    let result = hasher.finalize();
+    AddressHash::Keccak256.digest(data)
+}
+
+// Number of public key bytes hashed into a Fuel address: the x and y coordinates of the
+// uncompressed key, 32 bytes each, WITHOUT the 0x04 SEC1 tag in front of them. Hashing the
+// 65-byte tagged form or the 33-byte compressed form gives addresses no Fuel wallet knows.
+// With this input the secret key 0x00..01 has the address
+// 0x09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db, as in the Fuel SDK.
+pub const ADDRESS_HASH_INPUT_LEN: usize = 64;
+
+// Hash applied to the 64 public key bytes to get a 32-byte address. Fuel uses SHA-256; the
+// others give the addresses of tooling that hashes the same key differently, not Fuel ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AddressHash {
+    #[default]
+    Sha256,
+    Keccak256,
+    // BLAKE2b with a 32-byte output (BLAKE2b-256), not the 64-byte default
+    Blake2b,
+}
+
+impl AddressHash {
+    // Name used on the command line and in JSON output
+    pub fn name(self) -> &'static str {
+        match self {
+            AddressHash::Sha256 => "sha256",
+            AddressHash::Keccak256 => "keccak256",
+            AddressHash::Blake2b => "blake2b",
+        }
+    }
+
+    // Name for people, as in "SHA-256 of the public key"
+    pub fn label(self) -> &'static str {
+        match self {
+            AddressHash::Sha256 => "SHA-256",
+            AddressHash::Keccak256 => "Keccak-256",
+            AddressHash::Blake2b => "BLAKE2b-256",
+        }
+    }
+
+    pub fn digest(self, data: &[u8]) -> [u8; 32] {
+        match self {
+            AddressHash::Sha256 => digest_32::<Sha256>(data),
+            AddressHash::Keccak256 => digest_32::<Keccak256>(data),
+            AddressHash::Blake2b => digest_32::<Blake2b<U32>>(data),
+        }
+    }
+}
+
+fn digest_32<D: Digest>(data: &[u8]) -> [u8; 32] {
     let mut hash = [0u8; 32];
-    hash.copy_from_slice(&result[..]);
+    hash.copy_from_slice(&D::digest(data)[..]);
     hash
 }
+
+// Derive a Fuel address: the SHA-256 hash of the public key for the given secret key
+pub fn fuel_address_bytes(secret_key: &SecretKey) -> [u8; 32] {
+    let public_key = PublicKey::from(secret_key);
+    // fuel-crypto's PublicKey already holds just the two coordinates
+    let hash_input: &[u8; ADDRESS_HASH_INPUT_LEN] = public_key.as_ref().try_into()
+        .expect("fuel-crypto public keys are 64 bytes");
+    let mut hasher = Sha256::new();
+    hasher.update(hash_input);
+    let mut address = [0u8; 32];
+    address.copy_from_slice(&hasher.finalize()[..]);
+    address
+}
+
+// scrypt cost of passphrase keys: N = 2^15, r = 8, p = 1, which takes 32 MiB and tens of
+// milliseconds per key. It is part of the derivation, so changing it changes every key.
+pub const PASSPHRASE_SCRYPT_LOG_N: u8 = 15;
+
+// Brain-wallet style key: scrypt of the passphrase, salted with the counter as 8 little-endian
+// bytes. The same passphrase and counter always give the same key. The result can be an
+// invalid secret key with negligible probability, which EcDeriver reports as usual.
+pub fn passphrase_key(passphrase: &str, counter: u64) -> [u8; 32] {
+    let params = scrypt::Params::new(PASSPHRASE_SCRYPT_LOG_N, 8, 1, 32).expect("valid scrypt parameters");
+    let mut key = [0u8; 32];
+    scrypt::scrypt(passphrase.as_bytes(), &counter.to_le_bytes(), &params, &mut key)
+        .expect("32 bytes is a valid scrypt output length");
+    key
+}
+
+// Address of the secret key 0x00..01, as derived by the Fuel SDK
+const KEY_ONE_ADDRESS: &str = "09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db";
+
+// Guard against dependency drift: derive the address of the key 0x00..01 through both
+// fuel-crypto and secp256k1 and compare the public key length and both addresses with
+// the known values. An upgrade that changed the public key serialization would otherwise
+// silently change every address. Cheap enough to run at every startup.
+pub fn check_derivation() -> Result<(), String> {
+    let mut key = [0u8; 32];
+    key[31] = 1;
+    
+    let secret_key = SecretKey::try_from(&key[..]).map_err(|e| format!("fuel-crypto rejected the test key: {}", e))?;
+    let length = PublicKey::from(&secret_key).as_ref().len();
+    if length != ADDRESS_HASH_INPUT_LEN {
+        return Err(format!(
+            "fuel-crypto public keys are {} bytes, expected {}",
+            length, ADDRESS_HASH_INPUT_LEN
+        ));
+    }
+    
+    let derived = [
+        ("fuel-crypto", Some(fuel_address_bytes(&secret_key))),
+        ("secp256k1", EcDeriver::new().address(&key)),
+    ];
+    for (library, address) in derived {
+        let address = address.map(hex::encode).unwrap_or_default();
+        if address != KEY_ONE_ADDRESS {
+            return Err(format!(
+                "{} derives 0x{} for the key 0x00..01, expected 0x{}",
+                library, address, KEY_ONE_ADDRESS
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Derives Fuel addresses straight from raw key bytes. Each search worker creates one
+// and reuses its secp256k1 context for every key instead of going through string parsing.
+pub struct EcDeriver {
+    context: Secp256k1<SignOnly>,
+    hash: AddressHash,
+}
+
+impl EcDeriver {
+    pub fn new() -> Self {
+        Self::with_hash(AddressHash::Sha256)
+    }
+
+    // A deriver whose `address` applies the given hash instead of SHA-256
+    pub fn with_hash(hash: AddressHash) -> Self {
+        EcDeriver { context: Secp256k1::signing_only(), hash }
+    }
+
+    pub fn hash(&self) -> AddressHash {
+        self.hash
+    }
+
+    // The public key in the form Fuel uses: the 64 coordinate bytes, without the 0x04 tag.
+    // None if the bytes are not a valid secret key.
+    pub fn public_key(&self, key_bytes: &[u8; 32]) -> Option<[u8; ADDRESS_HASH_INPUT_LEN]> {
+        let secret_key = secp256k1::SecretKey::from_slice(key_bytes).ok()?;
+        let public_key = secp256k1::PublicKey::from_secret_key(&self.context, &secret_key);
+        let uncompressed = public_key.serialize_uncompressed();
+        let mut coordinates = [0u8; ADDRESS_HASH_INPUT_LEN];
+        coordinates.copy_from_slice(&uncompressed[uncompressed.len() - ADDRESS_HASH_INPUT_LEN..]);
+        Some(coordinates)
+    }
+
+    // Same result as `fuel_address_bytes` with the default hash, or None if the bytes are
+    // not a valid secret key
+    pub fn address(&self, key_bytes: &[u8; 32]) -> Option<[u8; 32]> {
+        Some(self.hash.digest(&self.public_key(key_bytes)?))
+    }
+
+    // Keccak-256 hash of the 64 coordinate bytes, before it is cut down to an Ethereum address
+    pub fn ethereum_hash(&self, key_bytes: &[u8; 32]) -> Option<[u8; 32]> {
+        Some(keccak256_hash(&self.public_key(key_bytes)?))
+    }
+
+    // Ethereum address of the same key: the last 20 bytes of the Keccak-256 hash of the
+    // 64 coordinate bytes, as in `verify_key_address_pair`
+    pub fn ethereum_address(&self, key_bytes: &[u8; 32]) -> Option<[u8; 20]> {
+        let hash = self.ethereum_hash(key_bytes)?;
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+        Some(address)
+    }
+}
+
+impl Default for EcDeriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Append the lowercase hex digits of `bytes` to `out`. Every candidate address is formatted
+// in the search loop and every result again for output, so going through hex::encode would
+// allocate a String per value only to copy it into the final one.
+pub fn push_hex(out: &mut String, bytes: &[u8]) {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    out.reserve(bytes.len() * 2);
+    for byte in bytes {
+        out.push(DIGITS[(byte >> 4) as usize] as char);
+        out.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+}
+
+// Human-readable part of Fuel bech32m addresses
+pub const FUEL_BECH32_HRP: &str = "fuel";
+
+// Human-readable part this tool gives testnet addresses, so they can't be mistaken for
+// mainnet ones. Only the checksum differs; the data characters after the "1" are the same.
+pub const FUEL_TESTNET_BECH32_HRP: &str = "tfuel";
+
+// Fuel network, which picks the human-readable part of bech32m addresses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    pub fn hrp(self) -> &'static str {
+        match self {
+            Network::Mainnet => FUEL_BECH32_HRP,
+            Network::Testnet => FUEL_TESTNET_BECH32_HRP,
+        }
+    }
+}
+
+// Encode address bytes in the Fuel mainnet bech32m form ("fuel1...")
+pub fn to_bech32(address: &[u8; 32]) -> String {
+    to_bech32_for_network(address, Network::Mainnet)
+}
+
+// Encode address bytes in the bech32m form of `network`
+pub fn to_bech32_for_network(address: &[u8; 32], network: Network) -> String {
+    bech32::encode(network.hrp(), address.to_base32(), Variant::Bech32m)
+        .expect("the Fuel HRPs are valid bech32 prefixes")
+}
+
+// String encodings of a 32-byte Fuel address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AddressFormat {
+    /// "0x" and 64 hex digits, in any letter case
+    Hex,
+    /// "0x" and 64 hex digits cased by the EIP-55 rule
+    Checksum,
+    /// Bech32m with the human-readable part of the network, "fuel" on mainnet
+    Bech32,
+}
+
+// Encode address bytes as hex with EIP-55 casing: a letter is uppercase when the matching
+// nibble of the Keccak-256 hash of the lowercase hex digits is 8 or more
+pub fn to_checksum_hex(address: &[u8; 32]) -> String {
+    let digits = hex::encode(address);
+    let hash = keccak256_hash(digits.as_bytes());
+    let cased: String = digits
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0xf;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+    format!("0x{}", cased)
+}
+
+// Check that a string is a well-formed address in the given format: length, alphabet,
+// prefix, and for the checksum format the casing, for the bech32 format the checksum.
+// Bech32 addresses must be mainnet ones.
+pub fn is_valid_address(s: &str, fmt: AddressFormat) -> bool {
+    is_valid_address_for_network(s, fmt, Network::Mainnet)
+}
+
+// Same as `is_valid_address`, but bech32 addresses must carry the HRP of `network`
+pub fn is_valid_address_for_network(s: &str, fmt: AddressFormat, network: Network) -> bool {
+    match fmt {
+        AddressFormat::Hex => parse_hex_address(s).is_some(),
+        AddressFormat::Checksum => parse_hex_address(s).is_some_and(|address| to_checksum_hex(&address) == s),
+        AddressFormat::Bech32 => match bech32::decode(s) {
+            Ok((hrp, data, Variant::Bech32m)) if hrp == network.hrp() => {
+                Vec::<u8>::from_base32(&data).is_ok_and(|bytes| bytes.len() == 32)
+            }
+            _ => false,
+        },
+    }
+}
+
+// Parse "0x" and exactly 64 hex digits into address bytes
+fn parse_hex_address(s: &str) -> Option<[u8; 32]> {
+    let digits = s.strip_prefix("0x")?;
+    if digits.len() != 64 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut address = [0u8; 32];
+    hex::decode_to_slice(digits, &mut address).ok()?;
+    Some(address)
+}
+
+// Check an address string against a pattern at a position ("prefix", "suffix" or "anywhere")
+pub fn matches_pattern(address: &str, pattern: &str, position: &str, case_sensitive: bool) -> bool {
+    // Remove the "0x" prefix if it exists
+    let address = address.strip_prefix("0x").unwrap_or(address);
+
+    if !case_sensitive {
+        let address = address.to_lowercase();
+        let pattern = pattern.to_lowercase();
+        
+        match position {
+            "prefix" => address.starts_with(&pattern),
+            "suffix" => address.ends_with(&pattern),
+            "anywhere" => address.contains(&pattern),
+            _ => false,
+        }
+    } else {
+        match position {
+            "prefix" => address.starts_with(pattern),
+            "suffix" => address.ends_with(pattern),
+            "anywhere" => address.contains(pattern),
+            _ => false,
+        }
+    }
+}
+
+// Decides whether a Fuel address is wanted. Implement it to search for anything the built-in
+// matchers don't cover, and pass it to `search_with_matcher`. Closures taking the address
+// bytes are matchers too.
+pub trait Matcher: Send + Sync {
+    fn matches(&self, addr: &[u8; 32]) -> bool;
+}
+
+impl<F: Fn(&[u8; 32]) -> bool + Send + Sync> Matcher for F {
+    fn matches(&self, addr: &[u8; 32]) -> bool {
+        self(addr)
+    }
+}
+
+// A hex pattern at one position of the address, compared case-insensitively
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HexMatcher {
+    Prefix(String),
+    Suffix(String),
+    Contains(String),
+}
+
+impl HexMatcher {
+    // Build from a position name as `matches_pattern` takes it ("anywhere" and "contains"
+    // both mean Contains). None for an unknown position.
+    pub fn new(pattern: &str, position: &str) -> Option<Self> {
+        let pattern = pattern.strip_prefix("0x").unwrap_or(pattern).to_lowercase();
+        match position {
+            "prefix" => Some(HexMatcher::Prefix(pattern)),
+            "suffix" => Some(HexMatcher::Suffix(pattern)),
+            "anywhere" | "contains" => Some(HexMatcher::Contains(pattern)),
+            _ => None,
+        }
+    }
+}
+
+impl Matcher for HexMatcher {
+    fn matches(&self, addr: &[u8; 32]) -> bool {
+        let address = hex::encode(addr);
+        match self {
+            HexMatcher::Prefix(pattern) => address.starts_with(pattern.as_str()),
+            HexMatcher::Suffix(pattern) => address.ends_with(pattern.as_str()),
+            HexMatcher::Contains(pattern) => address.contains(pattern.as_str()),
+        }
+    }
+}
+
+// Compile a mask into (value, mask) nibble pairs: a hex digit must match exactly and `?`
+// matches anything, so a digit matches when `digit & mask == value`. None if the mask has any
+// other character, so a typo is rejected instead of widening the search.
+pub fn compile_mask(pattern: &str) -> Option<Vec<(u8, u8)>> {
+    pattern
+        .chars()
+        .map(|c| match (c, c.to_digit(16)) {
+            (_, Some(value)) => Some((value as u8, 0xf)),
+            ('?', None) => Some((0, 0)),
+            _ => None,
+        })
+        .collect()
+}
+
+// A prefix mask where `?` matches any hex digit, e.g. "00??ff". Compares nibbles of the
+// address bytes directly, without hex encoding them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaskMatcher {
+    nibbles: Vec<(u8, u8)>,
+}
+
+impl MaskMatcher {
+    // None if the mask has anything but hex digits and `?` or is longer than an address
+    pub fn new(pattern: &str) -> Option<Self> {
+        if pattern.len() > 64 {
+            return None;
+        }
+        Some(MaskMatcher { nibbles: compile_mask(pattern)? })
+    }
+}
+
+impl Matcher for MaskMatcher {
+    fn matches(&self, addr: &[u8; 32]) -> bool {
+        self.nibbles.len() <= addr.len() * 2
+            && self.nibbles.iter().enumerate().all(|(i, &(value, mask))| {
+                let byte = addr[i / 2];
+                let nibble = if i % 2 == 0 { byte >> 4 } else { byte & 0xf };
+                nibble & mask == value
+            })
+    }
+}
+
+// A hex suffix compared case-insensitively against the trailing address bytes, without hex
+// encoding the address. An odd-length pattern starts in the low nibble of its first byte,
+// so that byte is compared under a 0x0f mask.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixMatcher {
+    bytes: Vec<u8>,
+    odd: bool,
+}
+
+impl SuffixMatcher {
+    // None if the pattern has anything but hex digits or is longer than an address
+    pub fn new(pattern: &str) -> Option<Self> {
+        let pattern = pattern.strip_prefix("0x").unwrap_or(pattern);
+        if pattern.len() > 64 || !pattern.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let odd = pattern.len() % 2 == 1;
+        let padded = if odd { format!("0{}", pattern) } else { pattern.to_string() };
+        Some(SuffixMatcher { bytes: hex::decode(padded).ok()?, odd })
+    }
+}
+
+impl Matcher for SuffixMatcher {
+    fn matches(&self, addr: &[u8; 32]) -> bool {
+        let tail = &addr[addr.len() - self.bytes.len()..];
+        match (self.odd, self.bytes.split_first()) {
+            (true, Some((first, rest))) => tail[0] & 0x0f == *first && tail[1..] == *rest,
+            _ => tail == self.bytes.as_slice(),
+        }
+    }
+}
+
+// How unusual an address looks, in hex digits: its leading zeros, its longest run of one
+// repeated digit and its longest run counting up or down by one, as in 3456 or dcba
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct SurpriseScore {
+    pub leading_zeros: u32,
+    pub longest_run: u32,
+    pub longest_sequence: u32,
+}
+
+impl SurpriseScore {
+    // Sum of the three; leading zeros are also a run, so they count twice
+    pub fn total(&self) -> u32 {
+        self.leading_zeros + self.longest_run + self.longest_sequence
+    }
+}
+
+// Score the 64 hex digits of an address in one pass over its bytes, without hex encoding it
+pub fn surprise_score(address: &[u8; 32]) -> SurpriseScore {
+    let mut score = SurpriseScore::default();
+    let (mut run, mut up, mut down) = (0, 0, 0);
+    let mut previous: Option<u8> = None;
+    let mut leading = true;
+    for digit in address.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]) {
+        leading &= digit == 0;
+        if leading {
+            score.leading_zeros += 1;
+        }
+        (run, up, down) = match previous {
+            Some(previous) => (
+                if digit == previous { run + 1 } else { 1 },
+                if digit == previous.wrapping_add(1) { up + 1 } else { 1 },
+                if digit == previous.wrapping_sub(1) { down + 1 } else { 1 },
+            ),
+            None => (1, 1, 1),
+        };
+        score.longest_run = score.longest_run.max(run);
+        score.longest_sequence = score.longest_sequence.max(up).max(down);
+        previous = Some(digit);
+    }
+    score
+}
+
+// One attempt of a search: draw a key from `rng`, derive its Fuel address and check it
+// with `matcher`. Some on a hit, None on a miss or an invalid key. A building block for
+// loops that drive the search themselves, e.g. on wasm or in tests with a fixed rng. It sets
+// up a secp256k1 context per call; search_with_matcher reuses one instead.
+pub fn try_once<R: RngCore + ?Sized, M: Matcher + ?Sized>(rng: &mut R, matcher: &M) -> Option<VanitySearchResult> {
+    attempt(&EcDeriver::new(), rng, matcher)
+}
+
+fn attempt<R: RngCore + ?Sized, M: Matcher + ?Sized>(
+    deriver: &EcDeriver,
+    rng: &mut R,
+    matcher: &M
+) -> Option<VanitySearchResult> {
+    let mut key_bytes = [0u8; 32];
+    rng.fill_bytes(&mut key_bytes);
+    let address = deriver.address(&key_bytes)?;
+    matcher.matches(&address).then(|| VanitySearchResult {
+        private_key: format!("0x{}", hex::encode(key_bytes)),
+        address: format!("0x{}", hex::encode(address)),
+    })
+}
+
+// Search random keys for Fuel addresses accepted by `matcher`, reporting attempts and
+// matches to `on_event` the same way `search_vanity_with_callback` does. Works with a
+// `Box<dyn Matcher>` as well as a concrete matcher.
+pub fn search_with_matcher<M: Matcher + ?Sized, F: FnMut(SearchEvent) -> bool>(
+    matcher: &M,
+    max_addresses: u32,
+    mut on_event: F
+) {
+    let deriver = EcDeriver::new();
+    let mut found_count = 0;
+    let mut attempts: u64 = 0;
+    
+    while found_count < max_addresses {
+        attempts += 1;
+        let hit = attempt(&deriver, &mut OsRng, matcher);
+        if !on_event(SearchEvent::Attempted(attempts)) {
+            return;
+        }
+        
+        if let Some(result) = hit {
+            found_count += 1;
+            if !on_event(SearchEvent::Found(result)) {
+                return;
+            }
+        }
+    }
+}
+
+// Receives the matches of a search as they are found. Implement it to send results where the
+// built-in sinks don't, and pass it to `search_into_sink`. Closures taking a result are sinks
+// too.
+pub trait ResultSink {
+    fn emit(&mut self, result: &VanitySearchResult);
+}
+
+impl<F: FnMut(&VanitySearchResult)> ResultSink for F {
+    fn emit(&mut self, result: &VanitySearchResult) {
+        self(result)
+    }
+}
+
+// Prints each result to stdout as its Display line, "address<TAB>private_key"
+#[derive(Debug, Default)]
+pub struct StdoutSink;
+
+impl ResultSink for StdoutSink {
+    fn emit(&mut self, result: &VanitySearchResult) {
+        println!("{}", result);
+    }
+}
+
+// Writes each result to a file as its Display line. `emit` can't fail, so the first write
+// error is kept and returned by `finish`, and nothing more is written after it.
+#[derive(Debug)]
+pub struct FileSink {
+    writer: BufWriter<File>,
+    error: Option<io::Error>,
+}
+
+impl FileSink {
+    // Create or truncate the file at `path`
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(FileSink { writer: BufWriter::new(File::create(path)?), error: None })
+    }
+
+    // Flush the file, returning the first error of any write
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => self.writer.flush(),
+        }
+    }
+}
+
+impl ResultSink for FileSink {
+    fn emit(&mut self, result: &VanitySearchResult) {
+        if self.error.is_none() {
+            self.error = writeln!(self.writer, "{}", result).err();
+        }
+    }
+}
+
+// Discards every result, for benchmarks and for searches run only for their side effects
+#[derive(Debug, Default)]
+pub struct NullSink;
+
+impl ResultSink for NullSink {
+    fn emit(&mut self, _result: &VanitySearchResult) {}
+}
+
+// Search random keys for `max_addresses` Fuel addresses accepted by `matcher`, handing each
+// match to `sink` as it is found. Takes a `&mut dyn ResultSink` as well as a concrete sink.
+pub fn search_into_sink<M: Matcher + ?Sized, S: ResultSink + ?Sized>(matcher: &M, max_addresses: u32, sink: &mut S) {
+    search_with_matcher(matcher, max_addresses, |event| {
+        if let SearchEvent::Found(result) = event {
+            sink.emit(&result);
+        }
+        true
+    });
+}
+
+// Derive the Fuel address for one account index of a BIP-39 mnemonic, using the default
+// Fuel wallet path m/44'/1179993420'/<index>'/0/0
+pub fn derive_indexed_address(phrase: &str, index: u32) -> Result<[u8; 32], VanityError> {
+    let secret_key = hd::derive_key_at_path(phrase, &hd::fuel_account_path(index))?;
+    Ok(fuel_address_bytes(&secret_key))
+}
+
+// Find every account index in `0..count` whose address matches the pattern. Unlike the
+// random search this always terminates; indices are checked in parallel and returned in order.
+pub fn scan_derivation_indices(
+    phrase: &str,
+    count: u32,
+    pattern: &str,
+    position: &str,
+    case_sensitive: bool
+) -> Result<Vec<(u32, String)>, VanityError> {
+    let matches: Vec<Option<(u32, String)>> = (0..count)
+        .into_par_iter()
+        .map(|index| {
+            let address = format!("0x{}", hex::encode(derive_indexed_address(phrase, index)?));
+            let is_match = matches_pattern(&address, pattern, position, case_sensitive);
+            Ok(is_match.then_some((index, address)))
+        })
+        .collect::<Result<_, VanityError>>()?;
+
+    Ok(matches.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_hex_matches_hex_encode() {
+        let mut rng = rand::thread_rng();
+        let mut out = String::from("0x");
+        for len in [0, 1, 2, 31, 32, 33] {
+            let mut bytes = vec![0u8; len];
+            rng.fill_bytes(&mut bytes);
+            out.truncate(2);
+            push_hex(&mut out, &bytes);
+            assert_eq!(out, format!("0x{}", hex::encode(&bytes)));
+        }
+        out.clear();
+        push_hex(&mut out, &[0x00, 0x0f, 0xf0, 0xff, 0xa5]);
+        assert_eq!(out, "000ff0ffa5");
+    }
+
+    #[test]
+    fn compile_mask_turns_question_marks_into_wildcards() {
+        assert_eq!(compile_mask("0a?F"), Some(vec![(0, 0xf), (0xa, 0xf), (0, 0), (0xf, 0xf)]));
+        assert_eq!(compile_mask("??"), Some(vec![(0, 0), (0, 0)]));
+    }
+
+    #[test]
+    fn compile_mask_rejects_other_characters() {
+        assert_eq!(compile_mask("00zz"), None);
+        assert_eq!(compile_mask("00*1"), None);
+        assert_eq!(compile_mask("0x00"), None);
+    }
+
+    #[test]
+    fn mask_wildcards_match_any_digit_at_any_position() {
+        let mut addr = [0u8; 32];
+        addr[0] = 0x12;
+        addr[1] = 0x34;
+        for mask in ["1234", "?234", "1?34", "12?4", "123?", "????", "1??4"] {
+            assert!(MaskMatcher::new(mask).unwrap().matches(&addr), "{mask}");
+        }
+        for mask in ["0234", "?034", "1?04", "12?0", "123?5"] {
+            assert!(!MaskMatcher::new(mask).unwrap().matches(&addr), "{mask}");
+        }
+    }
+
+    #[test]
+    fn mask_matcher_rejects_bad_masks() {
+        assert_eq!(MaskMatcher::new("00zz"), None);
+        assert_eq!(MaskMatcher::new(&"?".repeat(65)), None);
+        assert!(MaskMatcher::new(&"?".repeat(64)).is_some());
+    }
+
+    #[test]
+    fn suffix_matcher_agrees_with_ends_with() {
+        let mut rng = rand::thread_rng();
+        for round in 0..2000 {
+            let mut addr = [0u8; 32];
+            rng.fill_bytes(&mut addr);
+            let address = hex::encode(addr);
+            let len = 1 + round % 9;
+            // Half the patterns are the address's own tail, so both outcomes get checked
+            let pattern = if round % 2 == 0 {
+                address[64 - len..].to_uppercase()
+            } else {
+                let mut digits = [0u8; 8];
+                rng.fill_bytes(&mut digits);
+                hex::encode(digits)[..len].to_string()
+            };
+            let matcher = SuffixMatcher::new(&pattern).unwrap();
+            assert_eq!(matcher.matches(&addr), address.ends_with(&pattern.to_lowercase()), "{pattern} vs {address}");
+        }
+    }
+
+    #[test]
+    fn suffix_matcher_handles_both_parities_and_bad_input() {
+        let mut addr = [0u8; 32];
+        addr[30] = 0x12;
+        addr[31] = 0x34;
+        for (pattern, expected) in [("4", true), ("34", true), ("234", true), ("1234", true), ("01234", true), ("0x234", true),
+            ("5", false), ("24", false), ("334", false), ("11234", false)] {
+            assert_eq!(SuffixMatcher::new(pattern).unwrap().matches(&addr), expected, "{pattern}");
+        }
+        assert!(SuffixMatcher::new("").unwrap().matches(&addr));
+        assert!(SuffixMatcher::new(&hex::encode(addr)).unwrap().matches(&addr));
+        assert_eq!(SuffixMatcher::new("12g4"), None);
+        assert_eq!(SuffixMatcher::new(&"0".repeat(65)), None);
+    }
+
+    struct EvenByteSum;
+
+    impl Matcher for EvenByteSum {
+        fn matches(&self, addr: &[u8; 32]) -> bool {
+            addr.iter().map(|&b| b as u32).sum::<u32>() % 2 == 0
+        }
+    }
+
+    fn byte_sum(address: &str) -> u32 {
+        hex::decode(address.trim_start_matches("0x")).unwrap().iter().map(|&b| b as u32).sum()
+    }
+
+    #[test]
+    fn custom_matcher_drives_the_search() {
+        let matcher: Box<dyn Matcher> = Box::new(EvenByteSum);
+        let mut found = Vec::new();
+        search_with_matcher(matcher.as_ref(), 3, |event| {
+            if let SearchEvent::Found(result) = event {
+                found.push(result);
+            }
+            true
+        });
+        assert_eq!(found.len(), 3);
+        for result in &found {
+            assert_eq!(byte_sum(&result.address) % 2, 0);
+            assert!(verify_key_address_pair(&result.private_key, &result.address));
+        }
+    }
+
+    #[test]
+    fn closure_works_as_a_matcher() {
+        let odd_sum = |addr: &[u8; 32]| addr.iter().map(|&b| b as u32).sum::<u32>() % 2 == 1;
+        let mut found = Vec::new();
+        search_into_sink(&odd_sum, 2, &mut |result: &VanitySearchResult| found.push(result.clone()));
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|result| byte_sum(&result.address) % 2 == 1));
+    }
+
+    #[test]
+    fn builtin_matchers_agree_on_the_same_address() {
+        let mut addr = [0u8; 32];
+        addr[0] = 0xca;
+        addr[1] = 0xfe;
+        addr[31] = 0xbe;
+        addr[15] = 0x42;
+        assert!(HexMatcher::new("CAFE", "prefix").unwrap().matches(&addr));
+        assert!(HexMatcher::new("0xbe", "suffix").unwrap().matches(&addr));
+        assert!(HexMatcher::new("42", "contains").unwrap().matches(&addr));
+        assert!(!HexMatcher::new("beef", "anywhere").unwrap().matches(&addr));
+        assert_eq!(HexMatcher::new("cafe", "middle"), None);
+        assert!(MaskMatcher::new("c?f").unwrap().matches(&addr));
+        assert!(SuffixMatcher::new("0be").unwrap().matches(&addr));
+    }
+
+    #[test]
+    fn callback_sees_every_attempt_and_match() {
+        let (mut attempted, mut last_attempt, mut found) = (0u64, 0u64, 0);
+        search_vanity_with_callback("0x0", "prefix", false, 3, |event| {
+            match event {
+                SearchEvent::Attempted(n) => {
+                    attempted += 1;
+                    assert_eq!(n, last_attempt + 1);
+                    last_attempt = n;
+                }
+                SearchEvent::Found(result) => {
+                    found += 1;
+                    assert!(result.address.starts_with("0x0"));
+                }
+            }
+            true
+        });
+        assert_eq!(found, 3);
+        assert_eq!(attempted, last_attempt);
+    }
+
+    #[test]
+    fn callback_returning_false_stops_the_search() {
+        let mut events = 0;
+        search_vanity_with_callback("0xffffff", "prefix", false, 1, |_| {
+            events += 1;
+            events < 10
+        });
+        assert_eq!(events, 10);
+    }
+
+    #[test]
+    fn ec_deriver_agrees_with_fuel_crypto() {
+        let deriver = EcDeriver::new();
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let mut key = [0u8; 32];
+            rng.fill_bytes(&mut key);
+            let secret_key = SecretKey::try_from(&key[..]).unwrap();
+            assert_eq!(deriver.address(&key), Some(fuel_address_bytes(&secret_key)));
+        }
+        assert_eq!(deriver.address(&[0u8; 32]), None);
+        assert_eq!(deriver.address(&[0xff; 32]), None);
+    }
+
+    const TEST_PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn derivation_scan_reports_every_matching_index() {
+        let matches = scan_derivation_indices(TEST_PHRASE, 42, "8", "prefix", false).unwrap();
+        let indices: Vec<u32> = matches.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, [0, 36, 41]);
+        for (index, address) in &matches {
+            assert_eq!(*address, format!("0x{}", hex::encode(derive_indexed_address(TEST_PHRASE, *index).unwrap())));
+        }
+        assert!(scan_derivation_indices(TEST_PHRASE, 42, "5", "prefix", false).unwrap().is_empty());
+        assert!(scan_derivation_indices("not a mnemonic", 4, "8", "prefix", false).is_err());
+    }
+
+    #[test]
+    fn result_displays_as_a_tab_separated_line() {
+        let result = VanitySearchResult { private_key: "0x01".to_string(), address: "0xcafe".to_string() };
+        assert_eq!(result.to_string(), "0xcafe\t0x01");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn results_and_events_round_trip_through_json() {
+        let result = VanitySearchResult { private_key: "0x01".to_string(), address: "0xcafe".to_string() };
+        let json = serde_json::to_string(&result).unwrap();
+        let back: VanitySearchResult = serde_json::from_str(&json).unwrap();
+        assert_eq!((back.address, back.private_key), (result.address.clone(), result.private_key.clone()));
+
+        let event: SearchEvent = serde_json::from_str(&serde_json::to_string(&SearchEvent::Found(result)).unwrap()).unwrap();
+        assert!(matches!(event, SearchEvent::Found(found) if found.address == "0xcafe"));
+    }
+
+    #[test]
+    fn is_valid_address_checks_each_format() {
+        let address = [0x5au8; 32];
+        let hex = format!("0x{}", hex::encode(address));
+        let checksum = to_checksum_hex(&address);
+        let bech32 = to_bech32(&address);
+
+        assert!(is_valid_address(&hex, AddressFormat::Hex));
+        assert!(is_valid_address(&hex.to_uppercase().replacen("0X", "0x", 1), AddressFormat::Hex));
+        for malformed in [&hex[2..], &hex[..65], &format!("{}0", hex), &hex.replacen('a', "g", 1), ""] {
+            assert!(!is_valid_address(malformed, AddressFormat::Hex), "{:?}", malformed);
+        }
+
+        assert!(is_valid_address(&checksum, AddressFormat::Checksum));
+        // Any other casing of the same digits breaks the checksum
+        assert_ne!(checksum, hex);
+        assert!(!is_valid_address(&hex, AddressFormat::Checksum));
+        assert!(!is_valid_address(&checksum.to_uppercase().replacen("0X", "0x", 1), AddressFormat::Checksum));
+
+        assert!(is_valid_address(&bech32, AddressFormat::Bech32));
+        let other_hrp = bech32::encode("eth", address.to_base32(), Variant::Bech32m).unwrap();
+        let plain_bech32 = bech32::encode(FUEL_BECH32_HRP, address.to_base32(), Variant::Bech32).unwrap();
+        let short = bech32::encode(FUEL_BECH32_HRP, (&address[..31]).to_base32(), Variant::Bech32m).unwrap();
+        let typo = format!("{}{}", &bech32[..bech32.len() - 1], if bech32.ends_with('q') { 'p' } else { 'q' });
+        for malformed in [other_hrp, plain_bech32, short, typo, hex.clone()] {
+            assert!(!is_valid_address(&malformed, AddressFormat::Bech32), "{:?}", malformed);
+        }
+        assert!(!is_valid_address(&bech32, AddressFormat::Hex));
+    }
+
+    #[test]
+    fn fuel_addresses_hash_the_untagged_public_key() {
+        assert!(check_derivation().is_ok());
+
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        let coordinates = EcDeriver::new().public_key(&key).unwrap();
+        assert_eq!(hex::encode(Sha256::digest(coordinates)), KEY_ONE_ADDRESS);
+
+        // The tagged and compressed encodings of the same key give other, wrong addresses
+        let public_key = secp256k1::PublicKey::from_secret_key(&Secp256k1::new(), &secp256k1::SecretKey::from_slice(&key).unwrap());
+        assert_eq!(public_key.serialize_uncompressed()[1..], coordinates);
+        assert_ne!(hex::encode(Sha256::digest(public_key.serialize_uncompressed())), KEY_ONE_ADDRESS);
+        assert_ne!(hex::encode(Sha256::digest(public_key.serialize())), KEY_ONE_ADDRESS);
+    }
+
+    #[test]
+    fn parse_private_key_names_the_bad_input() {
+        let rejected = |input: &str| match parse_private_key(input) {
+            Err(VanityError::InvalidKey { input: reported }) => assert_eq!(reported, input),
+            other => panic!("{:?} gave {:?}", input, other.map(|key| hex::encode(*key))),
+        };
+        // Over-length keys are rejected rather than cut down to 64 digits
+        rejected(&format!("0x{}", "1".repeat(65)));
+        rejected(&"f".repeat(66));
+        for malformed in ["", "0x", "0xzz", "12 34", "0x-1", "not base64=", "AAAA="] {
+            rejected(malformed);
+        }
+        // The zero key and the group order are the right length but not valid secp256k1 keys
+        rejected(&"0".repeat(64));
+        rejected("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141");
+
+        // Short keys are zero-padded on the left
+        assert_eq!(*parse_private_key("0x1").unwrap(), *parse_private_key(&format!("{:0>64}", "1")).unwrap());
+        let max = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140";
+        assert_eq!(hex::encode(*parse_private_key(&format!("0x{}", max)).unwrap()), max);
+        assert_eq!(parse_private_key("0xzz").unwrap_err().to_string(), "invalid private key '0xzz'");
+    }
+
+    // Run `state` until it has found `stop_after` more matches, returning the attempt counts reported
+    fn run_until(state: &mut SearchState, stop_after: usize) -> Vec<u64> {
+        let (mut reported, mut found) = (Vec::new(), 0);
+        search_vanity_address_resumable(state, |event| {
+            match event {
+                SearchEvent::Attempted(attempts) => reported.push(attempts),
+                SearchEvent::Found(_) => found += 1,
+            }
+            found < stop_after
+        });
+        reported
+    }
+
+    #[test]
+    fn resumed_searches_accumulate_their_totals() {
+        let mut state = SearchState::new("0", "prefix", false, 4);
+        run_until(&mut state, 2);
+        assert_eq!(state.results.len(), 2);
+        assert!(!state.is_complete());
+        let addresses = |state: &SearchState| state.results.iter().map(|result| result.address.clone()).collect::<Vec<_>>();
+        let (attempts, elapsed, first) = (state.attempts, state.elapsed, addresses(&state));
+        assert!(attempts >= 2);
+
+        // Saved and restored by the embedder between runs
+        let mut restored = state.clone();
+        let reported = run_until(&mut restored, 2);
+        assert!(restored.is_complete());
+        assert_eq!(addresses(&restored)[..2], first);
+        assert!(restored.attempts >= attempts + 2);
+        assert!(restored.elapsed > elapsed);
+        // Progress carries on from the saved count rather than starting again at zero
+        assert!(reported.iter().all(|&reported| reported > attempts));
+        assert_eq!(reported.last(), Some(&restored.attempts));
+
+        // A complete search has nothing left to do
+        let before = restored.attempts;
+        run_until(&mut restored, 1);
+        assert_eq!((restored.attempts, restored.results.len()), (before, 4));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn search_state_round_trips_through_json() {
+        let mut state = SearchState::new("0", "prefix", false, 3);
+        run_until(&mut state, 1);
+        let restored: SearchState = serde_json::from_str(&serde_json::to_string(&state).unwrap()).unwrap();
+        assert_eq!((restored.attempts, restored.elapsed, restored.results.len()), (state.attempts, state.elapsed, 1));
+        assert_eq!(restored.results[0].address, state.results[0].address);
+    }
+
+    #[test]
+    fn fuel_crypto_public_keys_keep_their_layout() {
+        let secret_key = parse_private_key("0x1").unwrap();
+        let public_key = PublicKey::from(&secret_key);
+        assert_eq!(public_key.as_ref().len(), ADDRESS_HASH_INPUT_LEN);
+        // The generator point of secp256k1, x then y
+        assert_eq!(
+            hex::encode(public_key.as_ref()),
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"
+        );
+        assert_eq!(hex::encode(fuel_address_bytes(&secret_key)), KEY_ONE_ADDRESS);
+    }
+
+    // Hands out the same key on every draw
+    struct FixedKey([u8; 32]);
+
+    impl RngCore for FixedKey {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        // Repeats the key, so every request of 32 bytes gets exactly the key
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for (byte, key) in dest.iter_mut().zip(self.0.iter().cycle()) {
+                *byte = *key;
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn try_once_reports_a_hit_or_a_miss() {
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        let mut rng = FixedKey(key);
+
+        let hit = try_once(&mut rng, &HexMatcher::new("09c0b2", "prefix").unwrap()).expect("key 1 starts with 09c0b2");
+        assert_eq!(hit.private_key, format!("0x{}", hex::encode(key)));
+        assert_eq!(hit.address, "0x09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db");
+
+        assert!(try_once(&mut rng, &HexMatcher::new("ffff", "prefix").unwrap()).is_none());
+        assert!(try_once(&mut FixedKey([0u8; 32]), &|_: &[u8; 32]| true).is_none());
+
+        // The word methods read the same bytes, little-endian
+        let mut rng = FixedKey(core::array::from_fn(|i| i as u8));
+        assert_eq!(rng.next_u32(), 0x03020100);
+        assert_eq!(rng.next_u64(), 0x0706050403020100);
+    }
+
+    // Counts the results it is handed and keeps their addresses
+    #[derive(Default)]
+    struct CountingSink {
+        count: usize,
+        addresses: Vec<String>,
+    }
+
+    impl ResultSink for CountingSink {
+        fn emit(&mut self, result: &VanitySearchResult) {
+            self.count += 1;
+            self.addresses.push(result.address.clone());
+        }
+    }
+
+    #[test]
+    fn sinks_receive_exactly_the_found_results() {
+        let matcher = HexMatcher::new("0", "prefix").unwrap();
+        let mut sink = CountingSink::default();
+        search_into_sink(&matcher, 5, &mut sink);
+        assert_eq!(sink.count, 5);
+        assert!(sink.addresses.iter().all(|address| address.starts_with("0x0")));
+
+        // Through a trait object and a closure alike
+        let mut found = Vec::new();
+        let mut collect = |result: &VanitySearchResult| found.push(result.clone());
+        let sink: &mut dyn ResultSink = &mut collect;
+        search_into_sink(&matcher, 3, sink);
+        assert_eq!(found.len(), 3);
+        assert!(found.iter().all(|result| verify_key_address_pair(&result.private_key, &result.address)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.txt");
+        let mut file = FileSink::create(&path).unwrap();
+        search_into_sink(&matcher, 4, &mut file);
+        file.finish().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 4);
+        for line in contents.lines() {
+            let (address, key) = line.split_once('\t').unwrap();
+            assert!(address.starts_with("0x0") && verify_key_address_pair(key, address));
+        }
+
+        search_into_sink(&matcher, 2, &mut NullSink);
+    }
+
+    #[test]
+    fn each_address_hash_matches_its_vector_for_key_one() {
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        let public_key = hex::decode(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+        )
+        .unwrap();
+        // Keccak-256 ends in the well-known Ethereum address of key one, 0x7e5f...5bdf
+        let vectors = [
+            (AddressHash::Sha256, "09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db"),
+            (AddressHash::Keccak256, "c0a6c424ac7157ae408398df7e5f4552091a69125d5dfcb7b8c2659029395bdf"),
+            (AddressHash::Blake2b, "30f33b32810241070a419b14299ecdd155c5ab437cc523e8289d22e587109867"),
+        ];
+        for (hash, expected) in vectors {
+            assert_eq!(hex::encode(hash.digest(&public_key)), expected, "{}", hash.name());
+            assert_eq!(hex::encode(EcDeriver::with_hash(hash).address(&key).unwrap()), expected, "{}", hash.name());
+            assert!(verify_key_address_pair_with_hash("0x1", expected, hash));
+        }
+    }
+
+#[test]
+fn the_same_bytes_encode_differently_per_network() {
+    let address = [0x5au8; 32];
+    let mainnet = to_bech32_for_network(&address, Network::Mainnet);
+    let testnet = to_bech32_for_network(&address, Network::Testnet);
+
+    assert_eq!(mainnet, to_bech32(&address));
+    assert!(mainnet.starts_with("fuel1"), "{}", mainnet);
+    assert!(testnet.starts_with("tfuel1"), "{}", testnet);
+    // The data characters agree, only the checksum at the end depends on the HRP
+    assert_eq!(mainnet[5..mainnet.len() - 6], testnet[6..testnet.len() - 6]);
+    assert_ne!(mainnet[mainnet.len() - 6..], testnet[testnet.len() - 6..]);
+
+    assert!(is_valid_address_for_network(&testnet, AddressFormat::Bech32, Network::Testnet));
+    assert!(!is_valid_address_for_network(&testnet, AddressFormat::Bech32, Network::Mainnet));
+    assert!(!is_valid_address_for_network(&mainnet, AddressFormat::Bech32, Network::Testnet));
+    assert!(!is_valid_address(&testnet, AddressFormat::Bech32));
+}
+}
@@ -1,15 +1,30 @@
 // Import required modules - remove unused imports
-use clap::{Parser, Subcommand};
-use fuel_crypto::{SecretKey, PublicKey};
-use indicatif::{ProgressBar, ProgressStyle};
+use base64::prelude::*;
+use clap::{parser::ValueSource, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use fuel_vanity_generator::{check_derivation, compile_mask, matches_pattern, parse_private_key, passphrase_key, push_hex, scan_derivation_indices, surprise_score, to_bech32_for_network, to_checksum_hex, verify_key_address_pair_with_hash, AddressHash, EcDeriver, Matcher, Network, SuffixMatcher, FUEL_BECH32_HRP, FUEL_TESTNET_BECH32_HRP};
+use indicatif::{HumanCount, HumanDuration, ProgressBar, ProgressStyle};
+use num_bigint::BigUint;
 use rand::rngs::OsRng;
-use rand::RngCore;
-use sha2::{Sha256, Digest};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    history::DefaultHistory,
+    Context, Editor, Helper, Highlighter, Hinter, Validator,
+};
 use std::{
-    io::{self, Write},
-    str::FromStr,
-    sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}},
-    time::Instant,
+    cmp::Reverse,
+    collections::{HashSet, VecDeque},
+    ffi::OsString,
+    fmt,
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, atomic::{AtomicBool, AtomicUsize, Ordering}},
+    thread,
+    time::{Duration, Instant},
 };
 
 #[derive(Subcommand, Debug, Clone)]
@@ -28,11 +43,158 @@ enum Command {
     Contains {
         /// The pattern to search for anywhere in addresses
         pattern: String,
+        /// Also accept any N or more characters of the pattern in a row, e.g. "caf" or "feba"
+        /// for cafebabe with N = 3; shorter pieces never count
+        #[arg(long, value_name = "N")]
+        min_match_length: Option<usize>,
+    },
+    /// Look for addresses that start or end with a run of the same hex digit
+    Edges {
+        /// The hex digit that should repeat at the edge of the address
+        digit: char,
+        /// Minimum number of repetitions at either end
+        count: usize,
+    },
+    /// Look for addresses with a run of hex digits counting up or down by one, e.g. 0123456789
+    /// or fedcba; every extra digit makes a match about 16 times rarer
+    Sequence {
+        /// Whether the digits count up (asc) or down (desc)
+        direction: SequenceDirection,
+        /// How many digits the run needs, 2 to 16
+        length: usize,
+        /// Where the run must be: prefix, suffix or contains
+        #[arg(default_value = "prefix")]
+        position: String,
+    },
+    /// Look for addresses whose decimal value contains a string of digits (much slower)
+    DecimalContains {
+        /// The digits to search for in the address read as a 256-bit decimal number
+        pattern: String,
+    },
+    /// Look for addresses with several patterns at fixed nibble offsets, all of which must hold
+    MultiAt {
+        /// Constraints as offset:pattern, e.g. 0:cafe 60:cafe (offsets count hex digits after 0x)
+        #[arg(required = true, value_parser = parse_offset_pattern)]
+        constraints: Vec<(usize, String)>,
+    },
+    /// Look for addresses starting with a mask where `?` matches any hex digit
+    Mask {
+        /// The mask to match after 0x, e.g. c?fe
+        pattern: String,
+    },
+    /// Look for keys whose Fuel and Ethereum addresses both start with a pattern (much slower)
+    DualScheme {
+        /// The pattern both addresses must start with, after 0x
+        pattern: String,
+    },
+    /// Look for addresses whose EIP-55 checksum casing spells a word anywhere, e.g. BEEF (much slower)
+    ChecksumWord {
+        /// The hex word to find, in the casing it must have, e.g. BEEF or dEaD
+        word: String,
+    },
+    /// Look for addresses whose value, read as a 256-bit integer, leaves a remainder when
+    /// divided by a divisor, e.g. `modulo 2 0` for an even last byte
+    Modulo {
+        /// The number to divide the address by
+        divisor: u64,
+        /// The remainder the address must leave, below the divisor
+        remainder: u64,
+    },
+    /// Look for addresses that start with one pattern in hex and another in bech32 (much slower)
+    Dual {
+        /// The pattern the hex address must start with, after 0x
+        hex_pattern: String,
+        /// The pattern the bech32 address must start with, after fuel1 (tfuel1 on testnet)
+        bech32_pattern: String,
+    },
+    /// Search for the longest prefix expected to be found within a time budget
+    Budget {
+        /// Seconds the search should take, based on the measured rate
+        seconds: f64,
+        /// Repeat this hex digit instead of using a random prefix
+        digit: Option<char>,
+    },
+    /// Look for addresses starting with a date as YYYYMMDD digits, today's by default
+    Date {
+        /// The date to use, as YYYYMMDD or YYYY-MM-DD
+        date: Option<String>,
+    },
+    /// Match a prefix, then keep searching for addresses that share one more leading digit
+    /// with the last match, reporting each improvement until stopped with Ctrl+C
+    Climb {
+        /// The prefix to start from, after 0x
+        pattern: String,
+    },
+    /// Check random keys for a time or attempt budget and report the lexicographically
+    /// smallest address seen, the one with the most leading zeros
+    Minimize {
+        /// Stop after this many seconds
+        #[arg(long, required_unless_present = "attempts", conflicts_with = "attempts")]
+        seconds: Option<f64>,
+        /// Stop after checking this many keys
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        attempts: Option<u64>,
+    },
+    /// Check random keys for a time or attempt budget and report the most unusual address
+    /// seen, scored by its leading zeros, longest repeated digit and longest digit sequence
+    Surprise {
+        /// Stop after this many seconds
+        #[arg(long, required_unless_present = "attempts", conflicts_with = "attempts")]
+        seconds: Option<f64>,
+        /// Stop after checking this many keys
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        attempts: Option<u64>,
+    },
+    /// Check exactly N random keys against a pattern and report how many matched, to compare
+    /// the observed match rate with the expected one
+    Probe {
+        /// The pattern to check the keys against
+        pattern: String,
+        /// Number of keys to check, split across the threads
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        attempts: u64,
+        /// Where the pattern must appear: prefix, suffix or contains
+        #[arg(long, default_value = "prefix")]
+        position: String,
+    },
+    /// Print random keypairs without matching any pattern
+    Sample {
+        /// How many keypairs to generate
+        count: usize,
+    },
+    /// Find the first counter whose key, derived from a passphrase (read from stdin) and the
+    /// counter with scrypt, has an address with a prefix. Brain wallets are risky and slow.
+    FromPassphrase {
+        /// The prefix to look for, after 0x
+        pattern: String,
+        /// Rebuild the key of this counter instead of searching
+        #[arg(long)]
+        counter: Option<u64>,
+    },
+    /// Check a file of private_key,address lines, e.g. an earlier batch of results, and list
+    /// the lines whose key doesn't derive its address
+    Verify {
+        /// The file to check, one private_key,address pair per line
+        #[arg(long, value_name = "FILE")]
+        input: PathBuf,
+    },
+    /// List every account index of a mnemonic (read from stdin) whose address matches a pattern
+    DeriveScan {
+        /// The pattern to look for in the derived addresses
+        pattern: String,
+        /// Number of account indices to check, starting at 0
+        count: u32,
+        /// Where the pattern must appear: prefix, suffix or contains
+        #[arg(long, default_value = "prefix")]
+        position: String,
     },
     /// Exit the program
     Exit,
     /// Show information about commands
     Info,
+    /// Show version, CPU count, address format and the measured single-core rate
+    #[command(alias = "about")]
+    Stats,
     /// Run in interactive mode
     Interactive,
 }
@@ -52,579 +214,6183 @@ struct Args {
     #[command(subcommand)]
     command: Option<Command>,
 
-    /// Number of threads to use (default: all available cores)
+    /// Number of threads to use; 0 means all available cores (default: all available cores)
     #[arg(short, long, default_value_t = num_cpus::get())]
     threads: usize,
 
+    /// Benchmark 1, half and all cores at startup and search with the fastest thread count
+    #[arg(long, default_value_t = false, conflicts_with = "threads")]
+    auto_threads: bool,
+
     /// Case sensitive pattern matching
     #[arg(short, long, default_value_t = false)]
     case_sensitive: bool,
+
+    /// Number of matching addresses to find before a search stops
+    #[arg(long, value_name = "N", env = "FUEL_VANITY_COUNT", default_value_t = RESULT_LIMIT)]
+    count: usize,
+
+    /// When finding --count matches of a pattern is expected to take more than a day, search
+    /// for the number of matches that fits in a day instead of only warning about it
+    #[arg(long, default_value_t = false)]
+    auto_cap: bool,
+
+    /// Append a line per match (timestamp, pattern, position, address, attempts) to this file.
+    /// Private keys are never written to the audit log.
+    #[arg(long, value_name = "PATH")]
+    audit_log: Option<PathBuf>,
+
+    /// Hide the live progress line while searching. Banners and results are still shown.
+    #[arg(long, default_value_t = false)]
+    no_progress: bool,
+
+    /// How often the progress line is redrawn, in milliseconds
+    #[arg(long, value_name = "N", default_value_t = 100, conflicts_with = "no_progress")]
+    refresh_ms: u64,
+
+    /// When stderr is not a terminal, log cumulative attempts, elapsed time and rate every
+    /// N attempts in place of the live progress line
+    #[arg(long, value_name = "N", default_value_t = 10_000_000, conflicts_with = "no_progress")]
+    milestone: u64,
+
+    /// Share a keyspace counter file with other processes. Each worker claims batches of
+    /// counters from it, so coordinated processes never check the same key. The file keeps
+    /// the seed and the next free counter, so a search can be resumed later.
+    #[arg(long, value_name = "FILE")]
+    coordinator: Option<PathBuf>,
+
+    /// Check the keys of the coordinator file in counter order with a single worker, so the
+    /// same file always yields the same keys in the same order. On exit or Ctrl+C the exact
+    /// position is saved, and the next run resumes right after the last key handed out.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["threads", "auto_threads"])]
+    deterministic: bool,
+
+    /// State file of a resumable deterministic search, short for --coordinator FILE
+    /// --deterministic: the counter reached is saved on exit or Ctrl+C, and the next run
+    /// with the same file continues from it
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["coordinator", "threads", "auto_threads"])]
+    state: Option<PathBuf>,
+
+    /// With --deterministic, continue right after this key of the coordinator file's keyspace
+    /// instead of at the saved position. The key must already have been handed out by the
+    /// file, and finding its counter means generating every key before it.
+    #[arg(long, value_name = "HEX", value_parser = parse_keyspace_key, conflicts_with = "resume_from_counter")]
+    resume_from_key: Option<[u8; 32]>,
+
+    /// With --deterministic, continue at this counter of the coordinator file's keyspace
+    /// instead of at the saved position, e.g. at a shard boundary in a copy of the file
+    #[arg(long, value_name = "N")]
+    resume_from_counter: Option<u64>,
+
+    /// Letter case of the hex digits shown for addresses and keys. Case-sensitive searches
+    /// keep their mixed-case addresses.
+    #[arg(long, value_enum, default_value_t = HexCase::Lower)]
+    hex_case: HexCase,
+
+    /// Encoding of the private keys shown in the results: 0x-prefixed hex, or standard
+    /// base64 of the 32 key bytes
+    #[arg(long, value_enum, default_value_t = KeyFormat::Hex)]
+    key_format: KeyFormat,
+
+    /// Leave the 0x off the addresses and keys in the results, for tools that reject it.
+    /// Patterns are matched the same way.
+    #[arg(long = "no-0x", default_value_t = false)]
+    no_0x: bool,
+
+    /// Don't warn that private keys stay in the scrollback of tmux, screen or SSH sessions
+    #[arg(long, default_value_t = false)]
+    i_understand: bool,
+
+    /// Only draw private keys of one entropy class: `weight=N` or `weight=MIN-MAX` for the
+    /// number of set bits, `zero-bytes=N` for at least N leading zero bytes. Other keys are
+    /// drawn and thrown away, which slows the search down accordingly.
+    #[arg(long, value_name = "SPEC", value_parser = parse_key_constraint, conflicts_with_all = ["coordinator", "state", "test_derivation"])]
+    key_constraint: Option<KeyConstraint>,
+
+    /// Print an example address that the search would accept, then stop without searching
+    #[arg(long, default_value_t = false)]
+    format_preview: bool,
+
+    /// Check the command and every option (pattern, flag combinations, output files), then
+    /// exit with 0 if they are valid or 3 if not, without searching
+    #[arg(long, default_value_t = false, conflicts_with = "format_preview")]
+    dry_validate: bool,
+
+    /// How search results are written to stdout. `binary` writes a 64-byte record per
+    /// match (32-byte address, then 32-byte private key) and no text, for piping. `json`
+    /// writes one object with the run parameters under `meta` and the matches under `results`.
+    /// `line` writes ADDRESS:PRIVATEKEY per match with no decoration. `csv` writes a header
+    /// row, then one row per match as soon as it is found.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output_format: OutputFormat,
+
+    /// Also show the 64-byte public key of every result (not available with binary output)
+    #[arg(long, default_value_t = false)]
+    output_public_key: bool,
+
+    /// After a search, print the min, median, p90 and max time between matches to stderr
+    #[arg(long, default_value_t = false)]
+    latency_stats: bool,
+
+    /// After an interactive search with several matches, list only their addresses and ask
+    /// which one to show, or to save to a file, instead of printing every key
+    #[arg(long, default_value_t = false)]
+    pick: bool,
+
+    /// Re-derive the address of every match from its private key with the library's own
+    /// check before it is recorded, and stop at once with exit code 5 if they disagree
+    #[arg(long, default_value_t = false)]
+    verify_on_find: bool,
+
+    /// After a search, write the attempts taken by each match (cumulative and since the
+    /// previous match) to this file, one row per match, for studying their distribution
+    #[arg(long, value_name = "PATH")]
+    count_distribution: Option<PathBuf>,
+
+    /// Print the first few candidates of a search to stderr with the reason each one was
+    /// rejected, to debug a pattern that never matches
+    #[arg(long, default_value_t = false, visible_alias = "match-report")]
+    debug_match: bool,
+
+    /// Show result addresses in groups of N hex digits, e.g. 0x cafe 0000 dead beef. Only
+    /// changes the text display; matching and machine-readable output are unaffected.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    group: Option<u64>,
+
+    /// What goes between the groups of --group
+    #[arg(long, value_name = "SEP", default_value = " ", requires = "group")]
+    group_separator: String,
+
+    /// Separator between the address and the private key in `--output-format line` [default: :]
+    #[arg(long)]
+    delimiter: Option<String>,
+
+    /// After a search, ask the Fuel node at this URL whether any found address already holds
+    /// a balance. Requires a build with the `network` feature.
+    #[arg(long, value_name = "RPC_URL")]
+    check_balance: Option<String>,
+
+    /// How many times a failed balance RPC call is retried, waiting twice as long each time
+    #[arg(long, value_name = "N", default_value_t = 3, requires = "check_balance")]
+    balance_retries: u32,
+
+    /// POST a JSON object (address, pattern, position, attempts, timestamp) to this URL for
+    /// every match. Requires a build with the `network` feature.
+    #[arg(long, value_name = "URL")]
+    webhook: Option<String>,
+
+    /// Also send the private key of every match to the --webhook URL
+    #[arg(long, default_value_t = false, requires = "webhook")]
+    webhook_include_key: bool,
+
+    /// Pause the search workers while the CPU is hotter than this many degrees Celsius, and
+    /// resume once it has cooled 5 degrees below. Requires a build with the `thermal` feature.
+    #[arg(long, value_name = "CELSIUS")]
+    thermal_guard: Option<f64>,
+
+    /// Color theme for the output (mono disables colors)
+    #[arg(long, value_enum, default_value_t = ThemeName::Green)]
+    theme: ThemeName,
+
+    /// Hash of the public key that gives the address. Fuel addresses are sha256; the others
+    /// are for tooling that hashes the same key differently, and their addresses are NOT
+    /// Fuel addresses. blake2b is BLAKE2b with a 32-byte output.
+    #[arg(long, value_enum, default_value_t = HashName::Sha256)]
+    hash: HashName,
+
+    /// Network whose human-readable part bech32 addresses carry: fuel1... on mainnet,
+    /// tfuel1... on testnet. `dual` matches the bech32 address of this network.
+    #[arg(long, value_enum, default_value_t = NetworkName::Mainnet)]
+    network: NetworkName,
+
+    /// Check only this private key instead of searching, so tests get a deterministic result
+    #[arg(long, value_name = "HEX", hide = true)]
+    test_derivation: Option<String>,
+
+    /// With --test-derivation or verify, print each key's public key bytes and the raw hash
+    /// they give to stderr, to tell a hashing mismatch from a formatting one
+    #[arg(long, default_value_t = false)]
+    show_hash: bool,
 }
 
-#[derive(Debug, Copy, Clone)]
-enum Position {
-    /// Look for pattern at the beginning of the address
-    Prefix,
-    /// Look for pattern at the end of the address
-    Suffix,
-    /// Look for pattern anywhere in the address
-    Anywhere,
+impl Args {
+    // The coordinator file, given with --coordinator or --state
+    fn keyspace_file(&self) -> Option<&PathBuf> {
+        self.coordinator.as_ref().or(self.state.as_ref())
+    }
+
+    // --state always runs the deterministic mode
+    fn is_deterministic(&self) -> bool {
+        self.deterministic || self.state.is_some()
+    }
 }
 
-fn matches_pattern(address: &str, pattern: &str, position: &str, case_sensitive: bool) -> bool {
-    // Remove the "0x" prefix if it exists
-    let address = if address.starts_with("0x") {
-        &address[2..]
-    } else {
-        address
-    };
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum HexCase {
+    Lower,
+    Upper,
+}
 
-    if !case_sensitive {
-        let address = address.to_lowercase();
-        let pattern = pattern.to_lowercase();
-        
-        match position {
-            "prefix" => address.starts_with(&pattern),
-            "suffix" => address.ends_with(&pattern),
-            "anywhere" => address.contains(&pattern),
-            _ => false,
+impl HexCase {
+    // Recase the hex digits of a value, leaving any 0x prefix as it is
+    fn apply(self, value: &str) -> String {
+        let (prefix, digits) = match value.strip_prefix("0x") {
+            Some(digits) => ("0x", digits),
+            None => ("", value),
+        };
+        match self {
+            HexCase::Lower => format!("{}{}", prefix, digits.to_ascii_lowercase()),
+            HexCase::Upper => format!("{}{}", prefix, digits.to_ascii_uppercase()),
         }
-    } else {
-        match position {
-            "prefix" => address.starts_with(pattern),
-            "suffix" => address.ends_with(pattern),
-            "anywhere" => address.contains(pattern),
-            _ => false,
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyFormat {
+    Hex,
+    Base64,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Binary,
+    Json,
+    Line,
+    Csv,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum SequenceDirection {
+    Asc,
+    Desc,
+}
+
+impl SequenceDirection {
+    fn label(self) -> &'static str {
+        match self {
+            SequenceDirection::Asc => "ascending",
+            SequenceDirection::Desc => "descending",
         }
     }
 }
 
-// Generate a random private key
-fn generate_random_private_key() -> String {
-    let mut key_data = [0u8; 32];
-    OsRng.fill_bytes(&mut key_data);
-    hex::encode(key_data)
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum HashName {
+    Sha256,
+    Keccak256,
+    Blake2b,
 }
 
-// Function to convert an address to mixed case for better visual representation
-// when case-sensitive matching is enabled
-fn convert_to_mixed_case(address: &str) -> String {
-    if !address.starts_with("0x") {
-        return address.to_string();
+impl HashName {
+    fn address_hash(self) -> AddressHash {
+        match self {
+            HashName::Sha256 => AddressHash::Sha256,
+            HashName::Keccak256 => AddressHash::Keccak256,
+            HashName::Blake2b => AddressHash::Blake2b,
+        }
     }
-    
-    let address_part = &address[2..]; // Skip the 0x prefix
-    let mut result = String::from("0x");
-    
-    for (i, c) in address_part.chars().enumerate() {
-        if c >= 'a' && c <= 'f' {
-            // Convert some of the hex letters to uppercase based on position
-            if i % 3 == 0 {
-                result.push((c as u8 - b'a' + b'A') as char);
-            } else {
-                result.push(c);
-            }
-        } else {
-            result.push(c);
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq)]
+enum NetworkName {
+    Mainnet,
+    Testnet,
+}
+
+impl NetworkName {
+    fn network(self) -> Network {
+        match self {
+            NetworkName::Mainnet => Network::Mainnet,
+            NetworkName::Testnet => Network::Testnet,
         }
     }
-    
-    result
 }
 
-// Generate an address from a private key with case-sensitive option
-fn get_address_from_private_key_case_sensitive(private_key: &str, preserve_case: bool) -> std::result::Result<String, Box<dyn std::error::Error>> {
-    // Ensure the private key is padded to 64 characters
-    let padded_key = match private_key.len() {
-        64 => private_key.to_string(),
-        _ => format!("{:0>64}", private_key)
-    };
-    
-    // Convert to a Fuel SecretKey
-    let secret_key = SecretKey::from_str(&padded_key)?;
-    
-    // Get the public key from the secret key
-    let public_key = PublicKey::from(&secret_key);
-    
-    // In Fuel, the address is derived as the SHA-256 hash of the public key
-    let mut hasher = Sha256::new();
-    hasher.update(public_key.as_ref());
-    let address_bytes = hasher.finalize();
-    
-    // Format with 0x prefix
-    let address_str = if preserve_case {
-        // Use a mixed-case encoding for case-sensitive display
-        format!("0x{}", encode_mixed_case(&address_bytes))
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ThemeName {
+    Green,
+    Blue,
+    Mono,
+    HighContrast,
+}
+
+// ANSI escape strings used by the output UI; every field is empty for the mono theme
+#[derive(Debug, Clone, Copy)]
+struct Theme {
+    /// Frames, headings and the prompt
+    accent: &'static str,
+    /// Field labels and notices
+    label: &'static str,
+    /// Values that should stand out, such as the search rate
+    emphasis: &'static str,
+    address: &'static str,
+    key: &'static str,
+    error: &'static str,
+    reset: &'static str,
+}
+
+impl Theme {
+    fn new(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Green => Theme {
+                accent: "\x1b[1;32m",
+                label: "\x1b[1;33m",
+                emphasis: "\x1b[1;35m",
+                address: "\x1b[0;36m",
+                key: "\x1b[0;35m",
+                error: "\x1b[1;31m",
+                reset: "\x1b[0m",
+            },
+            ThemeName::Blue => Theme {
+                accent: "\x1b[1;34m",
+                label: "\x1b[1;36m",
+                emphasis: "\x1b[1;35m",
+                address: "\x1b[0;36m",
+                key: "\x1b[0;35m",
+                error: "\x1b[1;31m",
+                reset: "\x1b[0m",
+            },
+            ThemeName::Mono => Theme {
+                accent: "",
+                label: "",
+                emphasis: "",
+                address: "",
+                key: "",
+                error: "",
+                reset: "",
+            },
+            ThemeName::HighContrast => Theme {
+                accent: "\x1b[1;97m",
+                label: "\x1b[1;93m",
+                emphasis: "\x1b[1;95m",
+                address: "\x1b[1;96m",
+                key: "\x1b[1;95m",
+                error: "\x1b[1;91m",
+                reset: "\x1b[0m",
+            },
+        }
+    }
+}
+
+// Settings shared by every search started from the command line or the REPL
+struct SearchOptions {
+    case_sensitive: bool,
+    threads: usize,
+    /// Matches a search collects before it stops
+    count: usize,
+    audit_log: Option<Arc<AuditLog>>,
+    coordinator: Option<Arc<Coordinator>>,
+    webhook: Option<Arc<Webhook>>,
+    show_progress: bool,
+    refresh: Duration,
+    /// Attempts between milestone log lines, set when stderr can't show the progress line
+    milestone: Option<u64>,
+    hex_case: HexCase,
+    key_format: KeyFormat,
+    /// False with --no-0x
+    hex_prefix: bool,
+    output_format: OutputFormat,
+    output_public_key: bool,
+    latency_stats: bool,
+    pick: bool,
+    verify_on_find: bool,
+    count_distribution: Option<PathBuf>,
+    debug_match: bool,
+    group: Option<usize>,
+    group_separator: String,
+    delimiter: String,
+    format_preview: bool,
+    dry_validate: bool,
+    key_constraint: Option<KeyConstraint>,
+    #[cfg(feature = "network")]
+    check_balance: Option<String>,
+    #[cfg(feature = "network")]
+    balance_retries: u32,
+    /// --thermal-guard limit, left unset when no temperature can be read
+    #[cfg(feature = "thermal")]
+    thermal_guard: Option<f64>,
+    /// Fixed key from --test-derivation that replaces the random search
+    test_key: Option<[u8; 32]>,
+    show_hash: bool,
+    /// Hash from --hash that turns a public key into an address
+    hash: AddressHash,
+    /// Network from --network whose bech32 form `dual` matches
+    network: Network,
+    theme: Theme,
+    /// Set once the shared terminal warning was shown, or when it was turned off
+    shared_terminal_warned: AtomicBool,
+}
+
+// Name the file an error is about, which io::Error alone doesn't
+fn with_path(e: io::Error, path: &Path) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
+
+// Whether a file could be opened for writing, without creating or changing it: an existing
+// file must open for appending, and a new one needs its directory to exist
+fn check_writable(path: &Path) -> io::Result<()> {
+    if path.exists() {
+        return OpenOptions::new().append(true).open(path).map(|_| ());
+    }
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    if dir.is_dir() {
+        Ok(())
     } else {
-        // Use regular lowercase hex
-        format!("0x{}", hex::encode(address_bytes))
-    };
-    
-    Ok(address_str)
+        Err(io::Error::new(io::ErrorKind::NotFound, "the directory doesn't exist"))
+    }
 }
 
-// Get address from private key (backward compatibility)
-fn get_address_from_private_key(private_key: &str) -> std::result::Result<String, Box<dyn std::error::Error>> {
-    get_address_from_private_key_case_sensitive(private_key, false)
+// Thread count for a --threads value: 0 asks for every available core
+fn resolve_threads(requested: usize) -> usize {
+    if requested == 0 { num_cpus::get() } else { requested }
 }
 
-// Function to encode bytes with mixed-case for better visual diversity
-fn encode_mixed_case(bytes: &[u8]) -> String {
-    let mut result = String::with_capacity(bytes.len() * 2);
-    for &byte in bytes.iter() {
-        // For every other byte, use uppercase for one of the two hex characters
-        let high = byte >> 4;
-        let low = byte & 0xF;
-        
-        // Convert to hex chars with some randomness in the casing
-        if high < 10 {
-            result.push((b'0' + high) as char);
-        } else {
-            // Use uppercase for some of the alpha chars
-            if byte % 3 == 0 {
-                result.push((b'A' + (high - 10)) as char);
-            } else {
-                result.push((b'a' + (high - 10)) as char);
+impl SearchOptions {
+    fn from_args(args: &Args) -> io::Result<Self> {
+        // A dry run only checks that the files could be used; it creates, locks and changes nothing
+        if args.dry_validate {
+            if let Some(path) = &args.audit_log {
+                check_writable(path).map_err(|e| with_path(e, path))?;
+            }
+            if let Some(path) = args.keyspace_file() {
+                Coordinator::check(path, args.resume_from_key.as_ref()).map_err(|e| with_path(e, path))?;
             }
         }
-        
-        if low < 10 {
-            result.push((b'0' + low) as char);
-        } else {
-            // Use uppercase for some of the alpha chars
-            if byte % 2 == 0 {
-                result.push((b'A' + (low - 10)) as char);
-            } else {
-                result.push((b'a' + (low - 10)) as char);
+        let audit_log = match &args.audit_log {
+            Some(path) if !args.dry_validate => Some(Arc::new(AuditLog::open(path).map_err(|e| with_path(e, path))?)),
+            _ => None,
+        };
+        let coordinator = match args.keyspace_file() {
+            Some(path) if !args.dry_validate => {
+                let coordinator = Coordinator::open(path, args.is_deterministic()).map_err(|e| with_path(e, path))?;
+                if let Some(key) = &args.resume_from_key {
+                    coordinator.resume_after(key).map_err(|e| with_path(e, path))?;
+                }
+                if let Some(counter) = args.resume_from_counter {
+                    coordinator.resume_at(counter).map_err(|e| with_path(e, path))?;
+                }
+                Some(Arc::new(coordinator))
+            }
+            _ => None,
+        };
+
+        let theme = Theme::new(args.theme);
+        if args.check_balance.is_some() && !cfg!(feature = "network") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "--check-balance needs a build with the `network` feature (cargo build --features network)",
+            ));
+        }
+        if args.webhook.is_some() && !cfg!(feature = "network") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "--webhook needs a build with the `network` feature (cargo build --features network)",
+            ));
+        }
+        if args.thermal_guard.is_some() && !cfg!(feature = "thermal") {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "--thermal-guard needs a build with the `thermal` feature (cargo build --features thermal)",
+            ));
+        }
+        #[cfg(feature = "thermal")]
+        let thermal_guard = args.thermal_guard.filter(|_| {
+            let readable = cpu_temperature().is_some();
+            if !readable {
+                eprintln!(
+                    "{}⚠️  --thermal-guard: no CPU temperature can be read on this machine, so searches run unthrottled{}",
+                    theme.label, theme.reset
+                );
             }
+            readable
+        });
+        #[cfg(feature = "network")]
+        let webhook = match &args.webhook {
+            Some(url) => Some(Arc::new(Webhook::start(url, args.webhook_include_key, &theme)?)),
+            None => None,
+        };
+        #[cfg(not(feature = "network"))]
+        let webhook = None;
+        if let (Some(url), true) = (&args.webhook, args.webhook_include_key) {
+            eprintln!(
+                "{}⚠️  --webhook-include-key sends every private key to {}: whoever runs that endpoint, or can read its logs, can take the funds{}",
+                theme.error, url, theme.reset
+            );
         }
+        if args.hash != HashName::Sha256 {
+            eprintln!(
+                "{}⚠️  --hash {}: the addresses found are not Fuel addresses, which are the SHA-256 of the public key{}",
+                theme.error, args.hash.address_hash().name(), theme.reset
+            );
+        }
+        // A redrawn progress line is only readable on a terminal; logs get milestones instead
+        let progress_visible = atty::is(atty::Stream::Stderr);
+        let test_key = match &args.test_derivation {
+            Some(key) => match parse_private_key(key) {
+                Ok(secret_key) => Some(*secret_key),
+                Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidInput, e.to_string())),
+            },
+            None => None,
+        };
+        // A dry run only checks the options, so it skips the calibration
+        let threads = if args.is_deterministic() {
+            1
+        } else if args.auto_threads && !args.dry_validate {
+            let threads = calibrate_thread_count(Duration::from_millis(CALIBRATION_MS));
+            eprintln!("{}⚙️  Auto-tuned thread count: {}{}", theme.accent, threads, theme.reset);
+            threads
+        } else {
+            resolve_threads(args.threads)
+        };
+
+        Ok(SearchOptions {
+            case_sensitive: args.case_sensitive,
+            threads,
+            count: args.count,
+            audit_log,
+            coordinator,
+            webhook,
+            show_progress: !args.no_progress && progress_visible,
+            milestone: (!args.no_progress && !progress_visible).then_some(args.milestone.max(1)),
+            refresh: Duration::from_millis(args.refresh_ms.max(1)),
+            hex_case: args.hex_case,
+            key_format: args.key_format,
+            hex_prefix: !args.no_0x,
+            output_format: args.output_format,
+            output_public_key: args.output_public_key,
+            latency_stats: args.latency_stats,
+            pick: args.pick,
+            verify_on_find: args.verify_on_find,
+            count_distribution: args.count_distribution.clone(),
+            debug_match: args.debug_match,
+            group: args.group.map(|n| n as usize),
+            group_separator: args.group_separator.clone(),
+            delimiter: args.delimiter.clone().unwrap_or_else(|| ":".to_string()),
+            format_preview: args.format_preview,
+            dry_validate: args.dry_validate,
+            key_constraint: args.key_constraint,
+            #[cfg(feature = "network")]
+            check_balance: args.check_balance.clone(),
+            #[cfg(feature = "network")]
+            balance_retries: args.balance_retries,
+            #[cfg(feature = "thermal")]
+            thermal_guard,
+            test_key,
+            show_hash: args.show_hash,
+            hash: args.hash.address_hash(),
+            network: args.network.network(),
+            shared_terminal_warned: AtomicBool::new(args.i_understand),
+            theme,
+        })
     }
-    result
 }
 
-// Enhanced search function with beautiful UI
-async fn search_vanity_address(
-    pattern: String, 
-    position: String, 
-    case_sensitive: bool
-) -> Vec<(String, String)> {
-    // Create a progress bar with beautiful formatting
-    let progress = Arc::new(Mutex::new(ProgressBar::new(100)));
-    {
-        let progress_bar = progress.lock().unwrap();
-        progress_bar.set_style(ProgressStyle::default_bar()
-            .template("\r\x1b[2K\x1b[1;32mSearched:\x1b[0m {pos} | \x1b[1;32mFound:\x1b[0m {msg} | \x1b[1;35mRate:\x1b[0m {per_sec}/s")
-            .unwrap());
+// Append-only provenance record of found addresses. Only public data is written here.
+struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(AuditLog { file: Mutex::new(file) })
     }
-    
-    // Create a results vector to store (address, private_key) pairs
-    let results: Arc<Mutex<Vec<(String, String)>>> = Arc::new(Mutex::new(Vec::new()));
-    
-    // Set up thread count
-    let _num_threads = num_cpus::get();
-    
-    // Display beautiful configuration header with fixed width
-    println!("\n\x1b[1;32m╔════════════════════════════════════════════════════╗");
-    println!("║           VANITY ADDRESS SEARCH                 ║");
-    println!("╚════════════════════════════════════════════════════╝");
-    println!("╔════════════════════════════════════════════════════╗");
-    println!("║ \x1b[1;33mPattern:\x1b[0m {:<40} ║", pattern);
-    println!("║ \x1b[1;33mPosition:\x1b[0m {:<40} ║", position);
-    println!("║ \x1b[1;33mCase Sensitive:\x1b[0m {:<32} ║", case_sensitive);
-    println!("╚════════════════════════════════════════════════════╝");
-    println!("\x1b[1;33m⚠️  Press Ctrl+C to stop the search at any time\x1b[0m\n");
-    
-    let _start = Instant::now();
-    let addresses_checked = Arc::new(AtomicUsize::new(0));
-    let found_count = Arc::new(AtomicUsize::new(0));
-    
-    // Create a vector to hold thread handles
-    let mut handles = vec![];
-    
-    // Spawn worker threads
-    for _ in 0..num_cpus::get() {
-        let pattern = pattern.clone();
-        let position = position.clone();
-        let results = results.clone();
-        let progress = progress.clone();
-        let found_count = found_count.clone();
-        let addresses_checked = addresses_checked.clone();
+
+    // Write one tab-separated line and flush it immediately so a crash never loses an entry
+    fn record(&self, pattern: &str, position: &str, address: &str, attempts: usize) -> io::Result<()> {
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let mut file = self.file.lock().unwrap();
+        writeln!(
+            file,
+            "{}\tpattern={}\tposition={}\taddress={}\tattempts={}",
+            timestamp, pattern, position, address, attempts
+        )?;
+        file.flush()
+    }
+}
+
+// Retries of a failed webhook delivery, with the backoff of the balance check
+#[cfg(feature = "network")]
+const WEBHOOK_RETRIES: u32 = 2;
+
+// How long the end of a search waits for webhook deliveries still in flight
+#[cfg(feature = "network")]
+const WEBHOOK_DRAIN: Duration = Duration::from_secs(10);
+
+// Delivers a JSON object to the --webhook URL for every match. Deliveries run on a thread
+// of their own, so a slow or unreachable endpoint never holds up the search; one that still
+// fails after the retries is reported and dropped.
+#[cfg(feature = "network")]
+struct Webhook {
+    queue: std::sync::mpsc::Sender<serde_json::Value>,
+    pending: Arc<AtomicUsize>,
+    include_key: bool,
+}
+
+#[cfg(feature = "network")]
+impl Webhook {
+    fn start(url: &str, include_key: bool, theme: &Theme) -> io::Result<Self> {
+        let url = reqwest::Url::parse(url)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("--webhook {}: {}", url, e)))?;
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let (queue, deliveries) = std::sync::mpsc::channel::<serde_json::Value>();
+        let pending = Arc::new(AtomicUsize::new(0));
+        let Theme { error, reset, .. } = *theme;
         
-        let handle = tokio::spawn(async move {
-            loop {
-                addresses_checked.fetch_add(1, Ordering::SeqCst);
-                
-                // Generate a random private key
-                let private_key = generate_random_private_key();
-                
-                // Get the address from the private key
-                let address_result = get_address_from_private_key_case_sensitive(&private_key, case_sensitive);
-                
-                // Update progress bar
-                {
-                    let progress_bar = progress.lock().unwrap();
-                    progress_bar.set_position(addresses_checked.load(Ordering::SeqCst) as u64);
-                    progress_bar.set_message(format!("{}", found_count.load(Ordering::SeqCst)));
-                }
-                
-                if let Ok(address) = address_result {
-                    // Check if the address matches the pattern
-                    if matches_pattern(&address, &pattern, &position, case_sensitive) {
-                        // Increment the found count
-                        found_count.fetch_add(1, Ordering::SeqCst);
-                        
-                        // Add the address and private key to the results
-                        results.lock().unwrap().push((
-                            if case_sensitive { convert_to_mixed_case(&address) } else { address.clone() },
-                            format!("0x{}", private_key)
-                        ));
-                        
-                        // Let's find at most 5 addresses
-                        if found_count.load(Ordering::SeqCst) >= 5 {
-                            break;
-                        }
-                    }
+        let delivered = pending.clone();
+        thread::spawn(move || {
+            let client = reqwest::Client::new();
+            for payload in deliveries {
+                let body = payload.to_string();
+                let sent = runtime.block_on(with_backoff(WEBHOOK_RETRIES, || async {
+                    client
+                        .post(url.clone())
+                        .header(reqwest::header::CONTENT_TYPE, "application/json")
+                        .body(body.clone())
+                        .send()
+                        .await?
+                        .error_for_status()
+                }));
+                if let Err(e) = sent {
+                    eprintln!("\n{error}⚠️  Webhook delivery failed for {}: {}{reset}", payload["address"].as_str().unwrap_or_default(), e);
                 }
+                delivered.fetch_sub(1, Ordering::SeqCst);
             }
         });
         
-        handles.push(handle);
+        Ok(Webhook { queue, pending, include_key })
     }
     
-    // Wait for any thread to complete (when enough addresses are found)
-    for handle in handles {
-        if results.lock().unwrap().len() >= 5 { // Fixed limit at 5
-            break;
+    // Queue the delivery of one match
+    fn post(&self, found: &FoundAddress, pattern: &str, position: &str) {
+        let mut payload = serde_json::json!({
+            "address": found.address,
+            "pattern": pattern,
+            "position": position,
+            "attempts": found.attempts,
+            "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        });
+        if self.include_key {
+            payload["private_key"] = found.private_key.clone().into();
+        }
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        if self.queue.send(payload).is_err() {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
         }
-        let _ = handle.await;
-    }
-    
-    // Clear the progress bar before returning
-    {
-        let progress_bar = progress.lock().unwrap();
-        progress_bar.finish_and_clear();
     }
     
-    println!();  // Add a newline for spacing
-    
-    // Return a clone of the locked results before they go out of scope
-    let result_clone = results.lock().unwrap().clone();
-    result_clone
-}
-
-// Helper function to display results
-fn display_results(results: &[(String, String)]) {
-    if !results.is_empty() {
-        println!("\n\x1b[1;32m✅ Found {} matching addresses!\x1b[0m", results.len());
-        
-        println!("\n\x1b[1;32m╔════════════════════════════════════════════════════╗");
-        println!("║              MATCHING ADDRESSES                    ║");
-        println!("╚════════════════════════════════════════════════════╝\x1b[0m");
-        
-        for (i, (address, private_key)) in results.iter().enumerate() {
-            println!("\x1b[1;32m╔════════════════════════════════════════════════════╗\x1b[0m");
-            println!("\x1b[1;32m║\x1b[0m \x1b[1;32m#{:<4}\x1b[0m                                          \x1b[1;32m║\x1b[0m", i + 1);
-            println!("\x1b[1;32m╠════════════════════════════════════════════════════╣\x1b[0m");
-            println!("\x1b[1;32m║\x1b[0m \x1b[1;33m📫 Address:\x1b[0m                                     \x1b[1;32m║\x1b[0m");
-            
-            // Split long addresses to fit in the box
-            let wrapped_address = textwrap::fill(address, 48);
-            for line in wrapped_address.lines() {
-                println!("\x1b[1;32m║\x1b[0m \x1b[0;36m{:<48}\x1b[0m \x1b[1;32m║\x1b[0m", line);
-            }
-            
-            println!("\x1b[1;32m╠════════════════════════════════════════════════════╣\x1b[0m");
-            println!("\x1b[1;32m║\x1b[0m \x1b[1;33m🔑 Private Key:\x1b[0m                                 \x1b[1;32m║\x1b[0m");
-            
-            // Split long private keys to fit in the box
-            let wrapped_key = textwrap::fill(private_key, 48);
-            for line in wrapped_key.lines() {
-                println!("\x1b[1;32m║\x1b[0m \x1b[0;35m{:<48}\x1b[0m \x1b[1;32m║\x1b[0m", line);
-            }
-            
-            println!("\x1b[1;32m╚════════════════════════════════════════════════════╝\x1b[0m");
+    // Give the queued deliveries up to WEBHOOK_DRAIN to finish, so the last matches of a run
+    // still arrive when the process exits right after it
+    fn wait_delivered(&self) {
+        let start = Instant::now();
+        while self.pending.load(Ordering::SeqCst) > 0 && start.elapsed() < WEBHOOK_DRAIN {
+            thread::sleep(Duration::from_millis(20));
         }
-    } else {
-        println!("\n\x1b[1;31m❌ No matching addresses found within the search limit.\x1b[0m");
     }
 }
 
-// Function to find addresses based on pattern
-async fn find_addresses(pattern: String, position: Position, _threads: usize, case_sensitive: bool) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    // Convert Position enum to String for the new function
-    let position_str = match position {
-        Position::Prefix => "prefix".to_string(),
-        Position::Suffix => "suffix".to_string(),
-        Position::Anywhere => "anywhere".to_string(),
-    };
-    
-    // Call the async function and wait for it to complete
-    let results = search_vanity_address(pattern, position_str, case_sensitive).await;
-    
-    // Display the results
-    display_results(&results);
-    
-    Ok(())
+// Without the `network` feature --webhook is rejected, so there is never a webhook to call
+#[cfg(not(feature = "network"))]
+struct Webhook;
+
+#[cfg(not(feature = "network"))]
+impl Webhook {
+    fn post(&self, _found: &FoundAddress, _pattern: &str, _position: &str) {}
+    fn wait_delivered(&self) {}
 }
 
-// Function to validate a pattern for hex characters
-fn is_valid_hex_pattern(pattern: &str) -> bool {
-    pattern.chars().all(|c| c.is_digit(16))
+// Number of counters a worker reserves from the coordinator file at a time
+const COORDINATOR_BATCH: u64 = 4096;
+
+// 32-bit keystream words in one coordinated key
+const KEY_WORDS: u128 = 8;
+
+// Hands out non-overlapping ranges of a deterministic keyspace shared through a file.
+// The file holds one line, "<seed hex> <next free counter>", and is rewritten under an
+// exclusive advisory lock on every claim. Counters claimed by a process that exits before
+// finishing its batch are not handed out again, unless it runs in deterministic mode and
+// gives them back.
+struct Coordinator {
+    path: PathBuf,
+    seed: [u8; 32],
+    // Deterministic mode only: the counters of the current batch not handed out yet
+    unchecked: Option<Mutex<Range<u64>>>,
+    // Counters below the next free one that a resumed run checks again before claiming. They
+    // were handed out before, so they are never given back: moving the shared counter back
+    // over them would hand out batches other processes may have claimed since.
+    replay: Mutex<Range<u64>>,
 }
 
-// Function to check and warn about non-hex characters
-fn warn_if_invalid_hex(pattern: &str) -> bool {
-    if !is_valid_hex_pattern(pattern) {
-        eprintln!("\n WARNING: Your pattern contains non-hexadecimal characters!");
-        eprintln!("   Fuel addresses can only contain characters: 0-9, a-f");
-        eprintln!("   The search may run indefinitely without finding a match.\n");
-        
-        // List the invalid characters
-        let invalid_chars: Vec<char> = pattern.chars().filter(|c| !c.is_digit(16)).collect();
-        eprintln!("   Invalid characters in your pattern: {:?}", invalid_chars);
-        eprintln!("   Consider using only hexadecimal characters for a successful search.\n");
+impl Coordinator {
+    // Open the counter file, creating it with a fresh random seed if it is empty
+    fn open(path: &Path, deterministic: bool) -> io::Result<Self> {
+        let mut file = Self::lock(path)?;
+        let seed = match Self::read_state(&mut file)? {
+            Some((seed, _)) => seed,
+            None => {
+                let mut seed = [0u8; 32];
+                OsRng.fill_bytes(&mut seed);
+                Self::write_state(&mut file, &seed, 0)?;
+                seed
+            }
+        };
+
+        Ok(Coordinator {
+            path: path.to_path_buf(),
+            seed,
+            unchecked: deterministic.then(|| Mutex::new(0..0)),
+            replay: Mutex::new(0..0),
+        })
+    }
+
+    // What `open` checks, for --dry-validate, but without creating, locking or rewriting the
+    // file. A --resume-from-key key must be one the file has handed out.
+    fn check(path: &Path, resume_from_key: Option<&[u8; 32]>) -> io::Result<()> {
+        check_writable(path)?;
+        let state = match File::open(path) {
+            Ok(mut file) => Self::read_state(&mut file)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        if let Some(key) = resume_from_key {
+            let (seed, next) = state.unwrap_or_default();
+            let coordinator = Coordinator { path: path.to_path_buf(), seed, unchecked: None, replay: Mutex::new(0..0) };
+            coordinator.counter_below(key, next)?;
+        }
+        Ok(())
+    }
+
+    // The keyspace seed, needed to rebuild a coordinated key from its counter
+    fn seed_hex(&self) -> String {
+        hex::encode(self.seed)
+    }
+
+    // Atomically reserve the next batch of counters
+    fn claim(&self) -> io::Result<Range<u64>> {
+        let mut file = Self::lock(&self.path)?;
+        let (seed, next) = Self::read_state(&mut file)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "coordinator file is empty"))?;
+        if seed != self.seed {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "coordinator file seed changed"));
+        }
+
+        let end = next.checked_add(COORDINATOR_BATCH)
+            .ok_or_else(|| io::Error::other("coordinator keyspace exhausted"))?;
+        Self::write_state(&mut file, &seed, end)?;
+        Ok(next..end)
+    }
+
+    // Private key for the next counter of `claimed`, claiming a new batch when it runs out.
+    // Every worker keeps its own `claimed` range, and batches are the consecutive ranges
+    // [n, n + COORDINATOR_BATCH) taken from the shared counter under the file lock, so worker
+    // ranges act as strides that never overlap, however far each worker advances or however
+    // many processes join. Together the workers cover every counter below the next free one,
+    // except the unfinished rest of a batch whose worker stopped early.
+    fn next_key(&self, claimed: &mut Range<u64>) -> io::Result<[u8; 32]> {
+        if let Some(counter) = self.replay.lock().unwrap().next() {
+            return Ok(self.key_at(counter));
+        }
+        if claimed.is_empty() {
+            *claimed = self.claim()?;
+        }
+        let counter = claimed.next().expect("claimed range is not empty");
+        if let Some(unchecked) = &self.unchecked {
+            *unchecked.lock().unwrap() = claimed.clone();
+        }
+        Ok(self.key_at(counter))
+    }
+
+    // The key of a counter is the 32-byte block at offset 32 * counter of the ChaCha20
+    // keystream keyed by the seed (stream 0, nonce zero), so any key can be rebuilt from the
+    // seed and its counter alone, without generating the keys before it
+    fn key_at(&self, counter: u64) -> [u8; 32] {
+        let mut keystream = ChaCha20Rng::from_seed(self.seed);
+        keystream.set_word_pos(u128::from(counter) * KEY_WORDS);
+        let mut key = [0u8; 32];
+        keystream.fill_bytes(&mut key);
+        key
+    }
+
+    // The counter of a key handed out before `next`. The keystream can't be run backwards,
+    // so this generates it from the start in one pass until the key comes up.
+    fn counter_below(&self, key: &[u8; 32], next: u64) -> io::Result<u64> {
+        let mut keystream = ChaCha20Rng::from_seed(self.seed);
+        let mut candidate = [0u8; 32];
+        (0..next)
+            .find(|_| {
+                keystream.fill_bytes(&mut candidate);
+                candidate == *key
+            })
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("0x{} is not one of the {} keys handed out by this coordinator file", hex::encode(key), next),
+                )
+            })
+    }
+
+    // Continue at `counter`, for --resume-from-counter
+    fn resume_at(&self, counter: u64) -> io::Result<()> {
+        self.resume_with(|_| Ok(counter))
+    }
+
+    // Continue right after `key`, for --resume-from-key, returning the key's counter
+    fn resume_after(&self, key: &[u8; 32]) -> io::Result<u64> {
+        let mut found = 0;
+        self.resume_with(|next| {
+            found = self.counter_below(key, next)?;
+            Ok(found + 1)
+        })?;
+        Ok(found)
+    }
+
+    // Continue at the counter `start` picks from the next free one, all under one lock so no
+    // claim slips in between. A counter past the next free one moves the shared counter
+    // forward; the counters skipped are never handed out, like the rest of a crashed batch.
+    // An earlier counter is only replayed by this run, which leaves the shared counter alone.
+    fn resume_with(&self, start: impl FnOnce(u64) -> io::Result<u64>) -> io::Result<()> {
+        let mut file = Self::lock(&self.path)?;
+        let next = Self::read_state(&mut file)?.map_or(0, |(_, next)| next);
+        let counter = start(next)?;
+        if counter >= next {
+            Self::write_state(&mut file, &self.seed, counter)
+        } else {
+            *self.replay.lock().unwrap() = counter..next;
+            Ok(())
+        }
+    }
+
+    // Return the rest of the current batch, so the next run resumes right after the last key
+    // handed out. Only possible while no other process has claimed a later batch; otherwise
+    // the rest stays skipped as usual. Does nothing outside deterministic mode.
+    fn give_back(&self) -> io::Result<()> {
+        let Some(unchecked) = &self.unchecked else {
+            return Ok(());
+        };
+        let unchecked = unchecked.lock().unwrap().clone();
+        if unchecked.is_empty() {
+            return Ok(());
+        }
         
-        return false;
+        let mut file = Self::lock(&self.path)?;
+        if let Some((seed, next)) = Self::read_state(&mut file)? {
+            if seed == self.seed && next == unchecked.end {
+                Self::write_state(&mut file, &seed, unchecked.start)?;
+            }
+        }
+        Ok(())
+    }
+
+    // The lock is released when the returned file is dropped
+    fn lock(path: &Path) -> io::Result<File> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    fn read_state(file: &mut File) -> io::Result<Option<([u8; 32], u64)>> {
+        let mut contents = String::new();
+        file.seek(SeekFrom::Start(0))?;
+        file.read_to_string(&mut contents)?;
+        if contents.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "malformed coordinator file");
+        let mut fields = contents.split_whitespace();
+        let seed = fields.next()
+            .and_then(|s| hex::decode(s).ok())
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(invalid)?;
+        let next = fields.next()
+            .and_then(|n| n.parse::<u64>().ok())
+            .ok_or_else(invalid)?;
+
+        Ok(Some((seed, next)))
+    }
+
+    // Overwrite the state in place and only then cut off what is left of a longer old one.
+    // Truncating first would leave an empty file if the process died in between, which the
+    // next run would take for a new keyspace. The file isn't replaced by a renamed temporary
+    // one either, since other processes hold their lock on this very file.
+    fn write_state(file: &mut File, seed: &[u8; 32], next: u64) -> io::Result<()> {
+        let line = format!("{} {}\n", hex::encode(seed), next);
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(line.as_bytes())?;
+        file.set_len(line.len() as u64)?;
+        file.sync_data()
     }
-    true
 }
 
-// Function to display banner
-fn display_banner() {
-    println!("\n\
-\x1b[1;32m██╗███████╗██╗   ██╗███████╗██╗     \x1b[0m\n\
-\x1b[1;32m██║██╔════╝██║   ██║██╔════╝██║     \x1b[0m\n\
-\x1b[1;32m██║█████╗  ██║   ██║█████╗  ██║     \x1b[0m\n\
-\x1b[1;32m██║██╔══╝  ██║   ██║██╔══╝  ██║     \x1b[0m\n\
-\x1b[1;32m██║██║     ╚██████╔╝███████╗███████╗\x1b[0m\n\
-\x1b[1;32m╚═╝╚═╝      ╚═════╝ ╚══════╝╚══════╝\x1b[0m\n\
-    ");
-    println!("\x1b[1;32m════════════════════════════════════════════════════\x1b[0m");
-    println!("\x1b[1;32m⚡ Fuel Vanity Address Generator v1.0.0 ⚡\x1b[0m");
-    println!("\x1b[1;32m════════════════════════════════════════════════════\x1b[0m");
-    println!("  Created by Ban (https://x.com/ohbannedOS)");
-    println!("  https://github.com/ohbanned/Fuel-Vanity-Address-Generator");
-    println!("\x1b[1;32m════════════════════════════════════════════════════\x1b[0m\n");
+// Parse a --resume-from-key key: exactly 64 hex digits, with or without 0x
+fn parse_keyspace_key(input: &str) -> std::result::Result<[u8; 32], String> {
+    let digits = input.strip_prefix("0x").unwrap_or(input);
+    let mut key = [0u8; 32];
+    hex::decode_to_slice(digits, &mut key).map_err(|_| format!("'{}' is not a key of 64 hex digits", input))?;
+    Ok(key)
 }
 
-// Function to display help
-fn display_help() {
-    println!("\n\x1b[1;32m╔════════════════════════════════════════════╗");
-    println!("║           iFuel COMMAND REFERENCE          ║");
-    println!("╚════════════════════════════════════════════╝\x1b[0m");
-    println!("\x1b[1;32m┌────────────────────────────────────────────┐\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m 🔍 GENERATION COMMANDS:                    \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  prefix <pattern>                          \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Generate addresses with specified prefix\x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  suffix <pattern>                          \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Generate addresses with specified suffix\x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  contains <pattern>                        \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Generate addresses containing pattern   \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m└────────────────────────────────────────────┘\x1b[0m");
-    println!("\x1b[1;32m┌────────────────────────────────────────────┐\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m ⚙️  OPTIONS:                               \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  -s, --case-sensitive                      \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Enable case-sensitive pattern matching  \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  -t, --threads <number>                    \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Set number of worker threads            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m└────────────────────────────────────────────┘\x1b[0m");
-    println!("\x1b[1;32m┌────────────────────────────────────────────┐\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m 📋 EXAMPLES:                               \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  prefix abc                                \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Generate addresses starting with 'abc'  \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  suffix cafe -s                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Generate case-sensitive address ending  \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    with 'cafe'                             \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m└────────────────────────────────────────────┘\x1b[0m");
-    println!("\x1b[1;32m┌────────────────────────────────────────────┐\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m 🛠️  UTILITY COMMANDS:                      \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  info                                      \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Display this help message               \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m                                            \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m  exit                                      \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m│\x1b[0m    Exit the program                        \x1b[1;32m│\x1b[0m");
-    println!("\x1b[1;32m└────────────────────────────────────────────┘\x1b[0m");
+// Default number of matches a search collects, unless --count says otherwise
+const RESULT_LIMIT: usize = 5;
+
+// Matches a search keeps in full for the result boxes shown at the end. Every match is
+// written to stdout once it is accepted, so a large --count doesn't hold them all in memory.
+const DISPLAY_TAIL: usize = 10;
+
+// Process exit codes for the non-interactive mode, so scripts can tell outcomes apart
+const EXIT_FOUND: i32 = 0;
+const EXIT_INCOMPLETE: i32 = 2;
+const EXIT_INVALID: i32 = 3;
+const EXIT_RNG_FAILURE: i32 = 4;
+const EXIT_DERIVATION_FAILURE: i32 = 5;
+const EXIT_VERIFY_FAILED: i32 = 6;
+const EXIT_INTERRUPTED: i32 = 130;
+
+// Set on Ctrl+C in the non-interactive mode. Search workers stop at their next key, so the
+// matches found so far are still shown, and the process then exits with EXIT_INTERRUPTED.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+// How long a Ctrl+C waits for the search to wind down before exiting anyway; a second
+// Ctrl+C exits at once
+const INTERRUPT_GRACE: Duration = Duration::from_secs(2);
+
+// What each candidate address is checked against
+#[derive(Debug, Clone)]
+enum SearchCriteria {
+    /// A literal pattern at a position ("prefix", "suffix" or "anywhere")
+    Pattern { pattern: String, position: String },
+    /// At least `min_length` characters of `pattern` in a row, anywhere
+    Partial { pattern: String, min_length: usize },
+    /// At least `count` copies of `digit` at the start or the end of the address
+    Edges { digit: char, count: usize },
+    /// `length` digits in a row that each count up (or down) by one, at a position
+    /// ("prefix", "suffix" or "anywhere")
+    Sequence { direction: SequenceDirection, length: usize, position: String },
+    /// Digits anywhere in the address read as a big-endian integer in base 10
+    Decimal { pattern: String },
+    /// A hex prefix and a bech32 prefix that the same address must both have
+    Dual { hex: String, bech32: String, network: Network },
+    /// Patterns at fixed nibble offsets; every one must match
+    MultiAt { constraints: Vec<(usize, String)> },
+    /// A prefix mask compiled to (value, mask) nibble pairs; `?` compiles to a zero mask
+    Mask { pattern: String, nibbles: Vec<(u8, u8)> },
+    /// A prefix of both the Fuel (SHA-256) and the Ethereum (Keccak-256) address of a key.
+    /// `matches` only sees the Fuel address; the worker checks the Ethereum one.
+    DualScheme { pattern: String },
+    /// A word spelled, casing included, anywhere in the EIP-55 checksum form of the address.
+    /// `matches` only finds the digits; the worker checks the casing.
+    ChecksumWord { word: String },
+    /// The address read as a big-endian integer leaves `remainder` when divided by `divisor`
+    Modulo { divisor: u64, remainder: u64 },
 }
 
-// Interactive mode
-async fn interactive_mode(_threads: usize, case_sensitive: bool) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    display_banner();
-    println!("💡 Type 'help' for available commands or 'exit' to quit.");
-    println!("");
+impl SearchCriteria {
+    fn matches(&self, address: &str, case_sensitive: bool) -> bool {
+        match self {
+            SearchCriteria::Pattern { pattern, position } => {
+                matches_pattern(address, pattern, position, case_sensitive)
+            }
+            // Any longer piece contains one of the shortest ones
+            SearchCriteria::Partial { pattern, min_length } => {
+                pattern_pieces(pattern, *min_length).any(|piece| matches_pattern(address, piece, "anywhere", case_sensitive))
+            }
+            SearchCriteria::Edges { digit, count } => {
+                let (start, end) = edge_runs(address, *digit);
+                start.max(end) >= *count
+            }
+            SearchCriteria::Sequence { direction, length, position } => {
+                find_sequence(address, *direction, *length, position).is_some()
+            }
+            SearchCriteria::Decimal { pattern } => {
+                decimal_form(address).is_some_and(|decimal| decimal.contains(pattern.as_str()))
+            }
+            SearchCriteria::MultiAt { constraints } => {
+                let nibbles = address.strip_prefix("0x").unwrap_or(address);
+                constraints.iter().all(|(offset, pattern)| {
+                    nibbles.get(*offset..offset + pattern.len()).is_some_and(|chunk| {
+                        if case_sensitive { chunk == pattern } else { chunk.eq_ignore_ascii_case(pattern) }
+                    })
+                })
+            }
+            SearchCriteria::Mask { pattern, nibbles } => {
+                let digits = address.strip_prefix("0x").unwrap_or(address);
+                nibbles.len() <= digits.len() && nibbles.iter().zip(digits.chars()).all(|(&(value, mask), c)| {
+                    let nibble = c.to_digit(16).unwrap_or(0) as u8;
+                    (nibble & mask) == value
+                }) && (!case_sensitive || pattern.chars().zip(digits.chars()).all(|(p, c)| p == '?' || p == c))
+            }
+            SearchCriteria::DualScheme { pattern } => matches_pattern(address, pattern, "prefix", case_sensitive),
+            SearchCriteria::ChecksumWord { word } => matches_pattern(address, word, "anywhere", false),
+            SearchCriteria::Modulo { divisor, remainder } => address_modulo(address, *divisor) == *remainder,
+            SearchCriteria::Dual { hex, bech32, network } => {
+                // The cheap hex check runs first so most candidates skip the bech32 encoding
+                matches_pattern(address, hex, "prefix", case_sensitive)
+                    && bech32_form(address, *network).is_some_and(|encoded| {
+                        encoded[network.hrp().len() + 1..].starts_with(&bech32.to_lowercase())
+                    })
+            }
+        }
+    }
+
+    // Extra information about how a matching address matched, if there is any to show
+    fn describe_match(&self, address: &str) -> Option<String> {
+        match self {
+            SearchCriteria::Pattern { .. } => None,
+            SearchCriteria::Partial { pattern, min_length } => {
+                let (offset, length) = find_piece(address, pattern, *min_length)?;
+                let digits = address.strip_prefix("0x").unwrap_or(address);
+                Some(format!("{} at nibble {}", digits.get(offset..offset + length)?, offset))
+            }
+            SearchCriteria::Edges { count, digit } => {
+                let (start, end) = edge_runs(address, *digit);
+                let side = match (start >= *count, end >= *count) {
+                    (true, true) => "both ends",
+                    (true, false) => "start",
+                    _ => "end",
+                };
+                Some(format!("{} (runs: {} at start, {} at end)", side, start, end))
+            }
+            SearchCriteria::Sequence { direction, length, position } => {
+                let offset = find_sequence(address, *direction, *length, position)?;
+                let digits = address.strip_prefix("0x").unwrap_or(address);
+                Some(format!("{} at nibble {}", digits.get(offset..offset + length)?, offset))
+            }
+            SearchCriteria::Decimal { .. } => decimal_form(address).map(|decimal| format!("decimal {}", decimal)),
+            SearchCriteria::Dual { network, .. } => bech32_form(address, *network),
+            SearchCriteria::MultiAt { .. }
+            | SearchCriteria::Mask { .. }
+            | SearchCriteria::DualScheme { .. }
+            | SearchCriteria::ChecksumWord { .. }
+            | SearchCriteria::Modulo { .. } => None,
+        }
+    }
+
+    fn validate(&self, case_sensitive: bool) -> std::result::Result<(), String> {
+        self.validate_shape()?;
+        if case_sensitive {
+            self.validate_case()?;
+        }
+        Ok(())
+    }
     
-    loop {
-        print!("\x1b[1;32miFuel>\x1b[0m ");
-        io::stdout().flush()?;
-        
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        
-        let command = parse_input(&input, case_sensitive);
+    fn validate_shape(&self) -> std::result::Result<(), String> {
+        match self {
+            SearchCriteria::Pattern { pattern, position } => validate_pattern(pattern, position),
+            SearchCriteria::Partial { pattern, min_length } => {
+                validate_pattern(pattern, "anywhere")?;
+                validate_min_match_length(pattern, *min_length)
+            }
+            SearchCriteria::Edges { digit, count } => validate_edges(*digit, *count),
+            SearchCriteria::Sequence { length, position, .. } => validate_sequence(*length, position),
+            SearchCriteria::Decimal { pattern } => validate_decimal(pattern),
+            SearchCriteria::Dual { hex, bech32, network } => {
+                validate_pattern(hex, "prefix")?;
+                validate_bech32(bech32)?;
+                validate_dual_overlap(hex, bech32, *network)
+            }
+            SearchCriteria::MultiAt { constraints } => validate_multi_at(constraints),
+            SearchCriteria::Mask { pattern, nibbles } => {
+                validate_pattern(pattern, "prefix")?;
+                if let Some(c) = pattern.chars().find(|c| *c != '?' && !c.is_ascii_hexdigit()) {
+                    return Err(format!("Mask character '{}' must be a hex digit or '?'", c));
+                }
+                if nibbles.iter().all(|&(_, mask)| mask == 0) {
+                    return Err("Mask needs at least one fixed hex digit".to_string());
+                }
+                Ok(())
+            }
+            SearchCriteria::DualScheme { pattern } => {
+                if !is_valid_hex_pattern(pattern) {
+                    return Err("A dual-scheme pattern must use only hexadecimal characters (0-9, a-f)".to_string());
+                }
+                if pattern.len() > ETHEREUM_ADDRESS_HEX_LEN {
+                    return Err(format!(
+                        "A dual-scheme pattern can be at most {} characters long (the width of an Ethereum address), got {}",
+                        ETHEREUM_ADDRESS_HEX_LEN, pattern.len()
+                    ));
+                }
+                Ok(())
+            }
+            SearchCriteria::ChecksumWord { word } => {
+                if word.is_empty() || !is_valid_hex_pattern(word) {
+                    return Err("A checksum word must use only hexadecimal characters (0-9, a-f, A-F)".to_string());
+                }
+                validate_pattern(word, "anywhere")
+            }
+            SearchCriteria::Modulo { divisor, remainder } => {
+                if *divisor == 0 {
+                    return Err("The divisor must be greater than 0".to_string());
+                }
+                if remainder >= divisor {
+                    return Err(format!("The remainder must be below the divisor {}, got {}", divisor, remainder));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Why an address `matches` rejected fails this criteria, e.g. the first wrong digit of a
+    // prefix. Nibbles are counted from 0 after 0x, like multi-at offsets.
+    fn mismatch_reason(&self, address: &str, case_sensitive: bool) -> String {
+        let digits = address.strip_prefix("0x").unwrap_or(address);
+        let at = |offset: usize, wanted: &str| {
+            let reason = match digits.get(offset..).and_then(|rest| first_difference(rest, wanted, case_sensitive)) {
+                Some((i, actual, want)) => format!("nibble {} is '{}', the pattern wants '{}'", offset + i, actual, want),
+                None => format!("'{}' doesn't fit in the address at nibble {}", wanted, offset),
+            };
+            // Patterns are matched against hex digits only, which bech32 or 0x-prefixed
+            // patterns silently miss
+            if wanted.starts_with(FUEL_BECH32_HRP) || wanted.starts_with(FUEL_TESTNET_BECH32_HRP) {
+                format!("{} (patterns are matched against the hex address; use `dual` for bech32)", reason)
+            } else if wanted.starts_with("0x") {
+                format!("{} (leave out the 0x, patterns are matched after it)", reason)
+            } else {
+                reason
+            }
+        };
         
-        match command {
-            Some(Command::Exit) => {
-                println!("\n👋 Thank you for using iFuel Vanity Address Generator!");
-                println!("   Visit us at https://github.com/ohbanned/Fuel-Vanity-Address-Generator");
-                break;
+        match self {
+            SearchCriteria::Pattern { pattern, position } => match position.as_str() {
+                "suffix" => at(digits.len().saturating_sub(pattern.len()), pattern),
+                "anywhere" => format!("'{}' appears nowhere in the address", pattern),
+                _ => at(0, pattern),
             },
-            Some(cmd) => {
-                // Avoid recursion issue by manually handling each command type
-                match cmd {
-                    Command::Prefix { pattern } => {
-                        display_banner();
-                        println!("⚙️  CONFIGURATION:");
-                        println!("\x1b[1;32m┌────────────────────────────────────────────┐\x1b[0m");
-                        println!("\x1b[1;32m│\x1b[0m Pattern type: \x1b[1;32mPrefix\x1b[0m                    \x1b[1;32m│\x1b[0m");
-                        println!("\x1b[1;32m│\x1b[0m Pattern: \x1b[1;33m{:<32}\x1b[0m \x1b[1;32m│\x1b[0m", pattern);
-                        println!("\x1b[1;32m│\x1b[0m Case-sensitive: \x1b[1;35m{:<23}\x1b[0m \x1b[1;32m│\x1b[0m", case_sensitive);
-                        println!("\x1b[1;32m└────────────────────────────────────────────┘\x1b[0m");
-                        println!("🔍 Searching for vanity addresses...");
-                        println!("   Press Ctrl+C to stop at any time...\n");
-                        
-                        let position = "prefix".to_string();
-                        let results = search_vanity_address(pattern, position, case_sensitive).await;
-                        display_results(&results);
-                    },
-                    Command::Suffix { pattern } => {
-                        display_banner();
-                        println!("⚙️  CONFIGURATION:");
-                        println!("\x1b[1;32m┌────────────────────────────────────────────┐\x1b[0m");
-                        println!("\x1b[1;32m│\x1b[0m Pattern type: \x1b[1;32mSuffix\x1b[0m                    \x1b[1;32m│\x1b[0m");
-                        println!("\x1b[1;32m│\x1b[0m Pattern: \x1b[1;33m{:<32}\x1b[0m \x1b[1;32m│\x1b[0m", pattern);
-                        println!("\x1b[1;32m│\x1b[0m Case-sensitive: \x1b[1;35m{:<23}\x1b[0m \x1b[1;32m│\x1b[0m", case_sensitive);
-                        println!("\x1b[1;32m└────────────────────────────────────────────┘\x1b[0m");
-                        println!("🔍 Searching for vanity addresses...");
-                        println!("   Press Ctrl+C to stop at any time...\n");
-                        
-                        let position = "suffix".to_string();
-                        let results = search_vanity_address(pattern, position, case_sensitive).await;
-                        display_results(&results);
-                    },
-                    Command::Contains { pattern } => {
-                        display_banner();
-                        println!("⚙️  CONFIGURATION:");
-                        println!("\x1b[1;32m┌────────────────────────────────────────────┐\x1b[0m");
-                        println!("\x1b[1;32m│\x1b[0m Pattern type: \x1b[1;32mContains\x1b[0m                  \x1b[1;32m│\x1b[0m");
-                        println!("\x1b[1;32m│\x1b[0m Pattern: \x1b[1;33m{:<32}\x1b[0m \x1b[1;32m│\x1b[0m", pattern);
-                        println!("\x1b[1;32m│\x1b[0m Case-sensitive: \x1b[1;35m{:<23}\x1b[0m \x1b[1;32m│\x1b[0m", case_sensitive);
-                        println!("\x1b[1;32m└────────────────────────────────────────────┘\x1b[0m");
-                        println!("🔍 Searching for vanity addresses...");
-                        println!("   Press Ctrl+C to stop at any time...\n");
-                        
-                        let position = "anywhere".to_string();
-                        let results = search_vanity_address(pattern, position, case_sensitive).await;
-                        display_results(&results);
-                    },
-                    Command::Info => display_help(),
-                    Command::Interactive => println!("\x1b[1;33mℹ️  You're already in interactive mode\x1b[0m"),
-                    Command::Exit => break,
+            SearchCriteria::Partial { pattern, min_length } => {
+                format!("no {} characters of '{}' in a row appear in the address", min_length, pattern)
+            }
+            SearchCriteria::Edges { digit, count } => {
+                let (start, end) = edge_runs(address, *digit);
+                format!("{} '{}' at the start and {} at the end, {} needed", start, digit, end, count)
+            }
+            SearchCriteria::Sequence { direction, length, position } => {
+                let place = match position.as_str() {
+                    "suffix" => "at the end",
+                    "anywhere" => "anywhere",
+                    _ => "at the start",
+                };
+                format!("no {} run of {} digits {}", direction.label(), length, place)
+            }
+            SearchCriteria::Decimal { pattern } => format!(
+                "the decimal form {} doesn't contain {}",
+                decimal_form(address).unwrap_or_default(), pattern
+            ),
+            SearchCriteria::Dual { hex, bech32, network } => {
+                if matches_pattern(address, hex, "prefix", case_sensitive) {
+                    format!(
+                        "the hex prefix matches, but {} doesn't start with {}1{}",
+                        bech32_form(address, *network).unwrap_or_default(), network.hrp(), bech32
+                    )
+                } else {
+                    at(0, hex)
                 }
-            },
-            None => continue,
+            }
+            SearchCriteria::MultiAt { constraints } => constraints
+                .iter()
+                .find(|(offset, pattern)| {
+                    digits.get(*offset..).is_none_or(|rest| first_difference(rest, pattern, case_sensitive).is_some())
+                })
+                .map(|(offset, pattern)| at(*offset, pattern))
+                .unwrap_or_else(|| "every constraint matches".to_string()),
+            SearchCriteria::Mask { pattern, .. } => at(0, pattern),
+            SearchCriteria::DualScheme { pattern } => at(0, pattern),
+            SearchCriteria::ChecksumWord { word } => format!("'{}' appears nowhere in the address", word),
+            SearchCriteria::Modulo { divisor, remainder } => format!(
+                "the address leaves {} when divided by {}, not {}",
+                address_modulo(address, *divisor), divisor, remainder
+            ),
         }
+    }
+    
+    // Case-sensitive matching runs against the mixed-case encoding, which picks the case of
+    // each letter from the byte it is part of, so some casings can never come up
+    fn validate_case(&self) -> std::result::Result<(), String> {
+        let placed: Vec<(&str, Vec<usize>)> = match self {
+            SearchCriteria::Pattern { pattern, position } => {
+                let offsets = match position.as_str() {
+                    "suffix" => vec![ADDRESS_HEX_LEN.saturating_sub(pattern.len())],
+                    "anywhere" => vec![0, 1],
+                    _ => vec![0],
+                };
+                vec![(pattern.as_str(), offsets)]
+            }
+            SearchCriteria::MultiAt { constraints } => constraints
+                .iter()
+                .map(|(offset, pattern)| (pattern.as_str(), vec![*offset]))
+                .collect(),
+            SearchCriteria::Mask { pattern, .. } | SearchCriteria::DualScheme { pattern } => vec![(pattern.as_str(), vec![0])],
+            SearchCriteria::Dual { hex, .. } => vec![(hex.as_str(), vec![0])],
+            // The checksum casing doesn't come from the mixed-case encoding
+            // A sequence is matched by digit value, whatever the case of its letters
+            // A partial match needs only one of its pieces to fit the encoding
+            SearchCriteria::Partial { .. }
+            | SearchCriteria::Edges { .. }
+            | SearchCriteria::Sequence { .. }
+            | SearchCriteria::Decimal { .. }
+            | SearchCriteria::ChecksumWord { .. }
+            | SearchCriteria::Modulo { .. } => Vec::new(),
+        };
         
-        println!("");
+        for (pattern, offsets) in placed {
+            // Any one placement that fits is enough (an anywhere search tries both alignments)
+            let conflicts: Vec<String> = offsets.iter().filter_map(|&offset| mixed_case_conflict(pattern, offset)).collect();
+            if conflicts.len() == offsets.len() {
+                if let Some(digits) = conflicts.first() {
+                    return Err(format!(
+                        "'{}' can never match case-sensitively: the mixed-case encoding never writes \
+                         the digits '{}' with that casing. Change the case of those letters or drop --case-sensitive",
+                        pattern, digits
+                    ));
+                }
+            }
+        }
+        Ok(())
     }
     
-    Ok(())
-}
+    // An illustrative address this criteria accepts, padded with a filler digit. It is built
+    // the way candidates are matched: case is only kept when matching is case-sensitive.
+    fn preview(&self, case_sensitive: bool, hex_case: HexCase) -> String {
+        let filled = |body: String, filler: char, position: &str| {
+            let padding = ADDRESS_HEX_LEN.saturating_sub(body.chars().count());
+            let fill = |n: usize| filler.to_string().repeat(n);
+            match position {
+                "prefix" => format!("{}{}", body, fill(padding)),
+                "suffix" => format!("{}{}", fill(padding), body),
+                _ => format!("{}{}{}", fill(padding / 2), body, fill(padding - padding / 2)),
+            }
+        };
+        
+        let nibbles = match self {
+            SearchCriteria::Pattern { pattern, position } => {
+                let pattern = if case_sensitive { pattern.clone() } else { pattern.to_lowercase() };
+                filled(pattern, '0', position)
+            }
+            // The shortest piece that counts
+            SearchCriteria::Partial { pattern, min_length } => {
+                let piece = pattern_pieces(pattern, *min_length).next().unwrap_or_default();
+                let piece = if case_sensitive { piece.to_string() } else { piece.to_lowercase() };
+                filled(piece, '0', "anywhere")
+            }
+            SearchCriteria::Edges { digit, count } => {
+                let filler = if *digit == '0' { '1' } else { '0' };
+                filled(digit.to_string().repeat(*count), filler, "prefix")
+            }
+            // Up from 0 or down from f; a 0 next to either end never extends the run
+            SearchCriteria::Sequence { direction, length, position } => {
+                let digits = match direction {
+                    SequenceDirection::Asc => "0123456789abcdef",
+                    SequenceDirection::Desc => "fedcba9876543210",
+                };
+                filled(digits[..(*length).min(digits.len())].to_string(), '0', position)
+            }
+            SearchCriteria::MultiAt { constraints } => {
+                let mut nibbles = vec!['0'; ADDRESS_HEX_LEN];
+                for (offset, pattern) in constraints {
+                    for (i, c) in pattern.chars().enumerate() {
+                        if let Some(slot) = nibbles.get_mut(offset + i) {
+                            *slot = if case_sensitive { c } else { c.to_ascii_lowercase() };
+                        }
+                    }
+                }
+                nibbles.into_iter().collect()
+            }
+            SearchCriteria::Mask { pattern, .. } => {
+                let pattern = if case_sensitive { pattern.clone() } else { pattern.to_lowercase() };
+                filled(pattern.replace('?', "0"), '0', "prefix")
+            }
+            // The Fuel half; the Ethereum address starts the same way
+            SearchCriteria::DualScheme { pattern } => {
+                let pattern = if case_sensitive { pattern.clone() } else { pattern.to_lowercase() };
+                filled(pattern, '0', "prefix")
+            }
+            SearchCriteria::ChecksumWord { word } => {
+                // The casing comes from the hash of the whole address, so only the digits can be shown
+                filled(word.to_lowercase(), '0', "prefix")
+            }
+            SearchCriteria::Dual { hex, .. } => {
+                // Only the hex half can be shown; the bech32 half depends on the whole address
+                let hex = if case_sensitive { hex.clone() } else { hex.to_lowercase() };
+                filled(hex, '0', "prefix")
+            }
+            SearchCriteria::Decimal { pattern } => {
+                // A leading 1 keeps any leading zeros of the pattern in the decimal form
+                let value = BigUint::parse_bytes(format!("1{}", pattern).as_bytes(), 10).unwrap_or_default();
+                format!("{:0>64}", value.to_str_radix(16))
+            }
+            // The remainder itself is the smallest such address
+            SearchCriteria::Modulo { remainder, .. } => format!("{:064x}", remainder),
+        };
+        
+        let address = format!("0x{}", nibbles);
+        if case_sensitive { address } else { hex_case.apply(&address) }
+    }
 
-// Function to parse user input
-fn parse_input(input: &str, _case_sensitive: bool) -> Option<Command> {
-    let parts: Vec<&str> = input.trim().split_whitespace().collect();
-    
-    if parts.is_empty() {
-        return None;
+    fn pattern_label(&self) -> String {
+        match self {
+            SearchCriteria::Pattern { pattern, .. } => pattern.clone(),
+            SearchCriteria::Partial { pattern, min_length } => format!("{} ({}+ in a row)", pattern, min_length),
+            SearchCriteria::Edges { digit, count } => format!("{} x{}", digit, count),
+            SearchCriteria::Sequence { direction, length, .. } => format!("{} x{}", direction.label(), length),
+            SearchCriteria::Decimal { pattern } => pattern.clone(),
+            SearchCriteria::Dual { hex, bech32, network } => format!("0x{} / {}1{}", hex, network.hrp(), bech32),
+            SearchCriteria::MultiAt { constraints } => constraints
+                .iter()
+                .map(|(offset, pattern)| format!("{}:{}", offset, pattern))
+                .collect::<Vec<_>>()
+                .join(" "),
+            SearchCriteria::Mask { pattern, .. } | SearchCriteria::DualScheme { pattern } => pattern.clone(),
+            SearchCriteria::ChecksumWord { word } => word.clone(),
+            SearchCriteria::Modulo { divisor, remainder } => format!("{} mod {}", remainder, divisor),
+        }
+    }
+
+    fn position_label(&self) -> &str {
+        match self {
+            SearchCriteria::Pattern { position, .. } => position,
+            SearchCriteria::Partial { .. } => "anywhere",
+            SearchCriteria::Edges { .. } => "edges",
+            SearchCriteria::Sequence { position, .. } => position,
+            SearchCriteria::Decimal { .. } => "decimal",
+            SearchCriteria::Dual { .. } => "hex + bech32 prefix",
+            SearchCriteria::MultiAt { .. } => "multi-at",
+            SearchCriteria::Mask { .. } => "mask",
+            SearchCriteria::DualScheme { .. } => "prefix (Fuel + Ethereum)",
+            SearchCriteria::ChecksumWord { .. } => "checksum casing",
+            SearchCriteria::Modulo { .. } => "modulo",
+        }
     }
     
-    match parts[0].to_lowercase().as_str() {
-        "prefix" => {
-            if parts.len() < 2 {
-                println!("Error: 'prefix' command requires a pattern");
-                return None;
+    // Expected keys per match of a plain pattern: 16 per digit, spread over the places a
+    // contains pattern can start, and roughly doubled per letter whose case must match. A
+    // sequence of n digits can start at 17 - n of the 16 digits, and a partial match is
+    // roughly any one of its shortest pieces. None for the other criteria.
+    fn keys_per_match(&self, case_sensitive: bool) -> Option<f64> {
+        let places = |position: &str, len: usize| match position {
+            "anywhere" => ADDRESS_HEX_LEN.saturating_sub(len) + 1,
+            _ => 1,
+        };
+        match self {
+            SearchCriteria::Pattern { pattern, position } => {
+                let letters = if case_sensitive { pattern.chars().filter(|c| c.is_ascii_alphabetic()).count() } else { 0 };
+                Some(16f64.powi(pattern.len() as i32) * 2f64.powi(letters as i32) / places(position, pattern.len()) as f64)
             }
-            
-            let pattern = parts[1].to_string();
-            Some(Command::Prefix { pattern })
-        },
-        "suffix" => {
-            if parts.len() < 2 {
-                println!("Error: 'suffix' command requires a pattern");
-                return None;
+            SearchCriteria::Sequence { length, position, .. } => {
+                let starts = (17 - length) as f64;
+                Some(16f64.powi(*length as i32) / starts / places(position, *length) as f64)
             }
-            
-            let pattern = parts[1].to_string();
-            Some(Command::Suffix { pattern })
-        },
-        "contains" => {
-            if parts.len() < 2 {
-                println!("Error: 'contains' command requires a pattern");
-                return None;
+            SearchCriteria::Partial { pattern, min_length } if !case_sensitive => {
+                let pieces = pattern_pieces(pattern, *min_length).count().max(1) as f64;
+                Some(16f64.powi(*min_length as i32) / pieces / places("anywhere", *min_length) as f64)
             }
-            
-            let pattern = parts[1].to_string();
-            Some(Command::Contains { pattern })
-        },
-        "help" | "info" => Some(Command::Info),
-        "exit" | "quit" => Some(Command::Exit),
-        "interactive" => Some(Command::Interactive),
-        _ => {
-            println!("Unknown command: {}", parts[0]);
-            println!("Type 'help' for a list of available commands");
-            None
+            _ => None,
         }
     }
 }
 
-#[tokio::main]
-async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
-    
-    if args.command.is_some() {
-        // Execute a single command (non-interactive mode)
-        if let Some(cmd) = args.command {
-            display_banner();
-            println!("Running command in non-interactive mode");
-            execute_command(cmd, args.threads, args.case_sensitive).await?;
-        }
+// The address read as a big-endian integer, modulo `divisor`, reduced one nibble at a time.
+// A power-of-two divisor only depends on the last few nibbles, so only those are read.
+fn address_modulo(address: &str, divisor: u64) -> u64 {
+    let nibbles = address.strip_prefix("0x").unwrap_or(address);
+    let skip = if divisor.is_power_of_two() {
+        nibbles.len().saturating_sub(divisor.trailing_zeros().div_ceil(4) as usize)
     } else {
-        // Interactive mode
-        interactive_mode(args.threads, args.case_sensitive).await?;
+        0
+    };
+    nibbles.chars().skip(skip).fold(0u128, |rest, c| {
+        (rest * 16 + c.to_digit(16).unwrap_or(0) as u128) % divisor as u128
+    }) as u64
+}
+
+// Count how many times `digit` repeats at the start and at the end of the address nibbles
+fn edge_runs(address: &str, digit: char) -> (usize, usize) {
+    let nibbles = address.strip_prefix("0x").unwrap_or(address);
+    let target = digit.to_digit(16);
+    let start = nibbles.chars().take_while(|c| c.to_digit(16) == target).count();
+    let end = nibbles.chars().rev().take_while(|c| c.to_digit(16) == target).count();
+    (start, end)
+}
+
+// Offset of the first run of `length` digits that each count up (or down) by one from the
+// one before, in a single pass over the nibbles. A prefix run must start at nibble 0 and a
+// suffix run end at the last one; "anywhere" takes the first run found.
+fn find_sequence(address: &str, direction: SequenceDirection, length: usize, position: &str) -> Option<usize> {
+    let nibbles = address.strip_prefix("0x").unwrap_or(address);
+    let last = nibbles.len().checked_sub(1)?;
+    let mut start = 0;
+    let mut previous = None;
+    for (i, c) in nibbles.chars().enumerate() {
+        let digit = c.to_digit(16)?;
+        let next = match direction {
+            SequenceDirection::Asc => previous.map(|previous: u32| previous + 1),
+            SequenceDirection::Desc => previous.and_then(|previous: u32| previous.checked_sub(1)),
+        };
+        if previous.is_some() && next != Some(digit) {
+            start = i;
+        }
+        previous = Some(digit);
+        match position {
+            "prefix" if start > 0 => return None,
+            "suffix" if i < last => continue,
+            _ => {}
+        }
+        if i + 1 - start >= length {
+            return Some(i + 1 - length);
+        }
+    }
+    None
+}
+
+// Every `length` characters in a row of `pattern`, from its start on
+fn pattern_pieces(pattern: &str, length: usize) -> impl Iterator<Item = &str> {
+    (0..=pattern.len().saturating_sub(length)).filter_map(move |start| pattern.get(start..start + length))
+}
+
+// Nibble offset and length of the longest piece of at least `min_length` characters of
+// `pattern` in the address, compared case-insensitively
+fn find_piece(address: &str, pattern: &str, min_length: usize) -> Option<(usize, usize)> {
+    let digits = address.strip_prefix("0x").unwrap_or(address).to_lowercase();
+    let pattern = pattern.to_lowercase();
+    (min_length.max(1)..=pattern.len()).rev().find_map(|length| {
+        pattern_pieces(&pattern, length).find_map(|piece| digits.find(piece)).map(|offset| (offset, length))
+    })
+}
+
+// Number of hex characters in a Fuel address, without the 0x prefix
+const ADDRESS_HEX_LEN: usize = 64;
+
+// Hex digits in an Ethereum address (20 bytes)
+const ETHEREUM_ADDRESS_HEX_LEN: usize = 40;
+
+// Number of decimal digits in the largest 256-bit value
+const ADDRESS_DECIMAL_LEN: usize = 78;
+
+// The address read as a big-endian integer, written in base 10
+fn decimal_form(address: &str) -> Option<String> {
+    let nibbles = address.strip_prefix("0x").unwrap_or(address);
+    BigUint::parse_bytes(nibbles.as_bytes(), 16).map(|value| value.to_str_radix(10))
+}
+
+// Parse one offset:pattern constraint of a multi-at search
+fn parse_offset_pattern(pair: &str) -> std::result::Result<(usize, String), String> {
+    let (offset, pattern) = pair
+        .split_once(':')
+        .ok_or_else(|| format!("'{}' is not in offset:pattern form", pair))?;
+    let offset = offset
+        .parse::<usize>()
+        .map_err(|_| format!("'{}' is not a valid nibble offset", offset))?;
+    Ok((offset, pattern.to_string()))
+}
+
+// Check the constraints of a multi-at search: hex only, inside the address, no overlaps
+fn validate_multi_at(constraints: &[(usize, String)]) -> std::result::Result<(), String> {
+    if constraints.is_empty() {
+        return Err("multi-at needs at least one offset:pattern constraint".to_string());
     }
     
+    let mut sorted: Vec<&(usize, String)> = constraints.iter().collect();
+    sorted.sort_by_key(|(offset, _)| *offset);
+    
+    for (offset, pattern) in &sorted {
+        validate_ascii(pattern)?;
+        if pattern.is_empty() || !is_valid_hex_pattern(pattern) {
+            return Err(format!("{}:{} must use only hexadecimal characters (0-9, a-f)", offset, pattern));
+        }
+        if offset + pattern.len() > ADDRESS_HEX_LEN {
+            return Err(format!(
+                "{}:{} runs past the end of the address ({} hex digits)",
+                offset, pattern, ADDRESS_HEX_LEN
+            ));
+        }
+    }
+    for pair in sorted.windows(2) {
+        let ((first_offset, first), (second_offset, second)) = (pair[0], pair[1]);
+        if first_offset + first.len() > *second_offset {
+            return Err(format!(
+                "{}:{} and {}:{} overlap; constraints must cover separate nibbles",
+                first_offset, first, second_offset, second
+            ));
+        }
+    }
     Ok(())
 }
 
-// Function to execute a command
-async fn execute_command(cmd: Command, threads: usize, case_sensitive: bool) -> std::result::Result<(), Box<dyn std::error::Error>> {
-    match cmd {
-        Command::Prefix { pattern } => {
-            let position = "prefix".to_string();
-            let results = search_vanity_address(pattern, position, case_sensitive).await;
-            display_results(&results);
-        },
-        Command::Suffix { pattern } => {
-            let position = "suffix".to_string();
-            let results = search_vanity_address(pattern, position, case_sensitive).await;
-            display_results(&results);
-        },
-        Command::Contains { pattern } => {
-            let position = "anywhere".to_string();
-            let results = search_vanity_address(pattern, position, case_sensitive).await;
-            display_results(&results);
-        },
-        Command::Info => display_help(),
-        Command::Interactive => interactive_mode(threads, case_sensitive).await?,
-        Command::Exit => {}
+// Characters of the bech32 data alphabet
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+// Number of bech32 characters after "fuel1": 52 data characters and a 6 character checksum
+const ADDRESS_BECH32_LEN: usize = 58;
+
+// The address in its Fuel bech32m form on the given network
+fn bech32_form(address: &str, network: Network) -> Option<String> {
+    let bytes = hex::decode(address.strip_prefix("0x").unwrap_or(address)).ok()?;
+    Some(to_bech32_for_network(&bytes.try_into().ok()?, network))
+}
+
+// Check the bech32 half of a dual search before starting it
+fn validate_bech32(pattern: &str) -> std::result::Result<(), String> {
+    validate_ascii(pattern)?;
+    let pattern = pattern.to_lowercase();
+    if let Some(c) = pattern.chars().find(|c| !BECH32_CHARSET.contains(*c)) {
+        return Err(format!("'{}' can't appear in a bech32 address (allowed: {})", c, BECH32_CHARSET));
     }
+    if pattern.len() > ADDRESS_BECH32_LEN {
+        return Err(format!(
+            "A bech32 pattern can be at most {} characters long, got {}",
+            ADDRESS_BECH32_LEN, pattern.len()
+        ));
+    }
+    Ok(())
+}
+
+// Both prefixes describe the leading bits of the same address (4 bits per hex digit,
+// 5 per bech32 character), so they must agree wherever they overlap
+fn validate_dual_overlap(hex: &str, bech32: &str, network: Network) -> std::result::Result<(), String> {
+    let hex_bits: Option<Vec<bool>> = hex.chars()
+        .map(|c| c.to_digit(16).map(|v| (0..4).rev().map(|i| v >> i & 1 == 1).collect::<Vec<_>>()))
+        .collect::<Option<Vec<_>>>()
+        .map(|digits| digits.concat());
+    let bech32_bits: Vec<bool> = bech32.to_lowercase().chars()
+        .filter_map(|c| BECH32_CHARSET.find(c))
+        .flat_map(|v| (0..5).rev().map(move |i| v >> i & 1 == 1))
+        .collect();
     
+    // Non-hex patterns are already reported by warn_if_invalid_hex
+    let Some(hex_bits) = hex_bits else {
+        return Ok(());
+    };
+    if hex_bits.iter().zip(&bech32_bits).any(|(a, b)| a != b) {
+        return Err(format!(
+            "0x{} and {}1{} disagree on the leading bits of the address, so no address can match both",
+            hex, network.hrp(), bech32
+        ));
+    }
+    Ok(())
+}
+
+// Check the pattern of a decimal search before starting it
+fn validate_decimal(pattern: &str) -> std::result::Result<(), String> {
+    validate_ascii(pattern)?;
+    if pattern.is_empty() || !pattern.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("'{}' must contain only decimal digits (0-9)", pattern));
+    }
+    if pattern.len() > ADDRESS_DECIMAL_LEN {
+        return Err(format!(
+            "A decimal pattern can be at most {} digits long, got {}",
+            ADDRESS_DECIMAL_LEN, pattern.len()
+        ));
+    }
+    Ok(())
+}
+
+// Reject characters outside printable ASCII before a pattern is matched or echoed: no address
+// contains them, and control characters would garble the terminal when the pattern is shown.
+// The character is reported by code point rather than printed.
+fn validate_ascii(pattern: &str) -> std::result::Result<(), String> {
+    match pattern.chars().enumerate().find(|(_, c)| !c.is_ascii_graphic()) {
+        Some((i, c)) => Err(format!(
+            "Pattern character {} is U+{:04X}, which is not printable ASCII and never appears in an address",
+            i + 1, c as u32
+        )),
+        None => Ok(()),
+    }
+}
+
+// Reject patterns that can never fit in an address, which would otherwise search forever
+fn validate_pattern(pattern: &str, position: &str) -> std::result::Result<(), String> {
+    validate_ascii(pattern)?;
+    let len = pattern.chars().count();
+    if len > ADDRESS_HEX_LEN {
+        return Err(format!(
+            "A {} pattern can be at most {} characters long (the width of an address), got {}",
+            position, ADDRESS_HEX_LEN, len
+        ));
+    }
     Ok(())
 }
+
+// A run can't be longer than the 16 hex digits, and a single digit is no sequence
+fn validate_sequence(length: usize, position: &str) -> std::result::Result<(), String> {
+    if !(2..=16).contains(&length) {
+        return Err(format!("A sequence must be between 2 and 16 digits long, got {}", length));
+    }
+    if !matches!(position, "prefix" | "suffix" | "contains" | "anywhere") {
+        return Err(format!("Position must be prefix, suffix or contains, got '{}'", position));
+    }
+    Ok(())
+}
+
+// --min-match-length can lower the bar below the whole pattern, never raise it above
+fn validate_min_match_length(pattern: &str, min_length: usize) -> std::result::Result<(), String> {
+    if min_length == 0 || min_length > pattern.len() {
+        return Err(format!(
+            "--min-match-length must be between 1 and the {} characters of '{}', got {}",
+            pattern.len(), pattern, min_length
+        ));
+    }
+    Ok(())
+}
+
+// Check the arguments of an edges search before starting it
+fn validate_edges(digit: char, count: usize) -> std::result::Result<(), String> {
+    if !digit.is_ascii_hexdigit() {
+        return Err(format!("'{}' is not a hexadecimal digit (0-9, a-f)", digit));
+    }
+    if count == 0 || count > ADDRESS_HEX_LEN {
+        return Err(format!("Run length must be between 1 and {}, got {}", ADDRESS_HEX_LEN, count));
+    }
+    Ok(())
+}
+
+// A matching keypair, with an optional note on how it matched
+#[derive(Debug, Clone)]
+struct FoundAddress {
+    address: String,
+    private_key: String,
+    /// Only filled in with --output-public-key
+    public_key: Option<String>,
+    /// Keys checked when this one was found
+    attempts: usize,
+    /// Time from the start of the search until this one was found
+    found_after: Duration,
+    detail: Option<String>,
+}
+
+// The public key of a result as hex, when --output-public-key asked for it
+fn public_key_hex(wanted: bool, deriver: &EcDeriver, key: &[u8; 32], hex_case: HexCase, hex_prefix: bool) -> Option<String> {
+    if !wanted {
+        return None;
+    }
+    deriver
+        .public_key(key)
+        .map(|public_key| with_hex_prefix(hex_case.apply(&prefixed_hex(&public_key)), hex_prefix))
+}
+
+// Generate a random private key
+fn generate_random_private_key() -> [u8; 32] {
+    let mut key_data = [0u8; 32];
+    OsRng.fill_bytes(&mut key_data);
+    key_data
+}
+
+// Least share of random keys a --key-constraint may keep. Below it a single candidate takes
+// over a million draws, and the search would effectively stand still.
+const MIN_KEY_ACCEPTANCE: f64 = 1e-6;
+
+// An entropy class that drawn private keys must belong to, for --key-constraint
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KeyConstraint {
+    /// Number of set bits of the 256-bit key, inclusive on both ends
+    Weight { min: u32, max: u32 },
+    /// At least this many leading zero bytes
+    ZeroBytes(usize),
+}
+
+impl KeyConstraint {
+    fn accepts(&self, key: &[u8; 32]) -> bool {
+        match *self {
+            KeyConstraint::Weight { min, max } => {
+                let weight: u32 = key.iter().map(|byte| byte.count_ones()).sum();
+                (min..=max).contains(&weight)
+            }
+            KeyConstraint::ZeroBytes(count) => key.iter().take_while(|&&byte| byte == 0).count() >= count,
+        }
+    }
+
+    // Share of uniformly random keys the constraint keeps
+    fn acceptance(&self) -> f64 {
+        match *self {
+            KeyConstraint::Weight { min, max } => {
+                // Binomial(256, 1/2), built up from P(weight = 0) = 2^-256
+                let mut p = 0.5f64.powi(256);
+                let mut total = 0.0;
+                for k in 0..=max {
+                    if k >= min {
+                        total += p;
+                    }
+                    p *= (256 - k) as f64 / (k + 1) as f64;
+                }
+                total
+            }
+            KeyConstraint::ZeroBytes(count) => 256f64.powi(-(count as i32)),
+        }
+    }
+}
+
+impl fmt::Display for KeyConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyConstraint::Weight { min, max } if min == max => write!(f, "weight={}", min),
+            KeyConstraint::Weight { min, max } => write!(f, "weight={}-{}", min, max),
+            KeyConstraint::ZeroBytes(count) => write!(f, "zero-bytes={}", count),
+        }
+    }
+}
+
+// Parse a --key-constraint spec, rejecting classes too rare to draw from in practice
+fn parse_key_constraint(spec: &str) -> std::result::Result<KeyConstraint, String> {
+    let (kind, value) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' is not in kind=value form (weight=N, weight=MIN-MAX or zero-bytes=N)", spec))?;
+    let number = |n: &str| n.trim().parse::<u32>().map_err(|_| format!("'{}' is not a valid number", n));
+    
+    let constraint = match kind {
+        "weight" => {
+            let (min, max) = match value.split_once('-') {
+                Some((min, max)) => (number(min)?, number(max)?),
+                None => (number(value)?, number(value)?),
+            };
+            if min > max || max > 256 {
+                return Err(format!("'{}' is not a weight range within 0-256", value));
+            }
+            KeyConstraint::Weight { min, max }
+        }
+        "zero-bytes" => KeyConstraint::ZeroBytes(number(value)? as usize),
+        _ => return Err(format!("Unknown key constraint '{}', expected weight or zero-bytes", kind)),
+    };
+    
+    if constraint.acceptance() < MIN_KEY_ACCEPTANCE {
+        return Err(format!(
+            "{} keeps only about 1 in {:.1e} random keys, too few to search with",
+            constraint, 1.0 / constraint.acceptance()
+        ));
+    }
+    Ok(constraint)
+}
+
+// Draw random private keys until one satisfies the constraint, if there is one
+fn generate_constrained_key(constraint: Option<KeyConstraint>) -> [u8; 32] {
+    loop {
+        let key = generate_random_private_key();
+        if constraint.is_none_or(|constraint| constraint.accepts(&key)) {
+            return key;
+        }
+    }
+}
+
+// Number of keys drawn at startup to sanity-check the random generator
+const RNG_HEALTH_SAMPLE: usize = 4096;
+
+// Draw a sample of keys and reject a generator that is obviously broken: a repeated key,
+// an all-zero key or a key made of one repeated byte. This can't prove randomness, but it
+// catches an environment where every key would be predictable before any key is shown.
+fn check_rng_health(mut draw: impl FnMut() -> [u8; 32]) -> std::result::Result<(), String> {
+    let mut seen = HashSet::with_capacity(RNG_HEALTH_SAMPLE);
+    for i in 0..RNG_HEALTH_SAMPLE {
+        let key = draw();
+        if key.iter().all(|&byte| byte == key[0]) {
+            return Err(format!("sample key #{} is the byte 0x{:02x} repeated", i + 1, key[0]));
+        }
+        if !seen.insert(key) {
+            return Err(format!("sample key #{} repeats an earlier key", i + 1));
+        }
+    }
+    Ok(())
+}
+
+// How long each candidate thread count is benchmarked for by --auto-threads
+const CALIBRATION_MS: u64 = 300;
+
+// Candidate thread counts for calibration: one, half and all cores, without duplicates
+fn calibration_candidates(cores: usize) -> Vec<usize> {
+    let cores = cores.max(1);
+    let mut candidates = vec![1, (cores / 2).max(1), cores];
+    candidates.dedup();
+    candidates
+}
+
+// Measure keys/sec for each candidate thread count and return the fastest one
+fn calibrate_thread_count(duration: Duration) -> usize {
+    let mut best = (1, 0.0);
+    
+    for threads in calibration_candidates(num_cpus::get()) {
+        let rate = measure_key_rate(threads, duration);
+        if rate > best.1 {
+            best = (threads, rate);
+        }
+    }
+    
+    best.0
+}
+
+// Run the same generate-and-derive loop as the search on `threads` threads for `duration`
+fn measure_key_rate(threads: usize, duration: Duration) -> f64 {
+    let generated = AtomicUsize::new(0);
+    let start = Instant::now();
+    
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let deriver = EcDeriver::new();
+                while start.elapsed() < duration {
+                    let private_key = generate_random_private_key();
+                    let _ = deriver.address(&private_key).map(|address| format_address(&address, false));
+                    generated.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    
+    generated.load(Ordering::Relaxed) as f64 / start.elapsed().as_secs_f64()
+}
+
+// Function to convert an address to mixed case for better visual representation
+// when case-sensitive matching is enabled
+fn convert_to_mixed_case(address: &str) -> String {
+    if !address.starts_with("0x") {
+        return address.to_string();
+    }
+    
+    let address_part = &address[2..]; // Skip the 0x prefix
+    let mut result = String::from("0x");
+    
+    for (i, c) in address_part.chars().enumerate() {
+        if ('a'..='f').contains(&c) {
+            // Convert some of the hex letters to uppercase based on position
+            if i % 3 == 0 {
+                result.push((c as u8 - b'a' + b'A') as char);
+            } else {
+                result.push(c);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    
+    result
+}
+
+// Format derived address bytes with the case-sensitive option
+fn format_address(address_bytes: &[u8; 32], preserve_case: bool) -> String {
+    // Format with 0x prefix
+    if preserve_case {
+        // Use a mixed-case encoding for case-sensitive display
+        format!("0x{}", encode_mixed_case(address_bytes))
+    } else {
+        // Use regular lowercase hex
+        prefixed_hex(address_bytes)
+    }
+}
+
+// `bytes` as 0x-prefixed lowercase hex, in a single allocation
+fn prefixed_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + bytes.len() * 2);
+    hex.push_str("0x");
+    push_hex(&mut hex, bytes);
+    hex
+}
+
+// Format private key bytes in the chosen encoding; --hex-case and --no-0x only apply to hex
+fn format_private_key(key: &[u8; 32], key_format: KeyFormat, hex_case: HexCase, hex_prefix: bool) -> String {
+    match key_format {
+        KeyFormat::Hex => with_hex_prefix(hex_case.apply(&prefixed_hex(key)), hex_prefix),
+        KeyFormat::Base64 => BASE64_STANDARD.encode(key),
+    }
+}
+
+// A matched address (from format_address) as it is shown in the results
+fn display_address(address: &str, case_sensitive: bool, hex_case: HexCase, hex_prefix: bool) -> String {
+    let address = if case_sensitive { convert_to_mixed_case(address) } else { hex_case.apply(address) };
+    with_hex_prefix(address, hex_prefix)
+}
+
+// Drop the 0x of a shown value for --no-0x. Matching always sees it, so this only happens
+// once a result is formatted for output.
+fn with_hex_prefix(value: String, hex_prefix: bool) -> String {
+    match value.strip_prefix("0x") {
+        Some(digits) if !hex_prefix => digits.to_string(),
+        _ => value,
+    }
+}
+
+// The first pair of digits of `pattern`, placed at nibble `offset`, that no byte encodes to
+// under encode_mixed_case. '?' and non-hex characters (reported elsewhere) match anything.
+fn mixed_case_conflict(pattern: &str, offset: usize) -> Option<String> {
+    let mut slots: Vec<Option<char>> = vec![None; offset % 2];
+    slots.extend(pattern.chars().map(|c| c.is_ascii_hexdigit().then_some(c)));
+    slots.chunks(2).find_map(|pair| {
+        let fits = (0..=255u8).any(|byte| {
+            encode_mixed_case(&[byte]).chars().zip(pair).all(|(c, want)| want.is_none_or(|want| want == c))
+        });
+        (!fits).then(|| pair.iter().flatten().collect())
+    })
+}
+
+// Function to encode bytes with mixed-case for better visual diversity
+fn encode_mixed_case(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len() * 2);
+    for &byte in bytes.iter() {
+        // For every other byte, use uppercase for one of the two hex characters
+        let high = byte >> 4;
+        let low = byte & 0xF;
+        
+        // Convert to hex chars with some randomness in the casing
+        if high < 10 {
+            result.push((b'0' + high) as char);
+        } else {
+            // Use uppercase for some of the alpha chars
+            if byte % 3 == 0 {
+                result.push((b'A' + (high - 10)) as char);
+            } else {
+                result.push((b'a' + (high - 10)) as char);
+            }
+        }
+        
+        if low < 10 {
+            result.push((b'0' + low) as char);
+        } else {
+            // Use uppercase for some of the alpha chars
+            if byte % 2 == 0 {
+                result.push((b'A' + (low - 10)) as char);
+            } else {
+                result.push((b'a' + (low - 10)) as char);
+            }
+        }
+    }
+    result
+}
+
+// What a finished search produced, and how much work it took
+struct SearchRun {
+    /// The last DISPLAY_TAIL matches in full
+    results: Vec<FoundAddress>,
+    /// Every match in the order found, without its key
+    matches: Vec<MatchRecord>,
+    attempts: usize,
+    elapsed: Duration,
+    /// Keys per second after the warm-up window
+    rate: f64,
+}
+
+// What a search keeps of every match after writing it out: enough for the balance check,
+// --latency-stats and --count-distribution
+#[derive(Debug, Clone, Copy)]
+struct MatchRecord {
+    /// Only read by --check-balance, which needs the network feature
+    #[cfg_attr(not(feature = "network"), allow(dead_code))]
+    address: [u8; 32],
+    attempts: usize,
+    found_after: Duration,
+}
+
+impl MatchRecord {
+    fn of(address: [u8; 32], found: &FoundAddress) -> Self {
+        MatchRecord { address, attempts: found.attempts, found_after: found.found_after }
+    }
+}
+
+// Matches of a running search, shared by its workers. The address bytes turn duplicates
+// away in constant time, and only the newest DISPLAY_TAIL results are kept in full.
+#[derive(Default)]
+struct FoundSoFar {
+    seen: HashSet<[u8; 32]>,
+    matches: Vec<MatchRecord>,
+    tail: VecDeque<FoundAddress>,
+}
+
+// Longest the progress line waits between redraws, however slow drawing it gets
+const MAX_REFRESH: Duration = Duration::from_secs(2);
+
+// Interval between progress redraws that backs off when drawing is slow, e.g. over a laggy
+// SSH link where every write blocks. A draw taking over a quarter of the interval doubles
+// it; once draws are quick again it halves back down towards the configured one.
+struct RedrawPacer {
+    base: Duration,
+    current: Duration,
+}
+
+impl RedrawPacer {
+    fn new(base: Duration) -> Self {
+        RedrawPacer { base, current: base }
+    }
+
+    // Record how long a redraw took and return the interval to wait before the next one
+    fn record(&mut self, draw: Duration) -> Duration {
+        if draw * 4 > self.current {
+            self.current = (self.current * 2).min(MAX_REFRESH.max(self.base));
+        } else if draw * 16 < self.current {
+            self.current = (self.current / 2).max(self.base);
+        }
+        self.current
+    }
+}
+
+// Thread start-up and the first iterations make the first readings noisy, so the rate is
+// only reported once this much time has passed, and counted from that point on
+const RATE_WARMUP: Duration = Duration::from_millis(200);
+
+// Steady-state search rate, measured from a baseline taken at the end of the warm-up
+struct RateMeter {
+    start: Instant,
+    baseline: Option<(Instant, usize)>,
+}
+
+impl RateMeter {
+    fn new(start: Instant) -> Self {
+        RateMeter { start, baseline: None }
+    }
+    
+    // Keys per second since the baseline, or None while still warming up
+    fn rate(&mut self, checked: usize) -> Option<f64> {
+        let now = Instant::now();
+        let Some((since, base)) = self.baseline else {
+            if now.duration_since(self.start) >= RATE_WARMUP {
+                self.baseline = Some((now, checked));
+            }
+            return None;
+        };
+        let secs = now.duration_since(since).as_secs_f64();
+        (secs > 0.0).then(|| checked.saturating_sub(base) as f64 / secs)
+    }
+    
+    // The steady-state rate, falling back to the overall rate for runs shorter than the warm-up
+    fn final_rate(&mut self, checked: usize) -> f64 {
+        self.rate(checked).unwrap_or_else(|| {
+            let secs = self.start.elapsed().as_secs_f64();
+            if secs > 0.0 { checked as f64 / secs } else { 0.0 }
+        })
+    }
+}
+
+// Columns taken by the search box, and by the smaller configuration box
+const RESULT_BOX_WIDTH: usize = 54;
+const CONFIG_BOX_WIDTH: usize = 46;
+
+// Text width inside the result boxes: the default when the width of the output is unknown,
+// the narrowest box worth drawing, and the widest needed (0x and 64 digits on one line).
+// The borders take RESULT_BOX_BORDER more columns.
+const RESULT_WRAP_DEFAULT: usize = 48;
+const RESULT_WRAP_MIN: usize = 32;
+const RESULT_WRAP_MAX: usize = 66;
+const RESULT_BOX_BORDER: usize = 4;
+
+// Text width of the result boxes for a terminal this many columns wide, or None when not
+// even the narrowest box fits and the fields go one per line instead
+fn result_wrap_width(columns: Option<usize>) -> Option<usize> {
+    match columns {
+        None => Some(RESULT_WRAP_DEFAULT),
+        Some(columns) => {
+            let width = columns.saturating_sub(RESULT_BOX_BORDER);
+            (width >= RESULT_WRAP_MIN).then(|| width.min(RESULT_WRAP_MAX))
+        }
+    }
+}
+
+// Whether box art this wide fits the terminal. Checked each time a box is drawn, so a
+// resize between searches is picked up; output that isn't a terminal always gets boxes.
+fn box_fits(width: usize) -> bool {
+    box_fits_columns(width, crossterm::terminal::size().ok().map(|(columns, _)| columns as usize))
+}
+
+fn box_fits_columns(width: usize, columns: Option<usize>) -> bool {
+    columns.is_none_or(|columns| columns >= width)
+}
+
+// Watch the keyboard during an interactive search: space toggles `paused`, and Ctrl+C,
+// which raw mode no longer turns into a signal, sets `cancelled` to end just this search
+// and go back to the prompt. Returns None when stdin is not a terminal or raw mode can't
+// be entered.
+fn listen_for_pause(paused: Arc<AtomicBool>, cancelled: Arc<AtomicBool>, searching: Arc<AtomicBool>) -> Option<thread::JoinHandle<()>> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
+    
+    if !atty::is(atty::Stream::Stdin) || terminal::enable_raw_mode().is_err() {
+        return None;
+    }
+    
+    Some(thread::spawn(move || {
+        while searching.load(Ordering::SeqCst) {
+            if !event::poll(Duration::from_millis(100)).unwrap_or(false) {
+                continue;
+            }
+            let Ok(Event::Key(key)) = event::read() else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char(' ') => {
+                    paused.fetch_xor(true, Ordering::SeqCst);
+                }
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    cancelled.store(true, Ordering::SeqCst);
+                }
+                _ => {}
+            }
+        }
+        let _ = terminal::disable_raw_mode();
+    }))
+}
+
+// How often --thermal-guard reads the CPU temperature
+#[cfg(feature = "thermal")]
+const THERMAL_POLL: Duration = Duration::from_secs(2);
+
+// Degrees below the --thermal-guard limit the CPU must reach before the workers resume, so
+// they don't flip on and off around the limit
+#[cfg(feature = "thermal")]
+const THERMAL_HYSTERESIS: f64 = 5.0;
+
+// Hottest reading of the kernel's thermal zones, in degrees Celsius. None when there are no
+// zones, as in most VMs and containers, and on systems other than Linux.
+#[cfg(feature = "thermal")]
+fn cpu_temperature() -> Option<f64> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    std::fs::read_dir("/sys/class/thermal")
+        .ok()?
+        .flatten()
+        .filter(|zone| zone.file_name().to_string_lossy().starts_with("thermal_zone"))
+        .filter_map(|zone| std::fs::read_to_string(zone.path().join("temp")).ok())
+        // The kernel reports millidegrees
+        .filter_map(|millidegrees| millidegrees.trim().parse::<f64>().ok())
+        .map(|millidegrees| millidegrees / 1000.0)
+        .reduce(f64::max)
+}
+
+// Whether the workers should be held back after a temperature reading: start above `limit`
+// and stop once the CPU has cooled below the limit minus THERMAL_HYSTERESIS. A failed
+// reading keeps the current state.
+#[cfg(feature = "thermal")]
+fn thermal_throttle(throttled: bool, reading: Option<f64>, limit: f64) -> bool {
+    match reading {
+        Some(celsius) if !throttled => celsius > limit,
+        Some(celsius) => celsius >= limit - THERMAL_HYSTERESIS,
+        None => throttled,
+    }
+}
+
+// --thermal-guard: poll `read_temperature` (cpu_temperature outside of tests) and keep
+// `throttled` set as thermal_throttle says. Returns how long the workers were held back
+// in total.
+#[cfg(feature = "thermal")]
+fn watch_temperature(
+    limit: f64,
+    read_temperature: impl Fn() -> Option<f64> + Send + 'static,
+    throttled: Arc<AtomicBool>,
+    searching: Arc<AtomicBool>,
+) -> thread::JoinHandle<Duration> {
+    thread::spawn(move || {
+        let mut held = Duration::ZERO;
+        let mut since: Option<Instant> = None;
+        let mut next_reading = Instant::now();
+        while searching.load(Ordering::SeqCst) {
+            if Instant::now() >= next_reading {
+                next_reading += THERMAL_POLL;
+                match (thermal_throttle(since.is_some(), read_temperature(), limit), since) {
+                    (true, None) => {
+                        since = Some(Instant::now());
+                        throttled.store(true, Ordering::SeqCst);
+                    }
+                    (false, Some(start)) => {
+                        held += start.elapsed();
+                        since = None;
+                        throttled.store(false, Ordering::SeqCst);
+                    }
+                    _ => {}
+                }
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+        held + since.map_or(Duration::ZERO, |start| start.elapsed())
+    })
+}
+
+// How long a held worker idles before it looks at the flags again
+const HOLD_POLL: Duration = Duration::from_millis(50);
+
+// Hold the core idle while paused or cooling down, returning true if the worker waited and
+// should check its stop conditions again before the next key. The counters keep their values.
+fn hold_if_paused(paused: &AtomicBool, throttled: &AtomicBool) -> bool {
+    let held = paused.load(Ordering::SeqCst) || throttled.load(Ordering::SeqCst);
+    if held {
+        thread::sleep(HOLD_POLL);
+    }
+    held
+}
+
+// Enhanced search function with beautiful UI. A pausable search can be paused and
+// resumed with the space bar, and Ctrl+C ends it with the matches found so far by setting
+// `cancelled`, which the caller may also set to end just this search.
+async fn search_vanity_address(
+    criteria: SearchCriteria,
+    options: &SearchOptions,
+    pausable: bool,
+    cancelled: Arc<AtomicBool>
+) -> SearchRun {
+    let case_sensitive = options.case_sensitive;
+    let hex_case = options.hex_case;
+    let key_format = options.key_format;
+    let hex_prefix = options.hex_prefix;
+    let output_format = options.output_format;
+    let theme = options.theme;
+    let Theme { accent, label, emphasis, error, reset, .. } = theme;
+    
+    // Create a progress bar with beautiful formatting, unless it was turned off
+    let progress = options.show_progress.then(|| {
+        let progress_bar = ProgressBar::new(100);
+        progress_bar.set_style(ProgressStyle::default_bar()
+            .template(&format!("\r\x1b[2K{accent}Searched:{reset} {{human_pos}} | {accent}Found:{reset} {{msg}} | {emphasis}Rate:{reset} {{prefix}}/s"))
+            .unwrap());
+        progress_bar
+    });
+    
+    // Matches found so far, shared by the workers
+    let results: Arc<Mutex<FoundSoFar>> = Arc::default();
+    let debug_shown = Arc::new(AtomicUsize::new(if options.debug_match { 0 } else { DEBUG_MATCH_SAMPLE }));
+    
+    // Display beautiful configuration header with fixed width; binary output keeps stdout for records
+    let text_output = options.output_format == OutputFormat::Text;
+    if text_output {
+        if box_fits(RESULT_BOX_WIDTH) {
+            println!("\n{accent}╔════════════════════════════════════════════════════╗");
+            println!("║           VANITY ADDRESS SEARCH                 ║");
+            println!("╚════════════════════════════════════════════════════╝");
+            println!("╔════════════════════════════════════════════════════╗");
+            println!("║ {label}Pattern:{reset} {:<40} ║", criteria.pattern_label());
+            println!("║ {label}Position:{reset} {:<40} ║", criteria.position_label());
+            println!("║ {label}Case Sensitive:{reset} {:<32} ║", case_sensitive);
+            println!("╚════════════════════════════════════════════════════╝");
+        } else {
+            println!("\n{accent}VANITY ADDRESS SEARCH{reset}");
+            println!("  {label}Pattern:{reset} {}", criteria.pattern_label());
+            println!("  {label}Position:{reset} {}", criteria.position_label());
+            println!("  {label}Case Sensitive:{reset} {}", case_sensitive);
+        }
+        if pausable {
+            println!("{label}⚠️  Press Ctrl+C to stop the search and return to the prompt{reset}");
+            println!("{label}⏸️  Press space to pause or resume{reset}");
+        } else {
+            println!("{label}⚠️  Press Ctrl+C to stop the search at any time{reset}");
+        }
+        println!();
+        if let Some(constraint) = options.key_constraint {
+            println!(
+                "{label}🎲 Key constraint {}: about 1 in {:.0} random keys qualifies, so each candidate takes that many draws{reset}\n",
+                constraint, 1.0 / constraint.acceptance()
+            );
+        }
+        if let SearchCriteria::Decimal { .. } = criteria {
+            println!("{label}⚠️  Decimal matching converts every address to a big number and is much slower{reset}\n");
+        }
+        if let SearchCriteria::Dual { .. } | SearchCriteria::DualScheme { .. } = criteria {
+            println!("{label}⚠️  Both patterns must hold at once, so their difficulties multiply{reset}\n");
+        }
+        if let SearchCriteria::ChecksumWord { word } = &criteria {
+            let letters = word.chars().filter(|c| c.is_ascii_alphabetic()).count();
+            println!("{label}⚠️  Each letter of the word must also get the right checksum case: about 2^{} times more keys per match{reset}\n", letters);
+        }
+        if let SearchCriteria::Modulo { divisor, .. } = &criteria {
+            println!("{label}🎯 Remainders are spread evenly: about {} keys per match{reset}\n", HumanCount(*divisor));
+        }
+        if let (SearchCriteria::Sequence { .. }, Some(keys)) = (&criteria, criteria.keys_per_match(case_sensitive)) {
+            println!("{label}🎯 Each digit of the sequence makes a match about 16 times rarer: about {} keys per match{reset}\n", describe_keys(keys));
+        }
+        if let SearchCriteria::Mask { nibbles, .. } = &criteria {
+            let fixed = nibbles.iter().filter(|&&(_, mask)| mask != 0).count();
+            println!("{label}🎯 {} fixed digits: about 16^{} keys per match{reset}\n", fixed, fixed);
+        }
+    }
+    
+    let header = match output_format {
+        OutputFormat::Csv => write_csv_header(options.output_public_key),
+        OutputFormat::Json => write_json_head(),
+        _ => Ok(()),
+    };
+    if let Err(e) = header {
+        eprintln!("{error}⚠️  Failed to write {:?} output: {}{reset}", output_format, e);
+    }
+    // Text matches are listed as they are found only when there are more than the boxes show
+    let stream_text = text_output && options.count > DISPLAY_TAIL;
+    
+    let start = Instant::now();
+    let addresses_checked = Arc::new(AtomicUsize::new(0));
+    let found_count = Arc::new(AtomicUsize::new(0));
+    
+    // Workers only bump the counters; one aggregator thread measures the rate, redraws the
+    // bar every `refresh` (longer while drawing is slow) and logs a milestone line whenever
+    // the count passes the next one
+    let searching = Arc::new(AtomicBool::new(true));
+    let paused = Arc::new(AtomicBool::new(false));
+    let throttled = Arc::new(AtomicBool::new(false));
+    let milestone = options.milestone;
+    let aggregator = {
+        let progress_bar = progress.clone();
+        let searching = searching.clone();
+        let paused = paused.clone();
+        let throttled = throttled.clone();
+        let addresses_checked = addresses_checked.clone();
+        let found_count = found_count.clone();
+        let refresh = options.refresh;
+        
+        thread::spawn(move || {
+            let mut meter = RateMeter::new(start);
+            let mut next_milestone = milestone;
+            let mut pacer = RedrawPacer::new(refresh);
+            let mut interval = refresh;
+            while searching.load(Ordering::SeqCst) {
+                let checked = addresses_checked.load(Ordering::SeqCst);
+                let rate = meter.rate(checked);
+                let rate_label = rate.map_or_else(|| "…".to_string(), |rate| HumanCount(rate as u64).to_string());
+                if let Some(progress_bar) = &progress_bar {
+                    let draw_start = Instant::now();
+                    progress_bar.set_position(checked as u64);
+                    let found = found_count.load(Ordering::SeqCst);
+                    if paused.load(Ordering::SeqCst) {
+                        progress_bar.set_message(format!("{} | PAUSED, press space to resume", found));
+                    } else if throttled.load(Ordering::SeqCst) {
+                        progress_bar.set_message(format!("{} | COOLING DOWN, CPU above --thermal-guard", found));
+                    } else {
+                        progress_bar.set_message(format!("{}", found));
+                    }
+                    progress_bar.set_prefix(rate_label.clone());
+                    interval = pacer.record(draw_start.elapsed());
+                }
+                if let (Some(every), Some(next)) = (milestone, next_milestone) {
+                    if checked as u64 >= next {
+                        eprintln!(
+                            "Searched {} in {:.1}s ({}/s), found {}",
+                            HumanCount(checked as u64), start.elapsed().as_secs_f64(), rate_label, found_count.load(Ordering::SeqCst)
+                        );
+                        next_milestone = Some((checked as u64 / every + 1) * every);
+                    }
+                }
+                thread::sleep(interval);
+            }
+            meter.final_rate(addresses_checked.load(Ordering::SeqCst))
+        })
+    };
+    
+    let pause_listener = if pausable { listen_for_pause(paused.clone(), cancelled.clone(), searching.clone()) } else { None };
+    #[cfg(feature = "thermal")]
+    let thermal_watcher = options.thermal_guard.map(|limit| watch_temperature(limit, cpu_temperature, throttled.clone(), searching.clone()));
+    
+    // Create a vector to hold thread handles
+    let mut handles = vec![];
+    
+    // Suffix patterns are checked on the address bytes first, so almost every candidate is
+    // turned away before its address is formatted
+    let suffix_matcher = match &criteria {
+        SearchCriteria::Pattern { pattern, position } if position == "suffix" => SuffixMatcher::new(pattern),
+        _ => None,
+    };
+    
+    // Spawn worker threads
+    let limit = options.count;
+    for _ in 0..options.threads {
+        let criteria = criteria.clone();
+        let suffix_matcher = suffix_matcher.clone();
+        let verify_on_find = options.verify_on_find;
+        let results = results.clone();
+        let found_count = found_count.clone();
+        let addresses_checked = addresses_checked.clone();
+        let audit_log = options.audit_log.clone();
+        let webhook = options.webhook.clone();
+        let coordinator = options.coordinator.clone();
+        let paused = paused.clone();
+        let throttled = throttled.clone();
+        let cancelled = cancelled.clone();
+        let output_public_key = options.output_public_key;
+        let debug_shown = debug_shown.clone();
+        let key_constraint = options.key_constraint;
+        let hash = options.hash;
+        let progress = progress.clone();
+        let delimiter = options.delimiter.clone();
+        let pick = options.pick;
+        
+        // The loop never yields, so each worker gets an OS thread of its own from the blocking
+        // pool: a runtime task would cap --threads at the runtime's worker count
+        let handle = tokio::task::spawn_blocking(move || {
+            let deriver = EcDeriver::with_hash(hash);
+            let mut claimed = 0..0;
+            
+            loop {
+                // Another worker may have filled the results already, or Ctrl+C was
+                // pressed. Checked only between keys, so a match is always recorded in full.
+                if found_count.load(Ordering::SeqCst) >= limit || INTERRUPTED.load(Ordering::SeqCst) || cancelled.load(Ordering::SeqCst) {
+                    break;
+                }
+                
+                if hold_if_paused(&paused, &throttled) {
+                    continue;
+                }
+                
+                addresses_checked.fetch_add(1, Ordering::SeqCst);
+                
+                // Take the next coordinated key, or generate a random private key
+                let private_key = match &coordinator {
+                    Some(coordinator) => match coordinator.next_key(&mut claimed) {
+                        Ok(key) => key,
+                        Err(e) => {
+                            eprintln!("\n{error}⚠️  Coordinator failed: {}{reset}", e);
+                            break;
+                        }
+                    },
+                    None => generate_constrained_key(key_constraint),
+                };
+                
+                // Get the address from the private key; in Fuel it is the SHA-256 hash of the public key
+                // --debug-match candidates are formatted either way, to show why they were rejected
+                let address_result = deriver
+                    .address(&private_key)
+                    .filter(|address| {
+                        suffix_matcher.as_ref().is_none_or(|matcher| matcher.matches(address))
+                            || debug_shown.load(Ordering::Relaxed) < DEBUG_MATCH_SAMPLE
+                    })
+                    .map(|address| (address, format_address(&address, case_sensitive)));
+                
+                if let Some((address_bytes, address)) = address_result {
+                    if debug_shown.load(Ordering::Relaxed) < DEBUG_MATCH_SAMPLE {
+                        let shown = debug_shown.fetch_add(1, Ordering::Relaxed);
+                        if shown < DEBUG_MATCH_SAMPLE {
+                            let verdict = explain_candidate(&criteria, &deriver, &private_key, &address, case_sensitive, hex_case)
+                                .unwrap_or_else(|| "matches".to_string());
+                            eprintln!("{label}🔎 Candidate {}:{reset} {} - {}", shown + 1, address, verdict);
+                        }
+                    }
+                    
+                    // Check if the address matches the pattern
+                    if criteria.matches(&address, case_sensitive) {
+                        let Some(detail) = confirm_match(&criteria, &deriver, &private_key, &address, hex_case) else {
+                            continue;
+                        };
+                        if verify_on_find {
+                            verify_found(&private_key, &address, hash, &theme);
+                        }
+                        let display_address = display_address(&address, case_sensitive, hex_case, hex_prefix);
+                        
+                        // Check the cap, skip duplicates and add the result under one lock, so
+                        // concurrent workers can neither exceed the limit nor record an address twice
+                        let total = {
+                            let mut results = results.lock().unwrap();
+                            if results.matches.len() >= limit {
+                                break;
+                            }
+                            if results.seen.contains(&address_bytes) {
+                                continue;
+                            }
+                            
+                            let found = FoundAddress {
+                                detail,
+                                address: display_address.clone(),
+                                private_key: format_private_key(&private_key, key_format, hex_case, hex_prefix),
+                                public_key: public_key_hex(output_public_key, &deriver, &private_key, hex_case, hex_prefix),
+                                attempts: addresses_checked.load(Ordering::SeqCst),
+                                found_after: start.elapsed(),
+                            };
+                            
+                            // Every format but the text boxes is streamed to stdout as soon as a match is accepted
+                            let streamed = match output_format {
+                                OutputFormat::Binary => write_binary_record(&found),
+                                OutputFormat::Csv => write_csv_row(&found, &criteria.pattern_label()),
+                                OutputFormat::Line => write_stdout(format!("{}\n", line_record(&found, &delimiter)).as_bytes()),
+                                OutputFormat::Json => write_json_result(&found, results.matches.is_empty()),
+                                OutputFormat::Text if stream_text => {
+                                    // --pick keeps keys off the screen until one is asked for
+                                    let line = text_record(&found, results.matches.len() + 1, !pick, &theme);
+                                    match &progress {
+                                        Some(progress_bar) => progress_bar.suspend(|| write_stdout(line.as_bytes())),
+                                        None => write_stdout(line.as_bytes()),
+                                    }
+                                }
+                                OutputFormat::Text => Ok(()),
+                            };
+                            if let Err(e) = streamed {
+                                eprintln!("\n{error}⚠️  Failed to write {:?} output: {}{reset}", output_format, e);
+                                break;
+                            }
+                            
+                            if let Some(webhook) = &webhook {
+                                webhook.post(&found, &criteria.pattern_label(), criteria.position_label());
+                            }
+                            
+                            // Record the match, keeping the key only while it is among the newest
+                            results.seen.insert(address_bytes);
+                            results.matches.push(MatchRecord::of(address_bytes, &found));
+                            results.tail.push_back(found);
+                            if results.tail.len() > DISPLAY_TAIL {
+                                results.tail.pop_front();
+                            }
+                            results.matches.len()
+                        };
+                        // Publish outside the lock with fetch_max: a plain store from a slower worker
+                        // could lower the count after a faster one reached the limit, and workers
+                        // that never see the limit would keep searching after the results are full
+                        found_count.fetch_max(total, Ordering::SeqCst);
+                        
+                        if let Some(log) = &audit_log {
+                            let attempts = addresses_checked.load(Ordering::SeqCst);
+                            if let Err(e) = log.record(&criteria.pattern_label(), criteria.position_label(), &display_address, attempts) {
+                                eprintln!("\n{error}⚠️  Failed to write audit log: {}{reset}", e);
+                            }
+                        }
+                        
+                        // Let's find at most `limit` addresses
+                        if total >= limit {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        
+        handles.push(handle);
+    }
+    
+    // Wait for every worker to stop. Each one finishes the key in hand first, so a match
+    // found just as the results filled up is either in them or turned away, never half
+    // recorded, and its audit log entry is written before the results are handed back.
+    // A deterministic worker must also have stopped before its position is saved.
+    for handle in handles {
+        let _ = handle.await;
+    }
+    if let Some(coordinator) = &options.coordinator {
+        if let Err(e) = coordinator.give_back() {
+            eprintln!("{error}⚠️  Failed to save the coordinator position: {}{reset}", e);
+        }
+    }
+    if let Some(webhook) = &options.webhook {
+        webhook.wait_delivered();
+    }
+    
+    // Stop the aggregator and clear the progress bar before returning
+    searching.store(false, Ordering::SeqCst);
+    let rate = aggregator.join().unwrap_or_default();
+    if let Some(pause_listener) = pause_listener {
+        let _ = pause_listener.join();
+    }
+    if let Some(progress_bar) = &progress {
+        progress_bar.finish_and_clear();
+    }
+    #[cfg(feature = "thermal")]
+    if let Some(held) = thermal_watcher.and_then(|watcher| watcher.join().ok()).filter(|held| !held.is_zero()) {
+        eprintln!("{label}🌡️  Workers paused {:.1}s in total to let the CPU cool{reset}", held.as_secs_f64());
+    }
+    
+    if text_output {
+        println!();  // Add a newline for spacing
+    }
+    
+    // Every worker has stopped, so the results can be taken out of the lock
+    let FoundSoFar { matches, tail, .. } = std::mem::take(&mut *results.lock().unwrap());
+    if cancelled.load(Ordering::SeqCst) {
+        eprintln!("{label}⏹️  Search stopped with Ctrl+C after {} of {} matches{reset}", matches.len(), limit);
+    }
+    SearchRun {
+        results: tail.into(),
+        matches,
+        attempts: addresses_checked.load(Ordering::SeqCst),
+        elapsed: start.elapsed(),
+        rate,
+    }
+}
+
+// --verify-on-find: check a match with verify_key_address_pair_with_hash, which derives the
+// address through fuel-crypto instead of the secp256k1 context the workers use. A disagreement
+// means the search would hand out a key that doesn't own its address, so nothing more is recorded.
+fn verify_found(private_key: &[u8; 32], address: &str, hash: AddressHash, theme: &Theme) {
+    let Err(message) = check_found(private_key, address, hash) else {
+        return;
+    };
+    let Theme { error, reset, .. } = *theme;
+    // A search started from the prompt may still have the terminal in raw mode
+    let _ = crossterm::terminal::disable_raw_mode();
+    eprintln!("\n{error}❌ --verify-on-find: {}.{reset}", message);
+    eprintln!("{error}   This is a derivation bug; the match was not recorded and the search was stopped.{reset}");
+    std::process::exit(EXIT_DERIVATION_FAILURE);
+}
+
+// The --verify-on-find check of one match, with the reason when it fails
+fn check_found(private_key: &[u8; 32], address: &str, hash: AddressHash) -> std::result::Result<(), String> {
+    if verify_key_address_pair_with_hash(&prefixed_hex(private_key), address, hash) {
+        Ok(())
+    } else {
+        Err(format!("the key of match {} derives a different address", address))
+    }
+}
+
+// --pick: list the addresses of an interactive search without their keys, then show or save
+// only the one asked for. "2" shows result #2; "2 wallet.txt" writes it to a new file as a
+// private_key,address line, the form `verify --input` reads, without printing the key.
+fn pick_result(editor: &mut Editor<ReplHelper, DefaultHistory>, results: &[FoundAddress], options: &SearchOptions) -> rustyline::Result<()> {
+    let Theme { accent, label, address: address_color, error, reset, .. } = options.theme;
+    println!("\n{accent}✅ Found {} matching addresses!{reset}\n", results.len());
+    for (i, found) in results.iter().enumerate() {
+        println!("  {label}#{:<4}{reset} {address_color}{}{reset}", i + 1, found.address);
+    }
+    println!("\n{label}Enter a number to show that key, a number and a file name to save it, or nothing to skip{reset}");
+    
+    loop {
+        // Picks stay out of the history, which is saved to disk
+        let line = match editor.readline(&format!("{accent}Pick #>{reset} ")) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(());
+        }
+        let (index, path) = line.split_once(char::is_whitespace).map_or((line, ""), |(index, path)| (index, path.trim()));
+        let Some(found) = index.trim_start_matches('#').parse::<usize>().ok().and_then(|i| results.get(i.wrapping_sub(1))) else {
+            println!("{error}Pick a number from 1 to {}{reset}", results.len());
+            continue;
+        };
+        
+        if path.is_empty() {
+            warn_if_shared_terminal(std::slice::from_ref(found), options);
+            display_results(std::slice::from_ref(found), options);
+            return Ok(());
+        }
+        match export_result(found, Path::new(path)) {
+            Ok(()) => {
+                println!("{accent}💾 Saved {} and its key to {}{reset}", found.address, path);
+                return Ok(());
+            }
+            Err(e) => println!("{error}⚠️  {}{reset}", with_path(e, Path::new(path))),
+        }
+    }
+}
+
+// Write one result to a file that must not exist yet, readable only by its owner on Unix
+fn export_result(found: &FoundAddress, path: &Path) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    writeln!(file, "{},{}", found.private_key, found.address)
+}
+
+// Helper function to display results
+fn display_results(results: &[FoundAddress], options: &SearchOptions) {
+    display_tail(results, results.len(), options);
+}
+
+// Display the last results of a search that found `found` matches in all. Those before
+// them were listed as they were found, so the boxes keep their numbers.
+fn display_tail(results: &[FoundAddress], found: usize, options: &SearchOptions) {
+    let first = found - results.len() + 1;
+    let Theme { accent, label, address: address_color, key, error, reset, .. } = options.theme;
+    let shown = |address: &str| match options.group {
+        Some(size) => group_digits(address, size, &options.group_separator),
+        None => address.to_string(),
+    };
+    if !results.is_empty() {
+        println!("\n{accent}✅ Found {} matching addresses!{reset}", found);
+        if first > 1 {
+            println!("{label}Showing the last {}; all {} are listed above{reset}", results.len(), found);
+        }
+        
+        // The boxes follow the terminal width; output that isn't a terminal gets the default
+        let columns = atty::is(atty::Stream::Stdout)
+            .then(|| crossterm::terminal::size().ok())
+            .flatten()
+            .map(|(columns, _)| columns as usize);
+        // One field per line when the boxes would wrap and break apart
+        let Some(width) = result_wrap_width(columns) else {
+            for (i, FoundAddress { address, private_key, public_key, detail, .. }) in results.iter().enumerate() {
+                println!("\n{accent}#{}{reset}", first + i);
+                println!("{label}📫 Address:{reset} {address_color}{}{reset}", shown(address));
+                println!("{label}🔑 Private Key:{reset} {key}{}{reset}", private_key);
+                if let Some(public_key) = public_key {
+                    println!("{label}🔓 Public Key:{reset} {}", public_key);
+                }
+                if let Some(detail) = detail {
+                    println!("{label}🎯 Matched:{reset} {}", detail);
+                }
+            }
+            return;
+        };
+        
+        let rule = |left: &str, right: &str| format!("{accent}{}{}{}{reset}", left, "═".repeat(width + 2), right);
+        // Pad by displayed width, which leaves out color codes and counts emoji as two columns
+        let row = |text: &str| {
+            let padding = width.saturating_sub(textwrap::core::display_width(text));
+            println!("{accent}║{reset} {}{} {accent}║{reset}", text, " ".repeat(padding));
+        };
+        let wrapped = |text: &str, color: &str| {
+            for line in textwrap::fill(text, width).lines() {
+                row(&format!("{color}{}{reset}", line));
+            }
+        };
+        
+        println!("\n{}", rule("╔", "╗"));
+        row(&format!("{accent}{:^width$}{reset}", "MATCHING ADDRESSES"));
+        println!("{}", rule("╚", "╝"));
+        
+        for (i, result) in results.iter().enumerate() {
+            let FoundAddress { address, private_key, public_key, detail, .. } = result;
+            println!("{}", rule("╔", "╗"));
+            row(&format!("{accent}#{}{reset}", first + i));
+            println!("{}", rule("╠", "╣"));
+            row(&format!("{label}📫 Address:{reset}"));
+            wrapped(&shown(address), address_color);
+            
+            println!("{}", rule("╠", "╣"));
+            row(&format!("{label}🔑 Private Key:{reset}"));
+            wrapped(private_key, key);
+            
+            if let Some(public_key) = public_key {
+                println!("{}", rule("╠", "╣"));
+                row(&format!("{label}🔓 Public Key:{reset}"));
+                wrapped(public_key, "");
+            }
+            
+            if let Some(detail) = detail {
+                println!("{}", rule("╠", "╣"));
+                row(&format!("{label}🎯 Matched:{reset}"));
+                wrapped(detail, "");
+            }
+            
+            println!("{}", rule("╚", "╝"));
+        }
+    } else {
+        println!("\n{error}❌ No matching addresses found within the search limit.{reset}");
+    }
+}
+
+// Split the digits of an address into groups of `size` for reading, e.g. with a space:
+// 0x cafe 0000 dead beef ...
+fn group_digits(address: &str, size: usize, separator: &str) -> String {
+    let (prefix, digits) = match address.strip_prefix("0x") {
+        Some(digits) => ("0x", digits),
+        None => ("", address),
+    };
+    let groups: Vec<String> = digits
+        .chars()
+        .collect::<Vec<_>>()
+        .chunks(size)
+        .map(|group| group.iter().collect())
+        .collect();
+    let grouped = groups.join(separator);
+    if prefix.is_empty() { grouped } else { format!("{}{}{}", prefix, separator, grouped) }
+}
+
+// Nearest-rank percentile of sorted samples: the smallest value at least `p` percent of
+// the samples are less than or equal to
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+// Print the spread of the time between consecutive matches (the first is timed from the
+// start of the search). The average rate hides how uneven these gaps are.
+fn display_latency(matches: &[MatchRecord], theme: &Theme) {
+    let Theme { label, reset, .. } = *theme;
+    if matches.is_empty() {
+        return;
+    }
+    
+    let mut found_at: Vec<Duration> = matches.iter().map(|found| found.found_after).collect();
+    found_at.sort();
+    let mut gaps: Vec<Duration> = found_at
+        .iter()
+        .scan(Duration::ZERO, |previous, &at| {
+            let gap = at.saturating_sub(*previous);
+            *previous = at;
+            Some(gap)
+        })
+        .collect();
+    gaps.sort();
+    
+    eprintln!(
+        "{label}⏱️  Time to find, over {} matches:{reset} min {:.3}s, median {:.3}s, p90 {:.3}s, max {:.3}s",
+        gaps.len(),
+        gaps[0].as_secs_f64(),
+        percentile(&gaps, 50.0).as_secs_f64(),
+        percentile(&gaps, 90.0).as_secs_f64(),
+        gaps[gaps.len() - 1].as_secs_f64(),
+    );
+}
+
+// Write the attempts each match took, as counted when it was found, to `path`. The gaps
+// should be geometrically distributed around the expected keys per match, so the mean is
+// compared against it on stderr when the criteria allow an estimate.
+fn write_count_distribution(path: &Path, matches: &[MatchRecord], criteria: &SearchCriteria, options: &SearchOptions) {
+    let Theme { label, error, reset, .. } = options.theme;
+    let mut attempts: Vec<usize> = matches.iter().map(|found| found.attempts).collect();
+    attempts.sort();
+    let gaps: Vec<usize> = attempts
+        .iter()
+        .scan(0, |previous, &at| {
+            let gap = at.saturating_sub(*previous);
+            *previous = at;
+            Some(gap)
+        })
+        .collect();
+    
+    let mut rows = String::from("match,attempts,gap\n");
+    for (index, (at, gap)) in attempts.iter().zip(&gaps).enumerate() {
+        rows.push_str(&format!("{},{},{}\n", index + 1, at, gap));
+    }
+    if let Err(e) = std::fs::write(path, rows) {
+        eprintln!("{error}⚠️  Failed to write count distribution: {}{reset}", e);
+        return;
+    }
+    
+    if gaps.is_empty() {
+        return;
+    }
+    let mean = gaps.iter().sum::<usize>() as f64 / gaps.len() as f64;
+    match criteria.keys_per_match(options.case_sensitive) {
+        Some(expected) => eprintln!(
+            "{label}📊 Attempts per match, over {} matches:{reset} mean {:.0}, expected {:.0} ({})",
+            gaps.len(), mean, expected, path.display()
+        ),
+        None => eprintln!(
+            "{label}📊 Attempts per match, over {} matches:{reset} mean {:.0} ({})",
+            gaps.len(), mean, path.display()
+        ),
+    }
+}
+
+// Name of the multiplexer or remote session the output may be recorded by, if any
+fn shared_terminal() -> Option<&'static str> {
+    [("TMUX", "tmux"), ("STY", "screen"), ("SSH_TTY", "an SSH session")]
+        .into_iter()
+        .find(|(var, _)| std::env::var_os(var).is_some())
+        .map(|(_, name)| name)
+}
+
+// Warn once per run before private keys are printed into a terminal that keeps scrollback
+fn warn_if_shared_terminal(results: &[FoundAddress], options: &SearchOptions) {
+    if results.is_empty() || options.shared_terminal_warned.load(Ordering::SeqCst) {
+        return;
+    }
+    
+    if let Some(terminal) = shared_terminal() {
+        options.shared_terminal_warned.store(true, Ordering::SeqCst);
+        let Theme { error, reset, .. } = options.theme;
+        eprintln!("\n{error}⚠️  Running inside {}: the private keys below will stay visible in its scrollback and logs.{reset}", terminal);
+        eprintln!("   Clear the scrollback after saving them. Pass --i-understand to hide this warning.");
+    }
+}
+
+// Size of one record written by --output-format binary: address bytes, then key bytes
+const BINARY_RECORD_LEN: usize = 64;
+
+// Write one result as a fixed-size binary record with no delimiters
+fn write_binary_record(result: &FoundAddress) -> io::Result<()> {
+    let invalid = |e: hex::FromHexError| io::Error::new(io::ErrorKind::InvalidData, e);
+    // Decoded in place, so streaming a large batch doesn't allocate per record
+    let mut record = [0u8; BINARY_RECORD_LEN];
+    let (address, key) = record.split_at_mut(BINARY_RECORD_LEN / 2);
+    hex::decode_to_slice(result.address.trim_start_matches("0x"), address).map_err(invalid)?;
+    hex::decode_to_slice(result.private_key.trim_start_matches("0x"), key).map_err(invalid)?;
+    
+    write_stdout(&record)
+}
+
+// Write streamed output to stdout and flush it, so it can be read while the search is running
+fn write_stdout(bytes: &[u8]) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    let written = stdout.write_all(bytes).and_then(|_| stdout.flush());
+    if matches!(&written, Err(e) if e.kind() == io::ErrorKind::BrokenPipe) {
+        // The reader has everything it wanted
+        std::process::exit(EXIT_FOUND);
+    }
+    written
+}
+
+// Write the CSV header row: address, private key, the public key with --output-public-key,
+// then the pattern and the number of keys checked when the match was found
+fn write_csv_header(public_key: bool) -> io::Result<()> {
+    let mut columns = vec!["address", "private_key"];
+    if public_key {
+        columns.push("public_key");
+    }
+    columns.extend(["pattern", "attempts"]);
+    write_csv_record(&columns)
+}
+
+// Write one result as a CSV row matching `write_csv_header`
+fn write_csv_row(found: &FoundAddress, pattern: &str) -> io::Result<()> {
+    let attempts = found.attempts.to_string();
+    let mut fields = vec![found.address.as_str(), found.private_key.as_str()];
+    if let Some(public_key) = &found.public_key {
+        fields.push(public_key);
+    }
+    fields.extend([pattern, attempts.as_str()]);
+    write_csv_record(&fields)
+}
+
+// Rows are flushed one at a time so they can be read while the search is still running
+fn write_csv_record(fields: &[&str]) -> io::Result<()> {
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(io::stdout().lock());
+    let written = writer.write_record(fields).map_err(io::Error::from).and_then(|_| writer.flush());
+    if matches!(&written, Err(e) if e.kind() == io::ErrorKind::BrokenPipe) {
+        // The reader has everything it wanted
+        std::process::exit(EXIT_FOUND);
+    }
+    written
+}
+
+// Print each result as ADDRESS<delimiter>PRIVATEKEY, one per line, for cut and awk, with
+// <delimiter>PUBLICKEY appended when public keys were asked for
+fn write_lines(results: &[FoundAddress], delimiter: &str) {
+    for found in results {
+        println!("{}", line_record(found, delimiter));
+    }
+}
+
+// One line of `write_lines`, without the newline
+fn line_record(found: &FoundAddress, delimiter: &str) -> String {
+    match &found.public_key {
+        Some(public_key) => format!("{}{}{}{}{}", found.address, delimiter, found.private_key, delimiter, public_key),
+        None => format!("{}{}{}", found.address, delimiter, found.private_key),
+    }
+}
+
+// The line a text search with a large --count prints for each match as it is found
+fn text_record(found: &FoundAddress, number: usize, show_key: bool, theme: &Theme) -> String {
+    let Theme { label, address, key, reset, .. } = *theme;
+    if show_key {
+        format!("{label}#{:<4}{reset} {address}{}{reset} {key}{}{reset}\n", number, found.address, found.private_key)
+    } else {
+        format!("{label}#{:<4}{reset} {address}{}{reset}\n", number, found.address)
+    }
+}
+
+// Version of the --output-format json structure. Bump it whenever a field is renamed,
+// removed or changes meaning; adding a field keeps the version.
+const JSON_SCHEMA_VERSION: u32 = 1;
+
+fn json_results(results: &[FoundAddress]) -> Vec<serde_json::Value> {
+    results.iter().map(json_result).collect()
+}
+
+fn json_result(found: &FoundAddress) -> serde_json::Value {
+    let mut result = serde_json::json!({
+        "address": found.address,
+        "private_key": found.private_key,
+        "detail": found.detail,
+    });
+    // Left out entirely, not null, unless --output-public-key was given
+    if let Some(public_key) = &found.public_key {
+        result["public_key"] = serde_json::json!(public_key);
+    }
+    result
+}
+
+// The `meta` of a search's JSON report, which holds everything needed to rerun it
+fn json_meta(criteria: &SearchCriteria, options: &SearchOptions, run: &SearchRun) -> serde_json::Value {
+    serde_json::json!({
+        "schema_version": JSON_SCHEMA_VERSION,
+        "pattern": criteria.pattern_label(),
+        "position": criteria.position_label(),
+        "case_sensitive": options.case_sensitive,
+        "hash_algo": options.hash.name(),
+        "format": if options.case_sensitive { "mixed-case hex" } else { "hex" },
+        "hex_case": format!("{:?}", options.hex_case).to_lowercase(),
+        "key_format": format!("{:?}", options.key_format).to_lowercase(),
+        "seed": options.coordinator.as_ref().map(|coordinator| coordinator.seed_hex()),
+        "threads": options.threads,
+        "attempts": run.attempts,
+        "elapsed_secs": run.elapsed.as_secs_f64(),
+        "rate_per_sec": run.rate,
+    })
+}
+
+// A search's JSON report is written in three parts so each result can be streamed when it
+// is found: the opening of the object and its `results` array, one element per match, and
+// once the search is over, the end of the array and the `meta` that needs the totals
+fn write_json_head() -> io::Result<()> {
+    write_stdout(b"{\n  \"results\": [")
+}
+
+fn write_json_result(found: &FoundAddress, first: bool) -> io::Result<()> {
+    let result = serde_json::to_string_pretty(&json_result(found))?.replace('\n', "\n    ");
+    write_stdout(format!("{}\n    {}", if first { "" } else { "," }, result).as_bytes())
+}
+
+fn write_json_report(criteria: &SearchCriteria, options: &SearchOptions, run: &SearchRun) -> io::Result<()> {
+    let meta = serde_json::to_string_pretty(&json_meta(criteria, options, run))?.replace('\n', "\n  ");
+    write_stdout(format!("\n  ],\n  \"meta\": {}\n}}\n", meta).as_bytes())
+}
+
+// Show what a matching address looks like instead of searching
+fn display_preview(criteria: &SearchCriteria, options: &SearchOptions) {
+    let Theme { label, address, reset, .. } = options.theme;
+    let preview = criteria.preview(options.case_sensitive, options.hex_case);
+    println!("\n{label}🔎 Example of a matching address ({}):{reset}", criteria.position_label());
+    println!("   {address}{}{reset}", preview);
+    if criteria.matches(&preview, options.case_sensitive) {
+        if let Some(detail) = criteria.describe_match(&preview) {
+            println!("   Matched: {}", detail);
+        }
+    }
+}
+
+// Query the node given with --check-balance for every found address. A hit is practically
+// impossible for freshly generated keys; RPC failures are reported but never fail the run.
+#[cfg(feature = "network")]
+async fn check_balances(matches: &[MatchRecord], options: &SearchOptions) {
+    check_balances_with(matches, options, |line| eprintln!("{}", line)).await;
+}
+
+// `check_balances`, handing each line of the report to `report` as soon as it is known
+#[cfg(feature = "network")]
+async fn check_balances_with(matches: &[MatchRecord], options: &SearchOptions, mut report: impl FnMut(&str)) {
+    use fuels::accounts::provider::Provider;
+    use fuels::types::{bech32::Bech32Address, Address};
+    
+    let Some(url) = &options.check_balance else {
+        return;
+    };
+    if matches.is_empty() {
+        return;
+    }
+    let Theme { error, reset, .. } = options.theme;
+    
+    let retries = options.balance_retries;
+    let provider = match with_backoff(retries, || Provider::connect(url)).await {
+        Ok(provider) => provider,
+        Err(e) => {
+            report(&format!("{error}⚠️  Balance check skipped, could not connect to {}: {}{reset}", url, e));
+            return;
+        }
+    };
+    
+    for found in matches {
+        let owner = Bech32Address::from(Address::new(found.address));
+        
+        let outcome = with_backoff(retries, || provider.get_balances(&owner)).await;
+        for line in balance_report(&prefixed_hex(&found.address), outcome, &options.theme) {
+            report(&line);
+        }
+    }
+}
+
+// The lines reporting what a balance query returned for one address
+#[cfg(feature = "network")]
+fn balance_report<B, K, V, E>(address: &str, outcome: std::result::Result<B, E>, theme: &Theme) -> Vec<String>
+where
+    B: IntoIterator<Item = (K, V)>,
+    K: fmt::Display,
+    V: fmt::Display,
+    E: fmt::Display,
+{
+    let Theme { accent, error, reset, .. } = *theme;
+    let balances: Vec<String> = match outcome {
+        Ok(balances) => balances.into_iter().map(|(asset_id, amount)| format!("   {}: {}", asset_id, amount)).collect(),
+        Err(e) => return vec![format!("{error}⚠️  Balance check failed for {}: {}{reset}", address, e)],
+    };
+    if balances.is_empty() {
+        return vec![format!("{accent}✔ {} has no balance{reset}", address)];
+    }
+
+    let mut lines = vec![format!("{error}⚠️  {} already holds {} asset(s) on this network!{reset}", address, balances.len())];
+    lines.extend(balances);
+    lines
+}
+
+// Delay before the first retry of a failed balance RPC call; it doubles after every failure
+#[cfg(feature = "network")]
+const BALANCE_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+// Run an RPC call, retrying up to `retries` times with exponential backoff. The last error
+// is returned once the retries are used up, so the caller decides how to report it.
+#[cfg(feature = "network")]
+async fn with_backoff<T, E, F, Fut>(retries: u32, mut call: F) -> std::result::Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = std::result::Result<T, E>>,
+{
+    let mut delay = BALANCE_RETRY_DELAY;
+    let mut attempt = 0;
+    loop {
+        match call().await {
+            Err(_) if attempt < retries => {
+                attempt += 1;
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            outcome => return outcome,
+        }
+    }
+}
+
+#[cfg(not(feature = "network"))]
+async fn check_balances(_matches: &[MatchRecord], _options: &SearchOptions) {}
+
+// Function to validate a pattern for hex characters
+fn is_valid_hex_pattern(pattern: &str) -> bool {
+    pattern.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+// Function to check and warn about non-hex characters. Characters outside printable ASCII
+// are left to validate_ascii, which rejects the pattern without echoing them.
+fn warn_if_invalid_hex(pattern: &str) -> bool {
+    if !is_valid_hex_pattern(pattern) && validate_ascii(pattern).is_ok() {
+        eprintln!("\n WARNING: Your pattern contains non-hexadecimal characters!");
+        eprintln!("   Fuel addresses can only contain characters: 0-9, a-f");
+        eprintln!("   The search may run indefinitely without finding a match.\n");
+        
+        // List the invalid characters
+        let invalid_chars: Vec<char> = pattern.chars().filter(|c| !c.is_ascii_hexdigit()).collect();
+        eprintln!("   Invalid characters in your pattern: {:?}", invalid_chars);
+        eprintln!("   Consider using only hexadecimal characters for a successful search.\n");
+        
+        return false;
+    }
+    true
+}
+
+// Function to display banner
+fn display_banner(theme: &Theme) {
+    let Theme { accent, reset, .. } = *theme;
+    println!("\n\
+{accent}██╗███████╗██╗   ██╗███████╗██╗     {reset}\n\
+{accent}██║██╔════╝██║   ██║██╔════╝██║     {reset}\n\
+{accent}██║█████╗  ██║   ██║█████╗  ██║     {reset}\n\
+{accent}██║██╔══╝  ██║   ██║██╔══╝  ██║     {reset}\n\
+{accent}██║██║     ╚██████╔╝███████╗███████╗{reset}\n\
+{accent}╚═╝╚═╝      ╚═════╝ ╚══════╝╚══════╝{reset}\n\
+    ");
+    println!("{accent}════════════════════════════════════════════════════{reset}");
+    println!("{accent}⚡ Fuel Vanity Address Generator v1.0.0 ⚡{reset}");
+    println!("{accent}════════════════════════════════════════════════════{reset}");
+    println!("  Created by Ban (https://x.com/ohbannedOS)");
+    println!("  https://github.com/ohbanned/Fuel-Vanity-Address-Generator");
+    println!("{accent}════════════════════════════════════════════════════{reset}\n");
+}
+
+// Function to display help
+fn display_help(theme: &Theme) {
+    let Theme { accent, reset, .. } = *theme;
+    println!("\n{accent}╔════════════════════════════════════════════╗");
+    println!("║           iFuel COMMAND REFERENCE          ║");
+    println!("╚════════════════════════════════════════════╝{reset}");
+    println!("{accent}┌────────────────────────────────────────────┐{reset}");
+    println!("{accent}│{reset} 🔍 GENERATION COMMANDS:                    {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  prefix <pattern>                          {accent}│{reset}");
+    println!("{accent}│{reset}    Generate addresses with specified prefix{accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  suffix <pattern>                          {accent}│{reset}");
+    println!("{accent}│{reset}    Generate addresses with specified suffix{accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  contains <pattern> [min-length]           {accent}│{reset}");
+    println!("{accent}│{reset}    Generate addresses containing pattern   {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  edges <digit> <count>                     {accent}│{reset}");
+    println!("{accent}│{reset}    Start or end with a run of one digit    {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  sequence <asc|desc> <length> [position]   {accent}│{reset}");
+    println!("{accent}│{reset}    Digits counting up or down, e.g. 0123   {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  decimal-contains <digits>                 {accent}│{reset}");
+    println!("{accent}│{reset}    Decimal value contains digits (slow)    {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  multi-at <offset:pattern>...              {accent}│{reset}");
+    println!("{accent}│{reset}    Patterns at fixed nibble offsets        {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  mask <pattern>                            {accent}│{reset}");
+    println!("{accent}│{reset}    Prefix where ? matches any digit        {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  dual <hex> <bech32>                       {accent}│{reset}");
+    println!("{accent}│{reset}    Hex and bech32 prefixes together (slow) {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  dual-scheme <pattern>                     {accent}│{reset}");
+    println!("{accent}│{reset}    Fuel and Ethereum prefix (slow)         {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  checksum-word <word>                      {accent}│{reset}");
+    println!("{accent}│{reset}    Word in the checksum casing (slow)      {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  modulo <divisor> <remainder>              {accent}│{reset}");
+    println!("{accent}│{reset}    Address value mod divisor = remainder   {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  budget <seconds> [digit]                  {accent}│{reset}");
+    println!("{accent}│{reset}    Longest prefix that fits the time       {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  date [YYYYMMDD]                           {accent}│{reset}");
+    println!("{accent}│{reset}    Prefix of a date, today's by default    {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  climb <prefix>                            {accent}│{reset}");
+    println!("{accent}│{reset}    Ever longer prefixes until Ctrl+C       {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  sample <count>                            {accent}│{reset}");
+    println!("{accent}│{reset}    Random keypairs, no pattern             {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  verify <file>                             {accent}│{reset}");
+    println!("{accent}│{reset}    Check private_key,address lines         {accent}│{reset}");
+    println!("{accent}└────────────────────────────────────────────┘{reset}");
+    println!("{accent}┌────────────────────────────────────────────┐{reset}");
+    println!("{accent}│{reset} ⚙️  OPTIONS:                               {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  -s, --case-sensitive                      {accent}│{reset}");
+    println!("{accent}│{reset}    Enable case-sensitive pattern matching  {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  -t, --threads <number>                    {accent}│{reset}");
+    println!("{accent}│{reset}    Set number of worker threads            {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  --auto-threads                            {accent}│{reset}");
+    println!("{accent}│{reset}    Benchmark and pick the fastest count    {accent}│{reset}");
+    println!("{accent}└────────────────────────────────────────────┘{reset}");
+    println!("{accent}┌────────────────────────────────────────────┐{reset}");
+    println!("{accent}│{reset} 📋 EXAMPLES:                               {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  prefix abc                                {accent}│{reset}");
+    println!("{accent}│{reset}    Generate addresses starting with 'abc'  {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  suffix cafe -s                            {accent}│{reset}");
+    println!("{accent}│{reset}    Generate case-sensitive address ending  {accent}│{reset}");
+    println!("{accent}│{reset}    with 'cafe'                             {accent}│{reset}");
+    println!("{accent}└────────────────────────────────────────────┘{reset}");
+    println!("{accent}┌────────────────────────────────────────────┐{reset}");
+    println!("{accent}│{reset} 🛠️  UTILITY COMMANDS:                      {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  info                                      {accent}│{reset}");
+    println!("{accent}│{reset}    Display this help message               {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  stats                                     {accent}│{reset}");
+    println!("{accent}│{reset}    Show environment and hash rate          {accent}│{reset}");
+    println!("{accent}│{reset}                                            {accent}│{reset}");
+    println!("{accent}│{reset}  exit                                      {accent}│{reset}");
+    println!("{accent}│{reset}    Exit the program                        {accent}│{reset}");
+    println!("{accent}└────────────────────────────────────────────┘{reset}");
+}
+
+// Function to display an environment report for bug reports and comparing machines. With
+// JSON output the same fields go under `stats`, next to the usual `meta`.
+fn display_stats(options: &SearchOptions) {
+    let Theme { accent, label, emphasis, reset, .. } = options.theme;
+    let json = options.output_format == OutputFormat::Json;
+    if json {
+        eprintln!("🔍 Measuring single-core rate...");
+    } else {
+        println!("🔍 Measuring single-core rate...");
+    }
+    let rate = measure_key_rate(1, Duration::from_millis(CALIBRATION_MS));
+    
+    if json {
+        let report = serde_json::json!({
+            "meta": {
+                "schema_version": JSON_SCHEMA_VERSION,
+                "command": "stats",
+                "hash_algo": options.hash.name(),
+            },
+            "stats": {
+                "version": env!("CARGO_PKG_VERSION"),
+                "cpu_cores": num_cpus::get(),
+                "hash": format!("{} of secp256k1 public key", options.hash.label()),
+                "address": "0x + 64 hex digits (32 bytes)",
+                "keys_per_sec_one_core": rate,
+            },
+        });
+        println!("{:#}", report);
+        return;
+    }
+    
+    println!("\n{accent}╔════════════════════════════════════════════╗");
+    println!("║             iFuel ENVIRONMENT              ║");
+    println!("╚════════════════════════════════════════════╝{reset}");
+    println!("{accent}┌────────────────────────────────────────────┐{reset}");
+    println!("{accent}│{reset} {label}Version:{reset} {:<33} {accent}│{reset}", env!("CARGO_PKG_VERSION"));
+    println!("{accent}│{reset} {label}CPU cores:{reset} {:<31} {accent}│{reset}", num_cpus::get());
+    println!("{accent}│{reset} {label}Hash:{reset} {:<36} {accent}│{reset}", format!("{} of secp256k1 public key", options.hash.label()));
+    println!("{accent}│{reset} {label}Address:{reset} {:<33} {accent}│{reset}", "0x + 64 hex digits (32 bytes)");
+    println!("{accent}│{reset} {label}Rate (1 core):{reset} {emphasis}{:<27}{reset} {accent}│{reset}", format!("{} keys/s", HumanCount(rate as u64)));
+    println!("{accent}└────────────────────────────────────────────┘{reset}");
+}
+
+// Longest prefix length whose expected time to find `count` matches fits the budget.
+// Each extra hex digit makes a match 16 times rarer; at least one digit is always used.
+fn budget_pattern_length(budget_secs: f64, rate: f64, count: usize) -> usize {
+    let attempts = budget_secs * rate / count as f64;
+    if attempts < 16.0 {
+        return 1;
+    }
+    (attempts.log(16.0).floor() as usize).min(ADDRESS_HEX_LEN)
+}
+
+// An expected search time in words, for estimates that may be absurdly long
+fn describe_secs(secs: f64) -> String {
+    Duration::try_from_secs_f64(secs)
+        .map_or_else(|_| "longer than the universe has existed".to_string(), |time| HumanDuration(time).to_string())
+}
+
+// Digit separators stop being readable long before the counts of long patterns end
+fn describe_keys(keys: f64) -> String {
+    if keys < 1e15 { HumanCount(keys as u64).to_string() } else { format!("{:.1e}", keys) }
+}
+
+// One line with the expected cost of a pattern search, printed by the prompt before it
+// starts so a hopeless search can be abandoned right away
+fn display_estimate(keys_per_match: f64, rate: f64, options: &SearchOptions) {
+    let Theme { label, reset, .. } = options.theme;
+    let secs = keys_per_match * options.count as f64 / rate;
+    println!(
+        "{label}⏱️  About {} keys per match: {} for {} at {} keys/s. Ctrl+C stops it.{reset}",
+        describe_keys(keys_per_match), describe_secs(secs), options.count, HumanCount(rate as u64)
+    );
+}
+
+// Finding every match of a command-line search is expected to take longer than this, the
+// search is treated as one that would seem to hang
+const FEASIBLE_SECS: f64 = 24.0 * 60.0 * 60.0;
+
+// More keys per second than one thread derives, so the rate is only measured for searches
+// that might not fit in FEASIBLE_SECS
+const MAX_THREAD_RATE: f64 = 1_000_000.0;
+
+// Warn when --count matches of a pattern are expected to take more than a day, naming the
+// count that fits; with --auto-cap, lower the count to it instead
+fn check_feasibility(cmd: &Command, options: &mut SearchOptions, auto_cap: bool) {
+    let Theme { accent, error, reset, .. } = options.theme;
+    let Some(keys) = search_criteria(cmd, options.network).and_then(|(_, criteria)| criteria.keys_per_match(options.case_sensitive)) else {
+        return;
+    };
+    let count = options.count as f64;
+    if options.count <= 1 || keys * count <= FEASIBLE_SECS * MAX_THREAD_RATE * options.threads as f64 {
+        return;
+    }
+    let rate = measure_key_rate(options.threads, Duration::from_millis(CALIBRATION_MS));
+    let Some(feasible) = feasible_count(keys, options.count, rate) else {
+        return;
+    };
+    
+    eprintln!(
+        "{error}⚠️  {} matches at about {} keys each would take {} at {} keys/s.{reset}",
+        options.count, describe_keys(keys), describe_secs(keys * count / rate), HumanCount(rate as u64)
+    );
+    if auto_cap {
+        eprintln!("{accent}⚙️  --auto-cap: looking for {} instead of {} matches{reset}", feasible, options.count);
+        options.count = feasible;
+    } else if keys / rate > FEASIBLE_SECS {
+        eprintln!("{error}   Even one match would take {}; consider a shorter pattern.{reset}", describe_secs(keys / rate));
+    } else {
+        eprintln!("{error}   A day fits about {}: pass --count {}, or --auto-cap to search for that many.{reset}", feasible, feasible);
+    }
+}
+
+// The count of matches a day fits at `rate` keys per second, or None when `count` already fits.
+// Never below one, even when a single match takes longer.
+fn feasible_count(keys: f64, count: usize, rate: f64) -> Option<usize> {
+    (keys * count as f64 / rate > FEASIBLE_SECS).then(|| ((FEASIBLE_SECS * rate / keys) as usize).max(1))
+}
+
+// Turn a budget command into the prefix search it stands for, reporting the choice
+fn resolve_budget(cmd: Command, options: &SearchOptions) -> std::result::Result<Command, String> {
+    let Command::Budget { seconds, digit } = cmd else {
+        return Ok(cmd);
+    };
+    
+    if !(seconds > 0.0 && seconds.is_finite()) {
+        return Err(format!("The budget must be a positive number of seconds, got {}", seconds));
+    }
+    if let Some(digit) = digit {
+        if !digit.is_ascii_hexdigit() {
+            return Err(format!("'{}' is not a hexadecimal digit (0-9, a-f)", digit));
+        }
+    }
+    
+    if options.dry_validate {
+        return Ok(cmd);
+    }
+    
+    let Theme { label, reset, .. } = options.theme;
+    eprintln!("{label}⏱️  Measuring the search rate...{reset}");
+    let rate = measure_key_rate(options.threads, Duration::from_millis(CALIBRATION_MS));
+    let length = budget_pattern_length(seconds, rate, options.count);
+    
+    let pattern: String = match digit {
+        Some(digit) => digit.to_ascii_lowercase().to_string().repeat(length),
+        None => (0..length)
+            .map(|_| char::from_digit(OsRng.next_u32() % 16, 16).unwrap())
+            .collect(),
+    };
+    
+    let expected = 16f64.powi(length as i32) * options.count as f64 / rate;
+    eprintln!(
+        "{label}⏱️  {} keys/s: searching for prefix '{}' ({} digits), expected to take about {:.1}s{reset}",
+        HumanCount(rate as u64), pattern, length, expected
+    );
+    
+    Ok(Command::Prefix { pattern })
+}
+
+// Turn a date command into the prefix search for its YYYYMMDD digits. Decimal digits are all
+// hex digits, so any real date is a valid pattern; what needs checking is that it is a date.
+fn resolve_date(cmd: Command, options: &SearchOptions) -> std::result::Result<Command, String> {
+    let Command::Date { date } = cmd else {
+        return Ok(cmd);
+    };
+    
+    let pattern = match &date {
+        Some(date) => {
+            let digits = date.replace('-', "");
+            let valid = digits.len() == 8 && chrono::NaiveDate::parse_from_str(&digits, "%Y%m%d").is_ok();
+            if !valid {
+                return Err(format!("'{}' is not a date in the form YYYYMMDD", date));
+            }
+            digits
+        }
+        None => chrono::Local::now().format("%Y%m%d").to_string(),
+    };
+    
+    if !options.dry_validate {
+        let Theme { label, reset, .. } = options.theme;
+        let which = if date.is_some() { "the date" } else { "today's date" };
+        eprintln!("{label}📅 Searching for prefix '{}', {}{reset}", pattern, which);
+    }
+    Ok(Command::Prefix { pattern })
+}
+
+// Map a search command to its display name and the criteria the workers check
+fn search_criteria(cmd: &Command, network: Network) -> Option<(&'static str, SearchCriteria)> {
+    let pattern = |pattern: &str, position: &str| SearchCriteria::Pattern {
+        pattern: pattern.to_string(),
+        position: position.to_string(),
+    };
+    
+    match cmd {
+        Command::Prefix { pattern: p } => Some(("Prefix", pattern(p, "prefix"))),
+        Command::Suffix { pattern: p } => Some(("Suffix", pattern(p, "suffix"))),
+        Command::Contains { pattern: p, min_match_length: None } => Some(("Contains", pattern(p, "anywhere"))),
+        Command::Contains { pattern, min_match_length: Some(min_length) } => Some(("Contains", SearchCriteria::Partial {
+            pattern: pattern.clone(),
+            min_length: *min_length,
+        })),
+        Command::Edges { digit, count } => Some(("Edges", SearchCriteria::Edges { digit: *digit, count: *count })),
+        Command::Sequence { direction, length, position } => Some(("Sequence", SearchCriteria::Sequence {
+            direction: *direction,
+            length: *length,
+            position: if position == "contains" { "anywhere".to_string() } else { position.clone() },
+        })),
+        Command::DecimalContains { pattern } => Some(("Decimal", SearchCriteria::Decimal { pattern: pattern.clone() })),
+        Command::MultiAt { constraints } => Some(("Multi-at", SearchCriteria::MultiAt { constraints: constraints.clone() })),
+        Command::DualScheme { pattern } => Some(("Dual-scheme", SearchCriteria::DualScheme { pattern: pattern.clone() })),
+        Command::ChecksumWord { word } => Some(("Checksum word", SearchCriteria::ChecksumWord { word: word.clone() })),
+        Command::Modulo { divisor, remainder } => Some(("Modulo", SearchCriteria::Modulo { divisor: *divisor, remainder: *remainder })),
+        // A mask that doesn't compile is rejected by validate_shape, which names the bad character
+        Command::Mask { pattern } => Some(("Mask", SearchCriteria::Mask {
+            pattern: pattern.clone(),
+            nibbles: compile_mask(pattern).unwrap_or_default(),
+        })),
+        Command::Dual { hex_pattern, bech32_pattern } => Some(("Dual", SearchCriteria::Dual {
+            hex: hex_pattern.clone(),
+            bech32: bech32_pattern.clone(),
+            network,
+        })),
+        _ => None,
+    }
+}
+
+// Show the search configuration box printed before an interactive search
+fn display_search_config(kind: &str, criteria: &SearchCriteria, case_sensitive: bool, theme: &Theme) {
+    let Theme { accent, label, emphasis, reset, .. } = *theme;
+    println!("⚙️  CONFIGURATION:");
+    if box_fits(CONFIG_BOX_WIDTH) {
+        println!("{accent}┌────────────────────────────────────────────┐{reset}");
+        println!("{accent}│{reset} Pattern type: {accent}{:<26}{reset} {accent}│{reset}", kind);
+        println!("{accent}│{reset} Pattern: {label}{:<32}{reset} {accent}│{reset}", criteria.pattern_label());
+        println!("{accent}│{reset} Case-sensitive: {emphasis}{:<23}{reset} {accent}│{reset}", case_sensitive);
+        println!("{accent}└────────────────────────────────────────────┘{reset}");
+    } else {
+        println!("  Pattern type: {accent}{}{reset}", kind);
+        println!("  Pattern: {label}{}{reset}", criteria.pattern_label());
+        println!("  Case-sensitive: {emphasis}{}{reset}", case_sensitive);
+    }
+    println!("🔍 Searching for vanity addresses...");
+    println!("   Press Ctrl+C to stop and return to the prompt...\n");
+}
+
+// Command names offered by tab completion at the interactive prompt
+const REPL_COMMANDS: [&str; 24] = [
+    "prefix", "suffix", "contains", "edges", "sequence", "decimal-contains", "multi-at", "mask", "dual", "dual-scheme", "checksum-word", "modulo", "budget", "date", "climb", "sample", "verify", "help", "info", "stats", "about", "interactive", "exit", "quit",
+];
+
+// Line editor helper completing the command name at the start of the line
+#[derive(Helper, Highlighter, Hinter, Validator)]
+struct ReplHelper;
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let typed = &line[..pos];
+        let word = typed.trim_start();
+        
+        // Only the first word is a command; patterns and digits are free-form
+        if word.contains(char::is_whitespace) {
+            return Ok((pos, Vec::new()));
+        }
+        
+        let candidates = REPL_COMMANDS
+            .iter()
+            .filter(|command| command.starts_with(&word.to_lowercase()))
+            .map(|command| Pair { display: command.to_string(), replacement: format!("{} ", command) })
+            .collect();
+        Ok((typed.len() - word.len(), candidates))
+    }
+}
+
+// Where the interactive prompt keeps its history between sessions
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| Path::new(&home).join(".ifuel_history"))
+}
+
+// Interactive mode
+async fn interactive_mode(options: &SearchOptions) -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let case_sensitive = options.case_sensitive;
+    let theme = &options.theme;
+    let Theme { accent, label, error, reset, .. } = *theme;
+    display_banner(theme);
+    println!("💡 Type 'help' for available commands or 'exit' to quit.");
+    println!();
+    
+    // Measured before the first estimate and kept for the session
+    let mut key_rate = None;
+    
+    let mut editor: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    editor.set_helper(Some(ReplHelper));
+    let history = history_path();
+    if let Some(path) = &history {
+        // A missing history file just means this is the first session
+        let _ = editor.load_history(path);
+    }
+    
+    loop {
+        let command = match editor.readline(&format!("{accent}iFuel>{reset} ")) {
+            Ok(input) => {
+                if !input.trim().is_empty() {
+                    let _ = editor.add_history_entry(input.trim());
+                }
+                parse_input(&input, case_sensitive)
+            },
+            // Ctrl+C drops the current line, Ctrl+D leaves like 'exit'
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => Some(Command::Exit),
+            Err(e) => return Err(e.into()),
+        };
+        
+        match command {
+            Some(Command::Exit) => {
+                if let Some(path) = &history {
+                    if let Err(e) = editor.save_history(path) {
+                        eprintln!("{error}⚠️  Failed to save command history: {}{reset}", e);
+                    }
+                }
+                println!("\n👋 Thank you for using iFuel Vanity Address Generator!");
+                println!("   Visit us at https://github.com/ohbanned/Fuel-Vanity-Address-Generator");
+                break;
+            },
+            Some(cmd) => {
+                let cmd = match resolve_budget(cmd, options).and_then(|cmd| resolve_date(cmd, options)) {
+                    Ok(cmd) => cmd,
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        continue;
+                    }
+                };
+                
+                if let Some((kind, criteria)) = search_criteria(&cmd, options.network) {
+                    if let Err(e) = criteria.validate(options.case_sensitive) {
+                        println!("Error: {}", e);
+                        continue;
+                    }
+                    
+                    if options.format_preview {
+                        display_preview(&criteria, options);
+                        println!();
+                        continue;
+                    }
+                    
+                    display_banner(theme);
+                    display_search_config(kind, &criteria, case_sensitive, theme);
+                    if let Some(keys) = criteria.keys_per_match(case_sensitive) {
+                        let rate = *key_rate.get_or_insert_with(|| measure_key_rate(options.threads, Duration::from_millis(CALIBRATION_MS)));
+                        display_estimate(keys, rate, options);
+                    }
+                    
+                    let SearchRun { results, matches, .. } = search_vanity_address(criteria.clone(), options, true, Arc::default()).await;
+                    if options.pick && results.len() > 1 {
+                        pick_result(&mut editor, &results, options)?;
+                    } else {
+                        warn_if_shared_terminal(&results, options);
+                        display_tail(&results, matches.len(), options);
+                    }
+                    check_balances(&matches, options).await;
+                    if options.latency_stats {
+                        display_latency(&matches, &options.theme);
+                    }
+                    if let Some(path) = &options.count_distribution {
+                        write_count_distribution(path, &matches, &criteria, options);
+                    }
+                    println!();
+                    continue;
+                }
+                
+                // Avoid recursion issue by manually handling each command type
+                match cmd {
+                    Command::Sample { count } => {
+                        sample(count, options)?;
+                    },
+                    Command::Climb { pattern } => {
+                        climb(&pattern, options)?;
+                    },
+                    Command::Verify { input } => {
+                        verify_file(&input, options)?;
+                    },
+                    Command::Info => display_help(&options.theme),
+                    Command::Stats => display_stats(options),
+                    Command::Interactive => println!("{label}ℹ️  You're already in interactive mode{reset}"),
+                    Command::Exit => break,
+                    _ => {}
+                }
+            },
+            None => continue,
+        }
+        
+        println!();
+    }
+    
+    Ok(())
+}
+
+// Function to parse user input
+fn parse_input(input: &str, _case_sensitive: bool) -> Option<Command> {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+    
+    if parts.is_empty() {
+        return None;
+    }
+    
+    match parts[0].to_lowercase().as_str() {
+        "prefix" => {
+            if parts.len() < 2 {
+                println!("Error: 'prefix' command requires a pattern");
+                return None;
+            }
+            
+            let pattern = parts[1].to_string();
+            Some(Command::Prefix { pattern })
+        },
+        "suffix" => {
+            if parts.len() < 2 {
+                println!("Error: 'suffix' command requires a pattern");
+                return None;
+            }
+            
+            let pattern = parts[1].to_string();
+            Some(Command::Suffix { pattern })
+        },
+        "contains" => {
+            if parts.len() < 2 {
+                println!("Error: 'contains' command requires a pattern");
+                return None;
+            }
+            
+            let pattern = parts[1].to_string();
+            let min_match_length = match parts.get(2).map(|n| n.parse::<usize>()) {
+                None => None,
+                Some(Ok(n)) => Some(n),
+                Some(Err(_)) => {
+                    println!("Error: '{}' is not a valid minimum match length", parts[2]);
+                    return None;
+                }
+            };
+            Some(Command::Contains { pattern, min_match_length })
+        },
+        "edges" => {
+            if parts.len() < 3 {
+                println!("Error: 'edges' command requires a hex digit and a run length");
+                return None;
+            }
+            
+            let mut chars = parts[1].chars();
+            let digit = match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    println!("Error: 'edges' expects a single hex digit, got '{}'", parts[1]);
+                    return None;
+                }
+            };
+            let count = match parts[2].parse::<usize>() {
+                Ok(count) => count,
+                Err(_) => {
+                    println!("Error: '{}' is not a valid run length", parts[2]);
+                    return None;
+                }
+            };
+            
+            Some(Command::Edges { digit, count })
+        },
+        "sequence" => {
+            if parts.len() < 3 {
+                println!("Error: 'sequence' command requires asc or desc and a run length");
+                return None;
+            }
+            
+            let direction = match SequenceDirection::from_str(parts[1], true) {
+                Ok(direction) => direction,
+                Err(_) => {
+                    println!("Error: 'sequence' expects asc or desc, got '{}'", parts[1]);
+                    return None;
+                }
+            };
+            let length = match parts[2].parse::<usize>() {
+                Ok(length) => length,
+                Err(_) => {
+                    println!("Error: '{}' is not a valid run length", parts[2]);
+                    return None;
+                }
+            };
+            let position = parts.get(3).unwrap_or(&"prefix").to_string();
+            
+            Some(Command::Sequence { direction, length, position })
+        },
+        "dual" => {
+            if parts.len() < 3 {
+                println!("Error: 'dual' command requires a hex pattern and a bech32 pattern");
+                return None;
+            }
+            
+            let hex_pattern = parts[1].to_string();
+            warn_if_invalid_hex(&hex_pattern);
+            Some(Command::Dual { hex_pattern, bech32_pattern: parts[2].to_string() })
+        },
+        "climb" => {
+            if parts.len() < 2 {
+                println!("Error: 'climb' command requires a starting prefix");
+                return None;
+            }
+            
+            Some(Command::Climb { pattern: parts[1].to_string() })
+        },
+        "sample" => {
+            let count = match parts.get(1).map(|part| part.parse::<usize>()) {
+                Some(Ok(count)) => count,
+                _ => {
+                    println!("Error: 'sample' command requires a number of keypairs");
+                    return None;
+                }
+            };
+            
+            Some(Command::Sample { count })
+        },
+        "date" => Some(Command::Date { date: parts.get(1).map(|part| part.to_string()) }),
+        "verify" => {
+            if parts.len() < 2 {
+                println!("Error: 'verify' command requires a file of private_key,address lines");
+                return None;
+            }
+            
+            Some(Command::Verify { input: PathBuf::from(parts[1]) })
+        },
+        "budget" => {
+            if parts.len() < 2 {
+                println!("Error: 'budget' command requires a number of seconds");
+                return None;
+            }
+            
+            let seconds = match parts[1].parse::<f64>() {
+                Ok(seconds) => seconds,
+                Err(_) => {
+                    println!("Error: '{}' is not a valid number of seconds", parts[1]);
+                    return None;
+                }
+            };
+            let digit = match parts.get(2).map(|part| part.chars().collect::<Vec<_>>()) {
+                None => None,
+                Some(chars) if chars.len() == 1 => Some(chars[0]),
+                Some(_) => {
+                    println!("Error: 'budget' expects a single hex digit, got '{}'", parts[2]);
+                    return None;
+                }
+            };
+            
+            Some(Command::Budget { seconds, digit })
+        },
+        "decimal-contains" => {
+            if parts.len() < 2 {
+                println!("Error: 'decimal-contains' command requires a pattern of digits");
+                return None;
+            }
+            
+            Some(Command::DecimalContains { pattern: parts[1].to_string() })
+        },
+        "dual-scheme" => {
+            if parts.len() < 2 {
+                println!("Error: 'dual-scheme' command requires a pattern");
+                return None;
+            }
+            
+            let pattern = parts[1].to_string();
+            warn_if_invalid_hex(&pattern);
+            Some(Command::DualScheme { pattern })
+        },
+        "checksum-word" => {
+            if parts.len() < 2 {
+                println!("Error: 'checksum-word' command requires a word");
+                return None;
+            }
+            
+            let word = parts[1].to_string();
+            warn_if_invalid_hex(&word);
+            Some(Command::ChecksumWord { word })
+        },
+        "modulo" => {
+            let numbers = match parts[1..].iter().map(|part| part.parse::<u64>()).collect::<Result<Vec<_>, _>>() {
+                Ok(numbers) if numbers.len() == 2 => numbers,
+                _ => {
+                    println!("Error: 'modulo' command requires a divisor and a remainder, e.g. modulo 2 0");
+                    return None;
+                }
+            };
+            
+            Some(Command::Modulo { divisor: numbers[0], remainder: numbers[1] })
+        },
+        "mask" => {
+            if parts.len() < 2 {
+                println!("Error: 'mask' command requires a pattern, e.g. c?fe");
+                return None;
+            }
+            
+            Some(Command::Mask { pattern: parts[1].to_string() })
+        },
+        "multi-at" => {
+            if parts.len() < 2 {
+                println!("Error: 'multi-at' command requires offset:pattern constraints, e.g. 0:cafe 60:cafe");
+                return None;
+            }
+            
+            let mut constraints = Vec::new();
+            for pair in &parts[1..] {
+                match parse_offset_pattern(pair) {
+                    Ok(constraint) => constraints.push(constraint),
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        return None;
+                    }
+                }
+            }
+            
+            Some(Command::MultiAt { constraints })
+        },
+        "help" | "info" => Some(Command::Info),
+        "stats" | "about" => Some(Command::Stats),
+        "exit" | "quit" => Some(Command::Exit),
+        "interactive" => Some(Command::Interactive),
+        _ => {
+            println!("Unknown command: {}", parts[0]);
+            println!("Type 'help' for a list of available commands");
+            None
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    exit_quietly_on_broken_pipe();
+    // Usage errors exit with EXIT_INVALID like every other rejected input, not clap's 2,
+    // which scripts would read as EXIT_INCOMPLETE; --help and --version still exit with 0
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            let _ = e.print();
+            std::process::exit(if e.use_stderr() { EXIT_INVALID } else { EXIT_FOUND });
+        }
+    };
+    if let Err(e) = validate_flags(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(EXIT_INVALID);
+    }
+    let mut options = match SearchOptions::from_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(EXIT_INVALID);
+        }
+    };
+    
+    // Refuse to hand out any address if a dependency changed how addresses are derived
+    if let Err(e) = check_derivation() {
+        eprintln!("Error: address derivation failed its self-check ({}). No keys were generated.", e);
+        std::process::exit(EXIT_DERIVATION_FAILURE);
+    }
+    
+    // Refuse to hand out any key if the OS random generator looks broken
+    if let Err(e) = check_rng_health(generate_random_private_key) {
+        eprintln!("Error: the system random number generator failed a sanity check ({}). No keys were generated.", e);
+        std::process::exit(EXIT_RNG_FAILURE);
+    }
+    
+    // A subcommand on the command line wins over a search configured in the environment
+    let command = match args.command {
+        Some(cmd) => Some(cmd),
+        None => match command_from_env() {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(EXIT_INVALID);
+            }
+        },
+    };
+    
+    if command.is_some() {
+        // Execute a single command (non-interactive mode)
+        if let Some(cmd) = command {
+            if !options.dry_validate && options.test_key.is_none() {
+                check_feasibility(&cmd, &mut options, args.auto_cap);
+            }
+            if options.dry_validate {
+                if let Command::Interactive = cmd {
+                    eprintln!("Error: --dry-validate needs a command to check and can't be used in interactive mode");
+                    std::process::exit(EXIT_INVALID);
+                }
+            } else if options.output_format != OutputFormat::Text {
+                if let Command::Interactive = cmd {
+                    eprintln!("Error: {:?} output needs a search command and can't be used in interactive mode", options.output_format);
+                    std::process::exit(EXIT_INVALID);
+                }
+            } else {
+                display_banner(&options.theme);
+                println!("Running command in non-interactive mode");
+            }
+            exit_on_interrupt(options.coordinator.clone());
+            
+            let code = execute_command(cmd, &options).await?;
+            std::process::exit(if INTERRUPTED.load(Ordering::SeqCst) { EXIT_INTERRUPTED } else { code });
+        }
+    } else if options.dry_validate {
+        eprintln!("Error: --dry-validate needs a command to check and can't be used in interactive mode");
+        std::process::exit(EXIT_INVALID);
+    } else if options.output_format != OutputFormat::Text {
+        eprintln!("Error: {:?} output needs a search command and can't be used in interactive mode", options.output_format);
+        std::process::exit(EXIT_INVALID);
+    } else {
+        // Interactive mode
+        interactive_mode(&options).await?;
+    }
+    
+    Ok(())
+}
+
+// Reject flag combinations where one flag would silently be ignored. Pairs that clap can
+// check on its own (--threads with --auto-threads, --refresh-ms or --milestone with
+// --no-progress) are declared on the arguments instead.
+fn validate_flags(args: &Args) -> std::result::Result<(), String> {
+    if args.deterministic && args.keyspace_file().is_none() {
+        return Err("--deterministic needs the keyspace of a --coordinator or --state file".to_string());
+    }
+    if args.resume_from_key.is_some() && !args.is_deterministic() {
+        return Err("--resume-from-key only applies to a --deterministic or --state search".to_string());
+    }
+    if args.resume_from_counter.is_some() && !args.is_deterministic() {
+        return Err("--resume-from-counter only applies to a --deterministic or --state search".to_string());
+    }
+    if args.count == 0 {
+        return Err("--count (or FUEL_VANITY_COUNT) must be at least 1".to_string());
+    }
+    if args.delimiter.is_some() && args.output_format != OutputFormat::Line {
+        return Err("--delimiter only applies to --output-format line".to_string());
+    }
+    if args.group.is_some() && args.output_format != OutputFormat::Text {
+        return Err("--group only changes the text display and has no effect on other output formats".to_string());
+    }
+    if args.key_format != KeyFormat::Hex && args.output_format == OutputFormat::Binary {
+        return Err("--key-format can't be used with binary output, which writes the raw key bytes".to_string());
+    }
+    if args.output_public_key && args.output_format == OutputFormat::Binary {
+        return Err("--output-public-key can't be used with binary output, whose records have a fixed layout".to_string());
+    }
+    if args.format_preview && args.output_format != OutputFormat::Text {
+        return Err(format!(
+            "--format-preview prints a text example and can't be combined with --output-format {:?}",
+            args.output_format
+        ).to_lowercase());
+    }
+    if args.show_hash && args.test_derivation.is_none() && !matches!(args.command, Some(Command::Verify { .. })) {
+        return Err("--show-hash only applies to --test-derivation and the verify command, which derive a single key".to_string());
+    }
+    if args.pick && args.command.as_ref().is_some_and(|command| !matches!(command, Command::Interactive)) {
+        return Err("--pick only applies to searches started from the interactive prompt".to_string());
+    }
+    if args.hash != HashName::Sha256 && args.check_balance.is_some() {
+        return Err("--check-balance looks up Fuel addresses, which only --hash sha256 gives".to_string());
+    }
+    if args.hash != HashName::Sha256 && matches!(args.command, Some(Command::DeriveScan { .. })) {
+        return Err("derive-scan derives wallet addresses, which always use sha256; --hash doesn't apply".to_string());
+    }
+    if args.format_preview && args.check_balance.is_some() {
+        return Err("--format-preview doesn't search, so --check-balance would have nothing to check".to_string());
+    }
+    Ok(())
+}
+
+// Result of --dry-validate once the command and options passed every check. Goes to stderr
+// so stdout stays empty for the output formats meant for piping.
+fn report_valid() -> i32 {
+    eprintln!("Configuration is valid");
+    EXIT_FOUND
+}
+
+// Build a search from FUEL_VANITY_PATTERN and FUEL_VANITY_POSITION (prefix, suffix or
+// contains; default prefix), for CI and container runs that configure through the environment
+fn command_from_env() -> std::result::Result<Option<Command>, String> {
+    let Ok(pattern) = std::env::var("FUEL_VANITY_PATTERN") else {
+        return Ok(None);
+    };
+    let position = std::env::var("FUEL_VANITY_POSITION").unwrap_or_else(|_| "prefix".to_string());
+    
+    match position.as_str() {
+        "prefix" => Ok(Some(Command::Prefix { pattern })),
+        "suffix" => Ok(Some(Command::Suffix { pattern })),
+        "contains" | "anywhere" => Ok(Some(Command::Contains { pattern, min_match_length: None })),
+        _ => Err(format!("FUEL_VANITY_POSITION must be prefix, suffix or contains, got '{}'", position)),
+    }
+}
+
+// Name of the file holding default flags, looked for in the working directory and then in
+// $XDG_CONFIG_HOME (~/.config when unset)
+const CONFIG_FILE: &str = "fuel-vanity.toml";
+
+fn config_path() -> Option<PathBuf> {
+    let local = PathBuf::from(CONFIG_FILE);
+    if local.is_file() {
+        return Some(local);
+    }
+    let dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(dir.join(CONFIG_FILE)).filter(|path| path.is_file())
+}
+
+// Parse the command line with the config file's values filling in the flags it left unset.
+// The config becomes extra flags ahead of the typed ones, so clap validates it the same way.
+fn parse_args() -> std::result::Result<Args, clap::Error> {
+    let argv: Vec<OsString> = std::env::args_os().collect();
+    let cli = Args::command().try_get_matches_from(&argv)?;
+    let Some(path) = config_path() else {
+        return Args::from_arg_matches(&cli);
+    };
+    let flags = config_flags(&path, &cli)
+        .map_err(|e| Args::command().error(clap::error::ErrorKind::InvalidValue, format!("{}: {}", path.display(), e)))?;
+    
+    let (program, typed) = argv.split_first().map_or((None, &[][..]), |(program, typed)| (Some(program), typed));
+    let merged = program.cloned().into_iter().chain(flags.into_iter().map(OsString::from)).chain(typed.iter().cloned());
+    Args::try_parse_from(merged)
+}
+
+// Turn each `long-flag = value` of the config file into a flag. A flag typed on the command
+// line or set through its environment variable wins, as does one it conflicts with.
+fn config_flags(path: &Path, cli: &ArgMatches) -> std::result::Result<Vec<String>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let document = toml_edit::Document::parse(text).map_err(|e| e.to_string())?;
+    let mut command = Args::command();
+    command.build();
+    let is_set = |arg: &clap::Arg| matches!(cli.value_source(arg.get_id().as_str()), Some(ValueSource::CommandLine | ValueSource::EnvVariable));
+    // Conflicts are declared on one side of each pair only
+    let conflict = |a: &clap::Arg, b: &clap::Arg| {
+        command.get_arg_conflicts_with(a).iter().any(|other| other.get_id() == b.get_id())
+            || command.get_arg_conflicts_with(b).iter().any(|other| other.get_id() == a.get_id())
+    };
+    
+    let mut flags = Vec::new();
+    for (key, item) in document.as_table().iter() {
+        let long = key.replace('_', "-");
+        let Some(arg) = command.get_arguments().find(|arg| arg.get_long() == Some(long.as_str())) else {
+            return Err(format!("unknown option '{}'", key));
+        };
+        if is_set(arg) || command.get_arguments().any(|other| is_set(other) && conflict(arg, other)) {
+            continue;
+        }
+        let values: Vec<&toml_edit::Value> = match item.as_value() {
+            Some(toml_edit::Value::Array(array)) => array.iter().collect(),
+            Some(value) => vec![value],
+            None => return Err(format!("'{}' must be a value, not a table", key)),
+        };
+        for value in values {
+            let text = match value {
+                toml_edit::Value::String(text) => text.value().clone(),
+                toml_edit::Value::Integer(number) => number.value().to_string(),
+                toml_edit::Value::Float(number) => number.value().to_string(),
+                // A switch is passed bare when true and left out when false
+                toml_edit::Value::Boolean(on) if !arg.get_action().takes_values() => {
+                    if *on.value() {
+                        flags.push(format!("--{}", long));
+                    }
+                    continue;
+                }
+                toml_edit::Value::Boolean(on) => on.value().to_string(),
+                other => return Err(format!("'{}' can't be a {}", key, other.type_name())),
+            };
+            flags.push(format!("--{}={}", long, text));
+        }
+    }
+    Ok(flags)
+}
+
+// println! panics once stdout is closed, e.g. when piped into `head`. Treat that as the
+// reader being done and exit cleanly instead of printing a panic and a backtrace.
+fn exit_quietly_on_broken_pipe() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload.downcast_ref::<String>().map(String::as_str)
+            .or_else(|| payload.downcast_ref::<&str>().copied())
+            .unwrap_or_default();
+        if message.starts_with("failed printing to stdout") && message.contains("Broken pipe") {
+            std::process::exit(EXIT_FOUND);
+        }
+        default_hook(info);
+    }));
+}
+
+// Exit with EXIT_INTERRUPTED on Ctrl+C, saving the position of a deterministic search first.
+// A search gets INTERRUPT_GRACE to stop its workers and show what it found; commands that
+// don't check INTERRUPTED end when it runs out. The listener gets its own thread and runtime
+// so it keeps running while a command blocks the main thread, as the thread::scope ones do.
+fn exit_on_interrupt(coordinator: Option<Arc<Coordinator>>) {
+    thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start the Ctrl+C listener");
+        runtime.block_on(async {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                INTERRUPTED.store(true, Ordering::SeqCst);
+                let _ = tokio::time::timeout(INTERRUPT_GRACE, tokio::signal::ctrl_c()).await;
+                if let Some(coordinator) = coordinator {
+                    let _ = coordinator.give_back();
+                }
+                std::process::exit(EXIT_INTERRUPTED);
+            }
+        });
+    });
+}
+
+// Function to execute a command, returning the process exit code
+async fn execute_command(cmd: Command, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let cmd = match resolve_budget(cmd, options).and_then(|cmd| resolve_date(cmd, options)) {
+        Ok(cmd) => cmd,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(EXIT_INVALID);
+        }
+    };
+    
+    if let Some((_, criteria)) = search_criteria(&cmd, options.network) {
+        if let Err(e) = criteria.validate(options.case_sensitive) {
+            eprintln!("Error: {}", e);
+            return Ok(EXIT_INVALID);
+        }
+        
+        if options.format_preview {
+            display_preview(&criteria, options);
+            return Ok(EXIT_FOUND);
+        }
+        if options.dry_validate {
+            // A search for non-hex digits runs but can never finish, so a dry run fails it
+            let hex = match &criteria {
+                SearchCriteria::Pattern { pattern, .. } | SearchCriteria::Partial { pattern, .. } => Some(pattern),
+                SearchCriteria::Dual { hex, .. } => Some(hex),
+                _ => None,
+            };
+            if let Some(hex) = hex.filter(|hex| !is_valid_hex_pattern(hex)) {
+                eprintln!("Error: '{}' must use only hexadecimal characters (0-9, a-f)", hex);
+                return Ok(EXIT_INVALID);
+            }
+            return Ok(report_valid());
+        }
+        
+        let run = match options.test_key {
+            Some(key) => test_derivation(&key, &criteria, options)?,
+            None => search_vanity_address(criteria.clone(), options, false, Arc::default()).await,
+        };
+        let matches = &run.matches;
+        match options.output_format {
+            OutputFormat::Text => {
+                warn_if_shared_terminal(&run.results, options);
+                display_tail(&run.results, matches.len(), options);
+            }
+            // The results array was already streamed while searching
+            OutputFormat::Json => write_json_report(&criteria, options, &run)?,
+            // Every other format was already streamed while searching
+            OutputFormat::Binary | OutputFormat::Line | OutputFormat::Csv => {}
+        }
+        check_balances(matches, options).await;
+        if options.latency_stats {
+            display_latency(matches, &options.theme);
+        }
+        if let Some(path) = &options.count_distribution {
+            write_count_distribution(path, matches, &criteria, options);
+        }
+        
+        // A test derivation checks a single key, so one match is all it can find
+        let wanted = if options.test_key.is_some() { 1 } else { options.count };
+        return Ok(if matches.len() >= wanted { EXIT_FOUND } else { EXIT_INCOMPLETE });
+    }
+    
+    if options.dry_validate {
+        return Ok(report_valid());
+    }
+    
+    match cmd {
+        Command::DeriveScan { pattern, count, position } => return derive_scan(&pattern, count, &position, options),
+        Command::Sample { count } => return sample(count, options),
+        Command::Climb { pattern } => return climb(&pattern, options),
+        Command::Minimize { seconds, attempts } => return minimize(seconds, attempts, options),
+        Command::Surprise { seconds, attempts } => return surprise(seconds, attempts, options),
+        Command::Probe { pattern, attempts, position } => return probe(&pattern, attempts, &position, options),
+        Command::FromPassphrase { pattern, counter } => return from_passphrase(&pattern, counter, options),
+        Command::Verify { input } => return verify_file(&input, options),
+        Command::Info => display_help(&options.theme),
+        Command::Stats => display_stats(options),
+        Command::Interactive => interactive_mode(options).await?,
+        Command::Exit => {}
+        _ => {}
+    }
+    
+    Ok(EXIT_FOUND)
+}
+
+// Finish matching a key whose Fuel address matched: criteria that look past the address
+// derive the rest here, so only candidates pay for it. Returns the match detail, or None
+// when the key doesn't match after all.
+fn confirm_match(
+    criteria: &SearchCriteria,
+    deriver: &EcDeriver,
+    key: &[u8; 32],
+    address: &str,
+    hex_case: HexCase
+) -> Option<Option<String>> {
+    match criteria {
+        SearchCriteria::DualScheme { pattern } => {
+            let ethereum = format!("0x{}", hex::encode(deriver.ethereum_address(key)?));
+            matches_pattern(&ethereum, pattern, "prefix", false)
+                .then(|| Some(format!("Ethereum address {}", hex_case.apply(&ethereum))))
+        }
+        SearchCriteria::ChecksumWord { word } => {
+            let mut bytes = [0u8; 32];
+            hex::decode_to_slice(address.strip_prefix("0x").unwrap_or(address), &mut bytes).ok()?;
+            let checksummed = to_checksum_hex(&bytes);
+            checksummed.contains(word.as_str()).then(|| Some(format!("checksum {}", checksummed)))
+        }
+        _ => Some(criteria.describe_match(address)),
+    }
+}
+
+// Number of candidates --debug-match explains at the start of a search
+const DEBUG_MATCH_SAMPLE: usize = 5;
+
+// Why a candidate is rejected, for --debug-match, or None if it is accepted. Repeats the
+// checks of the worker, so it is only run for a handful of candidates.
+fn explain_candidate(
+    criteria: &SearchCriteria,
+    deriver: &EcDeriver,
+    key: &[u8; 32],
+    address: &str,
+    case_sensitive: bool,
+    hex_case: HexCase
+) -> Option<String> {
+    if !criteria.matches(address, case_sensitive) {
+        return Some(criteria.mismatch_reason(address, case_sensitive));
+    }
+    if confirm_match(criteria, deriver, key, address, hex_case).is_some() {
+        return None;
+    }
+    Some(match criteria {
+        SearchCriteria::DualScheme { pattern } => format!(
+            "the Fuel address matches, but the Ethereum address 0x{} doesn't start with '{}'",
+            deriver.ethereum_address(key).map(hex::encode).unwrap_or_default(), pattern
+        ),
+        SearchCriteria::ChecksumWord { word } => format!(
+            "the digits of '{}' appear, but not in that casing in the checksum form",
+            word
+        ),
+        _ => "rejected by the final check".to_string(),
+    })
+}
+
+// First nibble where `digits` differs from `wanted`, with both characters. '?' in `wanted`
+// matches anything.
+fn first_difference(digits: &str, wanted: &str, case_sensitive: bool) -> Option<(usize, char, char)> {
+    digits.chars().zip(wanted.chars()).enumerate().find_map(|(i, (actual, want))| {
+        let same = want == '?' || if case_sensitive { actual == want } else { actual.eq_ignore_ascii_case(&want) };
+        (!same).then_some((i, actual, want))
+    })
+}
+
+// Run the match for one fixed key in place of a search, formatting the result exactly as a
+// search would, so the whole output can be compared against a golden file
+fn test_derivation(key: &[u8; 32], criteria: &SearchCriteria, options: &SearchOptions) -> io::Result<SearchRun> {
+    let deriver = EcDeriver::with_hash(options.hash);
+    if options.show_hash {
+        display_hash_trace(&deriver, key, matches!(criteria, SearchCriteria::DualScheme { .. }), &options.theme);
+    }
+    let address_bytes = deriver
+        .address(key)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a valid secret key"))?;
+    let address = format_address(&address_bytes, options.case_sensitive);
+    
+    if options.output_format == OutputFormat::Json {
+        write_json_head()?;
+    }
+    let mut results = Vec::new();
+    let mut matches = Vec::new();
+    let detail = criteria
+        .matches(&address, options.case_sensitive)
+        .then(|| confirm_match(criteria, &deriver, key, &address, options.hex_case))
+        .flatten();
+    if let Some(detail) = detail {
+        let found = FoundAddress {
+            detail,
+            address: display_address(&address, options.case_sensitive, options.hex_case, options.hex_prefix),
+            private_key: format_private_key(key, options.key_format, options.hex_case, options.hex_prefix),
+            public_key: public_key_hex(options.output_public_key, &deriver, key, options.hex_case, options.hex_prefix),
+            attempts: 1,
+            found_after: Duration::ZERO,
+        };
+        match options.output_format {
+            OutputFormat::Binary => write_binary_record(&found)?,
+            OutputFormat::Csv => {
+                write_csv_header(options.output_public_key)?;
+                write_csv_row(&found, &criteria.pattern_label())?;
+            }
+            OutputFormat::Line => write_lines(std::slice::from_ref(&found), &options.delimiter),
+            OutputFormat::Json => write_json_result(&found, true)?,
+            OutputFormat::Text => {}
+        }
+        matches.push(MatchRecord::of(address_bytes, &found));
+        results.push(found);
+    }
+    
+    Ok(SearchRun { results, matches, attempts: 1, elapsed: Duration::ZERO, rate: 0.0 })
+}
+
+// Scan the account indices of a mnemonic for matching addresses, returning the exit code
+fn derive_scan(pattern: &str, count: u32, position: &str, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let Theme { accent, label, address: address_color, reset, .. } = options.theme;
+    
+    let position = match position {
+        "prefix" | "suffix" => position,
+        "contains" | "anywhere" => "anywhere",
+        _ => {
+            eprintln!("Error: position must be prefix, suffix or contains, got '{}'", position);
+            return Ok(EXIT_INVALID);
+        }
+    };
+    if let Err(e) = validate_pattern(pattern, position) {
+        eprintln!("Error: {}", e);
+        return Ok(EXIT_INVALID);
+    }
+    warn_if_invalid_hex(pattern);
+    
+    // The phrase is read from stdin so it never ends up in shell history or process lists
+    eprintln!("{label}🔑 Enter the mnemonic phrase:{reset}");
+    let mut phrase = String::new();
+    io::stdin().read_line(&mut phrase)?;
+    
+    let matches = match scan_derivation_indices(phrase.trim(), count, pattern, position, options.case_sensitive) {
+        Ok(matches) => matches,
+        Err(e) => {
+            eprintln!("Error: could not derive keys from the mnemonic: {}", e);
+            return Ok(EXIT_INVALID);
+        }
+    };
+    
+    println!("\n{accent}✅ {} of {} account indices match{reset}\n", matches.len(), count);
+    for (index, address) in &matches {
+        println!("  {label}#{:<6}{reset} {address_color}{}{reset}", index, with_hex_prefix(options.hex_case.apply(address), options.hex_prefix));
+    }
+    
+    Ok(if matches.is_empty() { EXIT_INCOMPLETE } else { EXIT_FOUND })
+}
+
+// --show-hash: the public key bytes that go into the address hash and the hash that comes
+// out, before any truncation or formatting. A Fuel address is the whole SHA-256 hash; an
+// Ethereum one, with `ethereum`, the last 20 bytes of the Keccak-256 hash.
+fn display_hash_trace(deriver: &EcDeriver, key: &[u8; 32], ethereum: bool, theme: &Theme) {
+    let Theme { label, reset, .. } = *theme;
+    let (Some(public_key), Some(address)) = (deriver.public_key(key), deriver.address(key)) else {
+        return;
+    };
+    eprintln!("{label}🧮 Public key (x and y):{reset} {}", hex::encode(public_key));
+    eprintln!("{label}🧮 {}:{reset} {}", deriver.hash().label(), hex::encode(address));
+    if let Some(keccak) = deriver.ethereum_hash(key).filter(|_| ethereum) {
+        eprintln!("{label}🧮 Keccak-256:{reset} {}", hex::encode(keccak));
+    }
+}
+
+// Check every private_key,address line of a file, returning the exit code. Blank lines are
+// skipped; a line that can't be read as a pair counts as a failure.
+fn verify_file(path: &Path, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let Theme { accent, label, error, reset, .. } = options.theme;
+    
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("Error: {}", with_path(e, path));
+            return Ok(EXIT_INVALID);
+        }
+    };
+    
+    let deriver = EcDeriver::with_hash(options.hash);
+    let mut checked = 0;
+    let mut failures = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        checked += 1;
+        
+        if options.show_hash {
+            if let Some((Ok(secret_key), address)) = line.split_once(',').map(|(key, address)| (parse_private_key(key.trim()), address.trim())) {
+                let ethereum = address.strip_prefix("0x").unwrap_or(address).len() != ADDRESS_HEX_LEN;
+                eprintln!("{label}Line {}:{reset}", index + 1);
+                display_hash_trace(&deriver, &secret_key, ethereum, &options.theme);
+            }
+        }
+        
+        let failure = match line.split_once(',') {
+            None => Some("not in private_key,address form".to_string()),
+            Some((key, address)) => {
+                let (key, address) = (key.trim(), address.trim());
+                if parse_private_key(key).is_err() {
+                    Some(format!("'{}' is not a valid private key", key))
+                } else if !verify_key_address_pair_with_hash(key, address, options.hash) {
+                    Some(format!("the key doesn't derive {}", address))
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(reason) = failure {
+            failures.push((index + 1, reason));
+        }
+    }
+    
+    for (line, reason) in &failures {
+        println!("{error}✗ Line {}:{reset} {}", line, reason);
+    }
+    let color = if failures.is_empty() { accent } else { error };
+    println!("{color}{} of {} pairs valid{reset}", checked - failures.len(), checked);
+    if checked == 0 {
+        eprintln!("{label}⚠️  {} has no pairs to check{reset}", path.display());
+    }
+    
+    Ok(if failures.is_empty() { EXIT_FOUND } else { EXIT_VERIFY_FAILED })
+}
+
+// Search the keys of a passphrase, one scrypt derivation per counter, for the first counter
+// whose address starts with the pattern, or rebuild the key of a known counter. Workers take
+// counters in order and keep going until every counter below the best match is checked, so
+// the result is always the lowest matching counter, whatever the thread count.
+fn from_passphrase(pattern: &str, counter: Option<u64>, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let Theme { label, error, reset, .. } = options.theme;
+    
+    let criteria = SearchCriteria::Pattern { pattern: pattern.to_string(), position: "prefix".to_string() };
+    if let Err(e) = criteria.validate(options.case_sensitive) {
+        eprintln!("Error: {}", e);
+        return Ok(EXIT_INVALID);
+    }
+    warn_if_invalid_hex(pattern);
+    
+    eprintln!("{error}⚠️  Brain wallets are risky: anyone who guesses the passphrase can rebuild the key,{reset}");
+    eprintln!("{error}   and the counter adds almost nothing since it is small. Attackers try common phrases,{reset}");
+    eprintln!("{error}   quotes and song lyrics at scale. Only use a long, random passphrase.{reset}");
+    
+    // Read from stdin so the passphrase never ends up in shell history or process lists
+    eprintln!("{label}🔑 Enter the passphrase:{reset}");
+    let mut passphrase = String::new();
+    io::stdin().read_line(&mut passphrase)?;
+    let passphrase = passphrase.trim_end_matches(['\r', '\n']);
+    if passphrase.is_empty() {
+        eprintln!("Error: the passphrase is empty");
+        return Ok(EXIT_INVALID);
+    }
+    
+    let derive = |counter: u64, deriver: &EcDeriver| {
+        let private_key = passphrase_key(passphrase, counter);
+        let address = format_address(&deriver.address(&private_key)?, options.case_sensitive);
+        Some((private_key, address))
+    };
+    
+    let start = Instant::now();
+    let found = match counter {
+        Some(counter) => derive(counter, &EcDeriver::with_hash(options.hash)).map(|(private_key, address)| (counter, private_key, address)),
+        None => {
+            eprintln!("{label}🔍 Deriving one scrypt key per counter, which is slow: keep the prefix short{reset}");
+            let next = AtomicUsize::new(0);
+            let best: Mutex<Option<(u64, [u8; 32], String)>> = Mutex::new(None);
+            thread::scope(|scope| {
+                for _ in 0..options.threads {
+                    scope.spawn(|| {
+                        let deriver = EcDeriver::with_hash(options.hash);
+                        loop {
+                            let counter = next.fetch_add(1, Ordering::SeqCst) as u64;
+                            if best.lock().unwrap().as_ref().is_some_and(|(best, _, _)| counter > *best) {
+                                break;
+                            }
+                            let Some((private_key, address)) = derive(counter, &deriver) else {
+                                continue;
+                            };
+                            if criteria.matches(&address, options.case_sensitive) {
+                                let mut best = best.lock().unwrap();
+                                if best.as_ref().is_none_or(|(best, _, _)| counter < *best) {
+                                    *best = Some((counter, private_key, address));
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+            best.into_inner().unwrap()
+        }
+    };
+    
+    let Some((counter, private_key, address)) = found else {
+        eprintln!("Error: counter {} doesn't give a valid key for this passphrase", counter.unwrap_or_default());
+        return Ok(EXIT_INVALID);
+    };
+    let deriver = EcDeriver::with_hash(options.hash);
+    let found = FoundAddress {
+        address: display_address(&address, options.case_sensitive, options.hex_case, options.hex_prefix),
+        private_key: format_private_key(&private_key, options.key_format, options.hex_case, options.hex_prefix),
+        public_key: public_key_hex(options.output_public_key, &deriver, &private_key, options.hex_case, options.hex_prefix),
+        attempts: counter as usize + 1,
+        found_after: start.elapsed(),
+        detail: Some(format!("passphrase counter {}", counter)),
+    };
+    
+    warn_if_shared_terminal(std::slice::from_ref(&found), options);
+    display_results(std::slice::from_ref(&found), options);
+    println!("{label}♻️  Rebuild this key with the same passphrase and --counter {}{reset}", counter);
+    Ok(EXIT_FOUND)
+}
+
+// Search for ever longer prefixes: every match raises the target to its own first digits
+// plus one, so each improvement shares the leading digits of the one before it. Runs until
+// the whole address matches, which in practice means until Ctrl+C.
+fn climb(start: &str, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let Theme { accent, label, address: address_color, key, error, reset, .. } = options.theme;
+    
+    let criteria = SearchCriteria::Pattern { pattern: start.to_string(), position: "prefix".to_string() };
+    if let Err(e) = criteria.validate(options.case_sensitive) {
+        eprintln!("Error: {}", e);
+        return Ok(EXIT_INVALID);
+    }
+    if !is_valid_hex_pattern(start) {
+        eprintln!("Error: a climb must start from hexadecimal digits (0-9, a-f), got '{}'", start);
+        return Ok(EXIT_INVALID);
+    }
+    
+    // Workers compare against a copy of the target and reload it when the version moves on
+    let target = Mutex::new(start.to_string());
+    let version = AtomicUsize::new(0);
+    let attempts = AtomicUsize::new(0);
+    let start_time = Instant::now();
+    let done = AtomicBool::new(start.len() == ADDRESS_HEX_LEN);
+    let text_output = options.output_format == OutputFormat::Text;
+    
+    if options.output_format == OutputFormat::Csv {
+        write_csv_header(options.output_public_key)?;
+    }
+    if text_output {
+        println!("\n{label}📈 Climbing from prefix '{}'; each match adds one digit. Press Ctrl+C to stop.{reset}\n", start);
+    }
+    
+    thread::scope(|scope| {
+        for _ in 0..options.threads {
+            scope.spawn(|| {
+                let deriver = EcDeriver::with_hash(options.hash);
+                let mut seen_version = usize::MAX;
+                let mut pattern = String::new();
+                
+                while !done.load(Ordering::SeqCst) {
+                    if version.load(Ordering::SeqCst) != seen_version {
+                        seen_version = version.load(Ordering::SeqCst);
+                        pattern = target.lock().unwrap().clone();
+                    }
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    
+                    let private_key = generate_constrained_key(options.key_constraint);
+                    let Some(address) = deriver.address(&private_key) else {
+                        continue;
+                    };
+                    let address = format_address(&address, options.case_sensitive);
+                    if !matches_pattern(&address, &pattern, "prefix", options.case_sensitive) {
+                        continue;
+                    }
+                    
+                    // Only the first worker to beat the current target reports; the others
+                    // matched a target that is already outdated
+                    let mut current = target.lock().unwrap();
+                    if *current != pattern || done.load(Ordering::SeqCst) {
+                        continue;
+                    }
+                    if options.verify_on_find {
+                        verify_found(&private_key, &address, options.hash, &options.theme);
+                    }
+                    let digits = &address[2..];
+                    let matched = digits[..pattern.len()].to_string();
+                    let found = FoundAddress {
+                        address: display_address(&address, options.case_sensitive, options.hex_case, options.hex_prefix),
+                        private_key: format_private_key(&private_key, options.key_format, options.hex_case, options.hex_prefix),
+                        public_key: public_key_hex(options.output_public_key, &deriver, &private_key, options.hex_case, options.hex_prefix),
+                        attempts: attempts.load(Ordering::Relaxed),
+                        found_after: start_time.elapsed(),
+                        detail: None,
+                    };
+                    
+                    match options.output_format {
+                        OutputFormat::Text => {
+                            warn_if_shared_terminal(std::slice::from_ref(&found), options);
+                            println!("{accent}⬆️  {}{reset} ({} keys): {address_color}{}{reset}", matched, HumanCount(attempts.load(Ordering::Relaxed) as u64), found.address);
+                            println!("   {key}{}{reset}", found.private_key);
+                            if let Some(public_key) = &found.public_key {
+                                println!("   {}", public_key);
+                            }
+                        }
+                        OutputFormat::Line => write_lines(std::slice::from_ref(&found), &options.delimiter),
+                        // One JSON object per line, since a climb never produces a final list
+                        OutputFormat::Json => {
+                            let mut result = json_results(std::slice::from_ref(&found)).remove(0);
+                            result["prefix_len"] = serde_json::json!(matched.len());
+                            println!("{}", result);
+                        }
+                        OutputFormat::Binary | OutputFormat::Csv => {
+                            let written = match options.output_format {
+                                OutputFormat::Binary => write_binary_record(&found),
+                                _ => write_csv_row(&found, &matched),
+                            };
+                            if let Err(e) = written {
+                                eprintln!("\n{error}⚠️  Failed to write {:?} output: {}{reset}", options.output_format, e);
+                                done.store(true, Ordering::SeqCst);
+                            }
+                        }
+                    }
+                    if let Some(log) = &options.audit_log {
+                        if let Err(e) = log.record(&matched, "climb", &found.address, attempts.load(Ordering::Relaxed)) {
+                            eprintln!("\n{error}⚠️  Failed to write audit log: {}{reset}", e);
+                        }
+                    }
+                    if let Some(webhook) = &options.webhook {
+                        webhook.post(&found, &matched, "climb");
+                    }
+                    
+                    if pattern.len() + 1 > ADDRESS_HEX_LEN {
+                        done.store(true, Ordering::SeqCst);
+                    } else {
+                        *current = digits[..pattern.len() + 1].to_string();
+                        version.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            });
+        }
+    });
+    
+    Ok(EXIT_FOUND)
+}
+
+// Keep the smallest address each worker derives until the time or attempt budget runs out
+// (or Ctrl+C), then report the smallest of those
+fn minimize(seconds: Option<f64>, attempts: Option<u64>, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    keep_best(
+        "minimize",
+        "📉 Keeping the smallest address found",
+        seconds,
+        attempts,
+        options,
+        |address| Reverse(*address),
+        |Reverse(address), checked| {
+            let zeros = hex::encode(address).chars().take_while(|c| *c == '0').count();
+            format!("{} leading zero digits, smallest of {} addresses", zeros, HumanCount(checked as u64))
+        },
+    )
+}
+
+// Keep the address with the highest surprise score until the budget runs out, for a cool
+// address without choosing a pattern. Ties go to the smallest address.
+fn surprise(seconds: Option<f64>, attempts: Option<u64>, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    keep_best(
+        "surprise",
+        "🎁 Keeping the most unusual address found",
+        seconds,
+        attempts,
+        options,
+        surprise_rank,
+        |(_, Reverse(address)), checked| {
+            let score = surprise_score(address);
+            format!(
+                "score {}: {} leading zeros, a run of {} and a sequence of {} digits, best of {} addresses",
+                score.total(), score.leading_zeros, score.longest_run, score.longest_sequence, HumanCount(checked as u64)
+            )
+        },
+    )
+}
+
+// The key among `keys` whose address `rank` puts highest, with its rank. Only a strictly
+// higher rank replaces the one held, so ties go to the earlier key.
+fn best_of<R: Ord>(keys: impl Iterator<Item = [u8; 32]>, deriver: &EcDeriver, rank: impl Fn(&[u8; 32]) -> R) -> Option<(R, [u8; 32])> {
+    let mut best: Option<(R, [u8; 32])> = None;
+    for private_key in keys {
+        let Some(address) = deriver.address(&private_key) else {
+            continue;
+        };
+        let score = rank(&address);
+        if best.as_ref().is_none_or(|(current, _)| score > *current) {
+            best = Some((score, private_key));
+        }
+    }
+    best
+}
+
+// What surprise ranks an address by: its total score, then the smaller address
+fn surprise_rank(address: &[u8; 32]) -> (u32, Reverse<[u8; 32]>) {
+    (surprise_score(address).total(), Reverse(*address))
+}
+
+// Derive random keys until the time or attempt budget runs out (or Ctrl+C) and report the
+// address that `rank` puts highest, described by `describe`. Each worker holds its own best,
+// so the only shared state is the attempt counter.
+fn keep_best<R: Ord + Send>(
+    command: &str,
+    heading: &str,
+    seconds: Option<f64>,
+    attempts: Option<u64>,
+    options: &SearchOptions,
+    rank: impl Fn(&[u8; 32]) -> R + Sync,
+    describe: impl Fn(&R, usize) -> String,
+) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let Theme { label, reset, .. } = options.theme;
+    
+    if let Some(seconds) = seconds.filter(|seconds| !(*seconds > 0.0 && seconds.is_finite())) {
+        eprintln!("Error: the budget must be a positive number of seconds, got {}", seconds);
+        return Ok(EXIT_INVALID);
+    }
+    let deadline = seconds.map(Duration::from_secs_f64);
+    let limit = attempts.map(|attempts| attempts as usize);
+    
+    if options.output_format == OutputFormat::Text {
+        let budget = match (deadline, limit) {
+            (Some(deadline), _) => HumanDuration(deadline).to_string(),
+            (_, Some(limit)) => format!("{} keys", HumanCount(limit as u64)),
+            _ => unreachable!("clap requires --seconds or --attempts"),
+        };
+        println!("\n{label}{} in {}. Press Ctrl+C to stop early.{reset}\n", heading, budget);
+    }
+    
+    let checked = AtomicUsize::new(0);
+    let start = Instant::now();
+    let best = thread::scope(|scope| {
+        let workers: Vec<_> = (0..options.threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let keys = std::iter::from_fn(|| {
+                        if INTERRUPTED.load(Ordering::SeqCst) || deadline.is_some_and(|deadline| start.elapsed() >= deadline) {
+                            return None;
+                        }
+                        if limit.is_some_and(|limit| checked.fetch_add(1, Ordering::Relaxed) >= limit) {
+                            return None;
+                        }
+                        if limit.is_none() {
+                            checked.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Some(generate_constrained_key(options.key_constraint))
+                    });
+                    best_of(keys, &EcDeriver::with_hash(options.hash), &rank)
+                })
+            })
+            .collect();
+        workers.into_iter().filter_map(|worker| worker.join().unwrap()).max()
+    });
+    // Workers past an attempt limit still counted their last, unchecked, key
+    let checked = checked.into_inner().min(limit.unwrap_or(usize::MAX));
+    
+    let Some((score, private_key)) = best else {
+        eprintln!("Error: stopped before any key was checked");
+        return Ok(EXIT_INCOMPLETE);
+    };
+    let deriver = EcDeriver::with_hash(options.hash);
+    let address = deriver.address(&private_key).expect("the key derived an address in the search");
+    let found = FoundAddress {
+        address: display_address(&format_address(&address, options.case_sensitive), options.case_sensitive, options.hex_case, options.hex_prefix),
+        private_key: format_private_key(&private_key, options.key_format, options.hex_case, options.hex_prefix),
+        public_key: public_key_hex(options.output_public_key, &deriver, &private_key, options.hex_case, options.hex_prefix),
+        attempts: checked,
+        found_after: start.elapsed(),
+        detail: Some(describe(&score, checked)),
+    };
+    let results = std::slice::from_ref(&found);
+    
+    match options.output_format {
+        OutputFormat::Text => {
+            warn_if_shared_terminal(results, options);
+            display_results(results, options);
+        }
+        OutputFormat::Binary => write_binary_record(&found)?,
+        OutputFormat::Line => write_lines(results, &options.delimiter),
+        OutputFormat::Csv => {
+            write_csv_header(options.output_public_key)?;
+            write_csv_row(&found, "")?;
+        }
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "meta": {
+                    "schema_version": JSON_SCHEMA_VERSION,
+                    "command": command,
+                    "hash_algo": options.hash.name(),
+                    "format": "hex",
+                    "attempts": checked,
+                    "elapsed_secs": found.found_after.as_secs_f64(),
+                },
+                "results": json_results(results),
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+    
+    Ok(EXIT_FOUND)
+}
+
+// Check exactly `attempts` keys, whatever they find: threads claim attempts from a shared
+// counter until it reaches the total, so the count is exact for any thread count. Reports
+// the matches next to the number the difficulty model expects.
+fn probe(pattern: &str, attempts: u64, position: &str, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let Theme { accent, label, reset, .. } = options.theme;
+    
+    let anywhere = match position {
+        "prefix" | "suffix" => position,
+        "contains" | "anywhere" => "anywhere",
+        _ => {
+            eprintln!("Error: position must be prefix, suffix or contains, got '{}'", position);
+            return Ok(EXIT_INVALID);
+        }
+    };
+    let criteria = SearchCriteria::Pattern { pattern: pattern.to_string(), position: anywhere.to_string() };
+    if let Err(e) = criteria.validate(options.case_sensitive) {
+        eprintln!("Error: {}", e);
+        return Ok(EXIT_INVALID);
+    }
+    warn_if_invalid_hex(pattern);
+    
+    let next = AtomicUsize::new(0);
+    let matched = AtomicUsize::new(0);
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..options.threads {
+            scope.spawn(|| {
+                let deriver = EcDeriver::with_hash(options.hash);
+                while next.fetch_add(1, Ordering::Relaxed) < attempts as usize && !INTERRUPTED.load(Ordering::SeqCst) {
+                    let private_key = generate_constrained_key(options.key_constraint);
+                    let Some(address) = deriver.address(&private_key) else {
+                        continue;
+                    };
+                    if criteria.matches(&format_address(&address, options.case_sensitive), options.case_sensitive) {
+                        matched.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+    let matched = matched.into_inner();
+    let expected = criteria.keys_per_match(options.case_sensitive).map(|keys| attempts as f64 / keys);
+    
+    if options.output_format == OutputFormat::Json {
+        let report = serde_json::json!({
+            "meta": {
+                "schema_version": JSON_SCHEMA_VERSION,
+                "command": "probe",
+                "hash_algo": options.hash.name(),
+                "pattern": pattern,
+                "position": position,
+                "attempts": attempts,
+                "elapsed_secs": start.elapsed().as_secs_f64(),
+            },
+            "matches": matched,
+            "expected_matches": expected,
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("\n{accent}🔬 {} of {} keys matched '{}' ({}){reset}", HumanCount(matched as u64), HumanCount(attempts), pattern, position);
+        if let Some(expected) = expected {
+            println!("   {label}Expected:{reset} {:.1}, one match per {} keys", expected, describe_keys(attempts as f64 / expected));
+        }
+    }
+    
+    Ok(EXIT_FOUND)
+}
+
+// Generate and print random keypairs with no pattern, returning the exit code
+fn sample(count: usize, options: &SearchOptions) -> std::result::Result<i32, Box<dyn std::error::Error>> {
+    let Theme { accent, label, address: address_color, key, reset, .. } = options.theme;
+    let deriver = EcDeriver::with_hash(options.hash);
+    let mut samples = Vec::with_capacity(count);
+    
+    while samples.len() < count {
+        let private_key = generate_constrained_key(options.key_constraint);
+        // Out-of-range keys are astronomically rare; just draw again
+        let Some(address) = deriver.address(&private_key) else {
+            continue;
+        };
+        
+        let address = format_address(&address, options.case_sensitive);
+        samples.push(FoundAddress {
+            address: display_address(&address, options.case_sensitive, options.hex_case, options.hex_prefix),
+            private_key: format_private_key(&private_key, options.key_format, options.hex_case, options.hex_prefix),
+            public_key: public_key_hex(options.output_public_key, &deriver, &private_key, options.hex_case, options.hex_prefix),
+            attempts: samples.len() + 1,
+            found_after: Duration::ZERO,
+            detail: None,
+        });
+    }
+    
+    match options.output_format {
+        OutputFormat::Binary => {
+            for found in &samples {
+                write_binary_record(found)?;
+            }
+        }
+        OutputFormat::Line => write_lines(&samples, &options.delimiter),
+        OutputFormat::Csv => {
+            write_csv_header(options.output_public_key)?;
+            for found in &samples {
+                write_csv_row(found, "")?;
+            }
+        }
+        OutputFormat::Json => {
+            let report = serde_json::json!({
+                "meta": {
+                    "schema_version": JSON_SCHEMA_VERSION,
+                    "command": "sample",
+                    "hash_algo": options.hash.name(),
+                    "format": "hex",
+                    "count": samples.len(),
+                },
+                "results": json_results(&samples),
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        OutputFormat::Text => {
+            warn_if_shared_terminal(&samples, options);
+            println!("\n{accent}🎲 {} random keypairs{reset}\n", samples.len());
+            for (i, found) in samples.iter().enumerate() {
+                println!("  {label}#{:<4}{reset} {address_color}{}{reset}", i + 1, found.address);
+                println!("        {key}{}{reset}", found.private_key);
+                if let Some(public_key) = &found.public_key {
+                    println!("        {}", public_key);
+                }
+            }
+        }
+    }
+    
+    Ok(EXIT_FOUND)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_log_lines_hold_public_data_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let key = [0x42u8; 32];
+        let address = prefixed_hex(&EcDeriver::new().address(&key).unwrap());
+        let log = AuditLog::open(&path).unwrap();
+        log.record("cafe", "prefix", &address, 1234).unwrap();
+        log.record("be", "suffix", &address, 7).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let fields: Vec<&str> = lines[0].split('\t').collect();
+        assert_eq!(fields.len(), 5);
+        assert!(chrono::DateTime::parse_from_rfc3339(fields[0]).is_ok(), "{}", fields[0]);
+        assert_eq!(&fields[1..], ["pattern=cafe", "position=prefix", &format!("address={}", address), "attempts=1234"]);
+        assert!(lines[1].ends_with(&format!("\tpattern=be\tposition=suffix\taddress={}\tattempts=7", address)));
+        assert!(!contents.contains(&hex::encode(key)));
+    }
+
+    #[test]
+    fn coordinated_workers_cover_the_keyspace_without_overlap() {
+        let dir = tempfile::tempdir().unwrap();
+        let coordinator = Coordinator::open(&dir.path().join("counter"), false).unwrap();
+        let (threads, batches) = (4, 3);
+        let keys: Vec<[u8; 32]> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut claimed = 0..0;
+                        (0..batches * COORDINATOR_BATCH)
+                            .map(|_| coordinator.next_key(&mut claimed).unwrap())
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+        });
+
+        let checked: HashSet<[u8; 32]> = keys.iter().copied().collect();
+        assert_eq!(checked.len(), keys.len(), "two workers checked the same counter");
+        let keyspace: HashSet<[u8; 32]> = (0..threads * batches * COORDINATOR_BATCH).map(|counter| coordinator.key_at(counter)).collect();
+        assert_eq!(checked, keyspace);
+    }
+
+    fn address_with(piece: &str, at: usize) -> String {
+        let mut digits = "1".repeat(ADDRESS_HEX_LEN);
+        digits.replace_range(at..at + piece.len(), piece);
+        format!("0x{}", digits)
+    }
+
+    #[test]
+    fn min_match_length_rejects_shorter_pieces() {
+        let criteria = SearchCriteria::Partial { pattern: "cafebabe".to_string(), min_length: 3 };
+        assert!(!criteria.matches(&address_with("ca", 10), false));
+        assert!(!criteria.matches(&address_with("be", 10), false));
+        assert!(criteria.matches(&address_with("cafe", 10), false));
+        assert!(criteria.matches(&address_with("EBA", 0), false));
+        assert_eq!(criteria.describe_match(&address_with("cafe", 10)), Some("cafe at nibble 10".to_string()));
+        assert_eq!(criteria.describe_match(&address_with("bab", 61)), Some("bab at nibble 61".to_string()));
+    }
+
+    #[test]
+    fn min_match_length_must_fit_the_pattern() {
+        let partial = |min_length| SearchCriteria::Partial { pattern: "cafe".to_string(), min_length };
+        assert!(partial(0).validate(false).is_err());
+        assert!(partial(5).validate(false).is_err());
+        assert!(partial(1).validate(false).is_ok());
+        assert!(partial(4).validate(false).is_ok());
+    }
+
+    #[test]
+    fn min_match_length_is_parsed_from_both_front_ends() {
+        let args = Args::try_parse_from(["fuel-vanity-generator", "contains", "cafe", "--min-match-length", "3"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Contains { min_match_length: Some(3), .. })));
+        assert!(matches!(parse_input("contains cafe 3", false), Some(Command::Contains { min_match_length: Some(3), .. })));
+        assert!(matches!(parse_input("contains cafe", false), Some(Command::Contains { min_match_length: None, .. })));
+        assert!(parse_input("contains cafe x", false).is_none());
+    }
+    #[test]
+    fn edges_match_runs_at_either_end() {
+        let edges = SearchCriteria::Edges { digit: 'f', count: 4 };
+        let both = format!("0xffff{}ffff", "1".repeat(56));
+        let start = format!("0xfffff{}", "1".repeat(59));
+        let end = format!("0x{}FFFF", "1".repeat(60));
+        let short = format!("0xfff{}fff", "1".repeat(58));
+        assert!(edges.matches(&both, false));
+        assert!(edges.matches(&start, false));
+        assert!(edges.matches(&end, false));
+        assert!(!edges.matches(&short, false));
+        assert_eq!(edges.describe_match(&both).unwrap(), "both ends (runs: 4 at start, 4 at end)");
+        assert_eq!(edges.describe_match(&start).unwrap(), "start (runs: 5 at start, 0 at end)");
+        assert_eq!(edges.describe_match(&end).unwrap(), "end (runs: 0 at start, 4 at end)");
+    }
+
+    #[test]
+    fn edges_arguments_are_checked() {
+        assert!(validate_edges('g', 4).is_err());
+        assert!(validate_edges('0', 0).is_err());
+        assert!(validate_edges('0', ADDRESS_HEX_LEN + 1).is_err());
+        assert!(validate_edges('a', ADDRESS_HEX_LEN).is_ok());
+    }
+
+    pub(super) fn options_for(args: &[&str]) -> SearchOptions {
+        let args = Args::try_parse_from(std::iter::once("fuel-vanity-generator").chain(args.iter().copied())).unwrap();
+        SearchOptions::from_args(&args).unwrap()
+    }
+
+    #[test]
+    fn no_progress_turns_off_the_progress_line_and_milestones() {
+        let options = options_for(&["--no-progress", "prefix", "0"]);
+        assert!(!options.show_progress);
+        assert_eq!(options.milestone, None);
+    }
+
+    #[test]
+    fn calibration_tries_one_half_and_all_cores() {
+        assert_eq!(calibration_candidates(8), [1, 4, 8]);
+        assert_eq!(calibration_candidates(3), [1, 3]);
+        assert_eq!(calibration_candidates(1), [1]);
+        assert_eq!(calibration_candidates(0), [1]);
+    }
+
+    #[test]
+    fn calibrated_thread_count_is_within_range() {
+        let threads = calibrate_thread_count(Duration::from_millis(20));
+        assert!((1..=num_cpus::get()).contains(&threads), "{}", threads);
+        assert!(calibration_candidates(num_cpus::get()).contains(&threads));
+    }
+
+    #[test]
+    fn over_length_patterns_are_rejected() {
+        let suffix = "a".repeat(70);
+        let error = validate_pattern(&suffix, "suffix").unwrap_err();
+        assert!(error.contains("at most 64 characters"), "{}", error);
+        assert!(error.contains("got 70"), "{}", error);
+        assert!(validate_pattern(&"a".repeat(65), "prefix").is_err());
+        assert!(validate_pattern(&"a".repeat(65), "anywhere").is_err());
+        assert!(validate_pattern(&"a".repeat(64), "suffix").is_ok());
+    }
+
+    #[test]
+    fn mono_theme_has_no_escapes() {
+        let Theme { accent, label, emphasis, address, key, error, reset } = Theme::new(ThemeName::Mono);
+        assert!([accent, label, emphasis, address, key, error, reset].iter().all(|code| code.is_empty()));
+        for name in [ThemeName::Green, ThemeName::Blue, ThemeName::HighContrast] {
+            let theme = Theme::new(name);
+            assert!([theme.accent, theme.address, theme.key, theme.reset].iter().all(|code| code.starts_with("\x1b[")));
+        }
+    }
+
+    #[test]
+    fn two_coordinated_processes_never_share_a_counter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counter");
+        // Each open stands for a separate process sharing the file
+        let first = Coordinator::open(&path, false).unwrap();
+        let second = Coordinator::open(&path, false).unwrap();
+        assert_eq!(first.seed_hex(), second.seed_hex());
+
+        let claims: Vec<Vec<Range<u64>>> = thread::scope(|scope| {
+            [&first, &second]
+                .map(|coordinator| scope.spawn(move || (0..50).map(|_| coordinator.claim().unwrap()).collect()))
+                .map(|process| process.join().unwrap())
+                .into()
+        });
+        let mut ranges: Vec<Range<u64>> = claims.concat();
+        ranges.sort_by_key(|range| range.start);
+        assert_eq!(ranges.len(), 100);
+        assert!(ranges.windows(2).all(|pair| pair[0].end == pair[1].start), "ranges overlap or leave gaps");
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, 100 * COORDINATOR_BATCH);
+    }
+
+    fn completions(line: &str) -> (usize, Vec<String>) {
+        let history = rustyline::history::DefaultHistory::new();
+        let (start, pairs) = ReplHelper.complete(line, line.len(), &Context::new(&history)).unwrap();
+        (start, pairs.into_iter().map(|pair| pair.replacement).collect())
+    }
+
+    #[test]
+    fn repl_completes_command_names_only() {
+        assert_eq!(completions("pre"), (0, vec!["prefix ".to_string()]));
+        assert_eq!(completions("  SU"), (2, vec!["suffix ".to_string()]));
+        assert_eq!(completions("d").1, ["decimal-contains ", "dual ", "dual-scheme ", "date "]);
+        assert_eq!(completions("ver"), (0, vec!["verify ".to_string()]));
+        assert!(completions("prefix ca").1.is_empty());
+        assert!(completions("verify pa").1.is_empty());
+        assert_eq!(completions("").1.len(), REPL_COMMANDS.len());
+        assert!(matches!(parse_input("verify pairs.csv", false), Some(Command::Verify { input }) if input == Path::new("pairs.csv")));
+        assert!(parse_input("verify", false).is_none());
+    }
+
+    #[test]
+    fn hex_case_recases_digits_but_not_the_prefix() {
+        assert_eq!(HexCase::Upper.apply("0xcafe01"), "0xCAFE01");
+        assert_eq!(HexCase::Upper.apply("cafe01"), "CAFE01");
+        assert_eq!(HexCase::Lower.apply("0xCAFE01"), "0xcafe01");
+    }
+
+    #[test]
+    fn decimal_form_reads_the_address_as_one_integer() {
+        let max = format!("0x{}", "f".repeat(64));
+        let decimal = decimal_form(&max).unwrap();
+        assert_eq!(decimal, "115792089237316195423570985008687907853269984665640564039457584007913129639935");
+        assert_eq!(decimal.len(), ADDRESS_DECIMAL_LEN);
+
+        let birthday = format!("0x{:0>64}", "12fa6c5");
+        assert_eq!(decimal_form(&birthday).unwrap(), "19900101");
+        let criteria = SearchCriteria::Decimal { pattern: "9900".to_string() };
+        assert!(criteria.matches(&birthday, false));
+        assert!(!SearchCriteria::Decimal { pattern: "1991".to_string() }.matches(&birthday, false));
+        assert!(SearchCriteria::Decimal { pattern: "907853269984".to_string() }.matches(&max, false));
+    }
+
+    #[test]
+    fn decimal_patterns_must_be_digits() {
+        assert!(validate_decimal("19900101").is_ok());
+        assert!(validate_decimal("1990ab").is_err());
+        assert!(validate_decimal("").is_err());
+        assert!(validate_decimal(&"1".repeat(79)).is_err());
+    }
+
+    #[test]
+    fn preview_of_a_prefix_embeds_the_pattern() {
+        let prefix = SearchCriteria::Pattern { pattern: "CaFe".to_string(), position: "prefix".to_string() };
+        let preview = prefix.preview(false, HexCase::Lower);
+        assert_eq!(preview, format!("0xcafe{}", "0".repeat(60)));
+        assert!(prefix.matches(&preview, false));
+        assert_eq!(prefix.preview(false, HexCase::Upper), format!("0xCAFE{}", "0".repeat(60)));
+
+        let suffix = SearchCriteria::Pattern { pattern: "beef".to_string(), position: "suffix".to_string() };
+        assert_eq!(suffix.preview(false, HexCase::Lower), format!("0x{}beef", "0".repeat(60)));
+    }
+
+    #[test]
+    fn fast_redraws_keep_the_requested_refresh_interval() {
+        assert_eq!(options_for(&["--refresh-ms", "250", "prefix", "0"]).refresh, Duration::from_millis(250));
+        assert_eq!(options_for(&["prefix", "0"]).refresh, Duration::from_millis(100));
+
+        let base = Duration::from_millis(250);
+        let mut pacer = RedrawPacer::new(base);
+        for _ in 0..20 {
+            assert_eq!(pacer.record(Duration::from_millis(1)), base);
+        }
+    }
+
+    #[test]
+    fn budget_length_is_the_longest_that_fits() {
+        // 10 s at 10,000 keys/s is 100,000 attempts: 16^4 = 65,536 fits, 16^5 doesn't
+        assert_eq!(budget_pattern_length(10.0, 10_000.0, 1), 4);
+        // Five matches leave 20,000 attempts each
+        assert_eq!(budget_pattern_length(10.0, 10_000.0, 5), 3);
+        assert_eq!(budget_pattern_length(3600.0, 1e6, 1), 7);
+        assert_eq!(budget_pattern_length(0.001, 100.0, 1), 1);
+        assert_eq!(budget_pattern_length(1e300, 1e6, 1), ADDRESS_HEX_LEN);
+        for (budget, rate, count) in [(10.0, 10_000.0, 1), (60.0, 50_000.0, 3), (3600.0, 1e6, 1)] {
+            let length = budget_pattern_length(budget, rate, count) as i32;
+            let secs = |length: i32| 16f64.powi(length) * count as f64 / rate;
+            assert!(secs(length) <= budget && secs(length + 1) > budget);
+        }
+    }
+
+    fn multi_at(pairs: &[&str]) -> SearchCriteria {
+        let constraints = pairs.iter().map(|pair| parse_offset_pattern(pair).unwrap()).collect();
+        SearchCriteria::MultiAt { constraints }
+    }
+
+    #[test]
+    fn multi_at_needs_every_constraint() {
+        let criteria = multi_at(&["0:cafe", "60:beef"]);
+        assert!(criteria.validate(false).is_ok());
+
+        let mut address = address_with("cafe", 0);
+        assert!(!criteria.matches(&address, false));
+        address.replace_range(62..66, "BEEF");
+        assert!(criteria.matches(&address, false));
+        assert!(!criteria.matches(&address, true));
+        assert!(!criteria.matches(&address_with("beef", 60), false));
+    }
+
+    #[test]
+    fn multi_at_rejects_overlapping_constraints() {
+        let error = multi_at(&["4:cafe", "6:beef"]).validate(false).unwrap_err();
+        assert!(error.contains("overlap"), "{}", error);
+        // Touching is fine, sharing a nibble is not
+        assert!(multi_at(&["4:cafe", "8:beef"]).validate(false).is_ok());
+        assert!(multi_at(&["62:beef"]).validate(false).is_err());
+        assert!(parse_offset_pattern("cafe").is_err());
+    }
+
+    #[test]
+    fn broken_random_generators_fail_the_health_check() {
+        assert!(check_rng_health(generate_random_private_key).is_ok());
+
+        let stuck = check_rng_health(|| [0u8; 32]).unwrap_err();
+        assert!(stuck.contains("0x00 repeated"), "{}", stuck);
+
+        // Distinct-looking keys that cycle after a few draws
+        let mut draws = 0u8;
+        let cycling = check_rng_health(|| {
+            draws = (draws + 1) % 8;
+            let mut key = [0x5a; 32];
+            key[31] = draws;
+            key
+        })
+        .unwrap_err();
+        assert!(cycling.contains("#9 repeats"), "{}", cycling);
+    }
+
+    fn flag_error(args: &[&str]) -> String {
+        let args = Args::try_parse_from(std::iter::once("fuel-vanity-generator").chain(args.iter().copied())).unwrap();
+        validate_flags(&args).unwrap_err()
+    }
+
+    #[test]
+    fn conflicting_flags_are_rejected_with_a_reason() {
+        let conflicts: &[(&[&str], &str)] = &[
+            (&["--count", "0", "prefix", "0"], "--count"),
+            (&["--delimiter", ";", "prefix", "0"], "--delimiter"),
+            (&["--group", "4", "--output-format", "json", "prefix", "0"], "--group"),
+            (&["--key-format", "base64", "--output-format", "binary", "prefix", "0"], "--key-format"),
+            (&["--output-public-key", "--output-format", "binary", "prefix", "0"], "--output-public-key"),
+            (&["--format-preview", "--output-format", "csv", "prefix", "0"], "--format-preview"),
+            (&["--show-hash", "prefix", "0"], "--show-hash"),
+            (&["--pick", "prefix", "0"], "--pick"),
+            (&["--hash", "keccak256", "--check-balance", "http://localhost", "prefix", "0"], "--check-balance"),
+            (&["--hash", "blake2b", "derive-scan", "8", "4"], "--hash"),
+            (&["--format-preview", "--check-balance", "http://localhost", "prefix", "0"], "--check-balance"),
+            (&["--deterministic", "prefix", "0"], "--deterministic"),
+            (&["--coordinator", "keyspace", "--resume-from-key", &"ab".repeat(32), "prefix", "0"], "--resume-from-key"),
+            (&["--coordinator", "keyspace", "--resume-from-counter", "5", "prefix", "0"], "--resume-from-counter"),
+        ];
+        for (args, flag) in conflicts {
+            let error = flag_error(args);
+            assert!(error.contains(flag), "{:?}: {}", args, error);
+        }
+        assert!(validate_flags(&Args::try_parse_from(["fuel-vanity-generator", "prefix", "0"]).unwrap()).is_ok());
+
+        // Pairs clap rejects on its own
+        for args in [&["--threads", "2", "--auto-threads"][..], &["--no-progress", "--milestone", "5"][..]] {
+            assert!(Args::try_parse_from(std::iter::once("fuel-vanity-generator").chain(args.iter().copied())).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn rate_counts_only_keys_checked_after_the_warm_up() {
+        // Still warming up: no rate yet, and the final rate falls back to the overall one
+        let mut meter = RateMeter::new(Instant::now());
+        assert_eq!(meter.rate(500), None);
+        assert!(meter.final_rate(500) > 0.0);
+
+        // A burst of a million keys during the warm-up becomes the baseline, not part of the rate
+        let mut meter = RateMeter::new(Instant::now() - RATE_WARMUP * 2);
+        assert_eq!(meter.rate(1_000_000), None);
+        thread::sleep(Duration::from_millis(100));
+        let rate = meter.rate(1_000_100).unwrap();
+        assert!(rate > 0.0 && rate <= 1_000.0, "{}", rate);
+    }
+
+    #[test]
+    fn json_reports_carry_the_schema_version() {
+        let options = options_for(&["--output-format", "json", "prefix", "0"]);
+        let criteria = SearchCriteria::Pattern { pattern: "0".to_string(), position: "prefix".to_string() };
+        let run = SearchRun { results: Vec::new(), matches: Vec::new(), attempts: 0, elapsed: Duration::ZERO, rate: 0.0 };
+        let meta = json_meta(&criteria, &options, &run);
+        assert_eq!(meta["schema_version"], JSON_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn paused_workers_stop_counting_until_resumed() {
+        let paused = Arc::new(AtomicBool::new(false));
+        let throttled = Arc::new(AtomicBool::new(false));
+        let searching = Arc::new(AtomicBool::new(true));
+        let checked = Arc::new(AtomicUsize::new(0));
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let (paused, throttled, searching, checked) = (paused.clone(), throttled.clone(), searching.clone(), checked.clone());
+                thread::spawn(move || {
+                    while searching.load(Ordering::SeqCst) {
+                        if hold_if_paused(&paused, &throttled) {
+                            continue;
+                        }
+                        checked.fetch_add(1, Ordering::SeqCst);
+                        thread::yield_now();
+                    }
+                })
+            })
+            .collect();
+
+        // Once every worker has finished the key it was on, a paused count has to stand still
+        thread::sleep(Duration::from_millis(50));
+        paused.store(true, Ordering::SeqCst);
+        thread::sleep(HOLD_POLL * 2);
+        let at_pause = checked.load(Ordering::SeqCst);
+        thread::sleep(HOLD_POLL * 4);
+        assert_eq!(checked.load(Ordering::SeqCst), at_pause);
+
+        paused.store(false, Ordering::SeqCst);
+        thread::sleep(HOLD_POLL * 4);
+        assert!(checked.load(Ordering::SeqCst) > at_pause);
+
+        searching.store(false, Ordering::SeqCst);
+        for worker in workers {
+            worker.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn boxes_are_never_wider_than_the_terminal() {
+        for columns in 0..=200 {
+            for width in [RESULT_BOX_WIDTH, CONFIG_BOX_WIDTH] {
+                if box_fits_columns(width, Some(columns)) {
+                    assert!(width <= columns, "{} columns wide box on a {} column terminal", width, columns);
+                }
+            }
+            if let Some(wrap) = result_wrap_width(Some(columns)) {
+                assert!(wrap + RESULT_BOX_BORDER <= columns, "result boxes {} wide on {} columns", wrap + RESULT_BOX_BORDER, columns);
+            }
+        }
+        assert!(box_fits_columns(RESULT_BOX_WIDTH, Some(RESULT_BOX_WIDTH)));
+        assert!(!box_fits_columns(CONFIG_BOX_WIDTH, Some(CONFIG_BOX_WIDTH - 1)));
+        // Output that isn't a terminal always gets boxes
+        assert!(box_fits_columns(RESULT_BOX_WIDTH, None));
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let ms = |values: &[u64]| values.iter().map(|&v| Duration::from_millis(v)).collect::<Vec<_>>();
+        let samples = ms(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(percentile(&samples, 50.0), Duration::from_millis(5));
+        assert_eq!(percentile(&samples, 90.0), Duration::from_millis(9));
+        assert_eq!(percentile(&samples, 91.0), Duration::from_millis(10));
+        assert_eq!(percentile(&samples, 100.0), Duration::from_millis(10));
+        assert_eq!(percentile(&samples, 0.0), Duration::from_millis(1));
+        // One sample is every percentile; an even split rounds the median down
+        assert_eq!(percentile(&ms(&[7]), 90.0), Duration::from_millis(7));
+        assert_eq!(percentile(&ms(&[1, 100]), 50.0), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn impossible_case_sensitive_patterns_are_rejected() {
+        let prefix = |pattern: &str| SearchCriteria::Pattern { pattern: pattern.to_string(), position: "prefix".to_string() };
+        // The low digit of a byte is always A, C, E uppercase and b, d, f lowercase
+        for impossible in ["0a", "0B", "cafe0a"] {
+            let error = prefix(impossible).validate(true).unwrap_err();
+            assert!(error.contains("can never match case-sensitively"), "{}: {}", impossible, error);
+            // Without --case-sensitive the same pattern is fine
+            assert!(prefix(impossible).validate(false).is_ok());
+        }
+        for possible in ["0A", "0b", "Ab", "0", "1234"] {
+            assert!(prefix(possible).validate(true).is_ok(), "{}", possible);
+        }
+
+        // The offending digits are named, and a placement shifted by one digit changes them
+        assert_eq!(mixed_case_conflict("0a", 0), Some("0a".to_string()));
+        assert_eq!(mixed_case_conflict("10a", 1), Some("0a".to_string()));
+        let anywhere = SearchCriteria::Pattern { pattern: "0a".to_string(), position: "anywhere".to_string() };
+        assert_eq!(anywhere.validate(true).is_ok(), mixed_case_conflict("0a", 1).is_none());
+    }
+
+    #[test]
+    fn coordinated_keys_are_consecutive_blocks_of_the_chacha20_keystream() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut coordinator = Coordinator::open(&dir.path().join("state"), true).unwrap();
+        coordinator.seed = [0; 32];
+        // RFC 7539 A.1, test vector 1: the keystream of the all-zero key and nonce
+        assert_eq!(hex::encode(coordinator.key_at(0)), "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7");
+        assert_eq!(hex::encode(coordinator.key_at(1)), "da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586");
+
+        // Any counter is a seek into the same stream, not a fresh one
+        coordinator.seed = [0x5a; 32];
+        let mut keystream = ChaCha20Rng::from_seed(coordinator.seed);
+        let mut stream = [0u8; 32 * 100];
+        keystream.fill_bytes(&mut stream);
+        for counter in [0, 1, 7, 99] {
+            let offset = counter as usize * 32;
+            assert_eq!(coordinator.key_at(counter), stream[offset..offset + 32], "{}", counter);
+        }
+    }
+
+    #[test]
+    fn deterministic_resume_continues_after_the_last_checked_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state");
+
+        // The first run checks ten keys of its batch and is interrupted
+        let first = Coordinator::open(&path, true).unwrap();
+        let mut claimed = 0..0;
+        let checked: Vec<[u8; 32]> = (0..10).map(|_| first.next_key(&mut claimed).unwrap()).collect();
+        first.give_back().unwrap();
+        drop(first);
+
+        // The second run picks up at the eleventh counter of the same keyspace
+        let second = Coordinator::open(&path, true).unwrap();
+        let mut claimed = 0..0;
+        let resumed: Vec<[u8; 32]> = (0..10).map(|_| second.next_key(&mut claimed).unwrap()).collect();
+        assert_eq!(resumed, (10..20).map(|counter| second.key_at(counter)).collect::<Vec<_>>());
+        assert_eq!(checked, (0..10).map(|counter| second.key_at(counter)).collect::<Vec<_>>());
+        assert!(resumed.iter().all(|key| !checked.contains(key)));
+
+        // Without deterministic mode the rest of the batch is skipped rather than given back
+        second.give_back().unwrap();
+        let third = Coordinator::open(&path, false).unwrap();
+        let mut claimed = 0..0;
+        third.next_key(&mut claimed).unwrap();
+        third.give_back().unwrap();
+        assert_eq!(claimed.start, 21);
+        assert_eq!(Coordinator::open(&path, false).unwrap().claim().unwrap().start, 20 + COORDINATOR_BATCH);
+    }
+
+    #[test]
+    fn zero_threads_means_every_core() {
+        let cores = num_cpus::get();
+        assert_eq!(resolve_threads(0), cores);
+        assert_eq!(options_for(&["--threads", "0", "prefix", "0"]).threads, cores);
+        assert_eq!(options_for(&["--threads", "3", "prefix", "0"]).threads, 3);
+    }
+
+    #[test]
+    fn slow_redraws_stretch_the_interval_until_they_speed_up() {
+        let base = Duration::from_millis(100);
+        let mut pacer = RedrawPacer::new(base);
+        // A draw taking more than a quarter of the interval doubles it, until the draw fits
+        let intervals: Vec<Duration> = (0..8).map(|_| pacer.record(Duration::from_millis(60))).collect();
+        assert_eq!(intervals[0], base * 2);
+        assert!(intervals[1..].iter().all(|&interval| interval == base * 4));
+        // However slow the terminal, it stops at the cap
+        let intervals: Vec<Duration> = (0..8).map(|_| pacer.record(Duration::from_secs(1))).collect();
+        assert!(intervals.windows(2).all(|pair| pair[1] >= pair[0]));
+        assert_eq!(*intervals.last().unwrap(), MAX_REFRESH);
+
+        // Fast draws again bring it back down, never below the requested interval
+        let recovered: Vec<Duration> = (0..8).map(|_| pacer.record(Duration::from_millis(1))).collect();
+        assert!(recovered.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert_eq!(*recovered.last().unwrap(), base);
+
+        // A requested interval above the cap is kept as it is
+        let mut slow = RedrawPacer::new(MAX_REFRESH * 2);
+        assert_eq!(slow.record(MAX_REFRESH), MAX_REFRESH * 2);
+    }
+
+    #[test]
+    fn constrained_keys_satisfy_their_constraint() {
+        for spec in ["weight=100-120", "weight=128", "zero-bytes=1"] {
+            let constraint = parse_key_constraint(spec).unwrap();
+            for _ in 0..50 {
+                let key = generate_constrained_key(Some(constraint));
+                assert!(constraint.accepts(&key), "{} drew 0x{}", spec, hex::encode(key));
+            }
+        }
+        let weight = |key: &[u8; 32]| key.iter().map(|byte| byte.count_ones()).sum::<u32>();
+        let key = generate_constrained_key(Some(parse_key_constraint("weight=100-120").unwrap()));
+        assert!((100..=120).contains(&weight(&key)));
+        assert_eq!(generate_constrained_key(Some(parse_key_constraint("zero-bytes=1").unwrap()))[0], 0);
+
+        // Unparseable specs, and ones that would keep too few keys to search with
+        for spec in ["weight", "weight=10-5", "weight=300", "entropy=3", "zero-bytes=4", "weight=0-20"] {
+            assert!(parse_key_constraint(spec).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn coordinator_state_survives_a_crash_mid_rewrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counter");
+        let seed = [7u8; 32];
+        let mut file = Coordinator::lock(&path).unwrap();
+        Coordinator::write_state(&mut file, &seed, 123_456_789).unwrap();
+
+        // A crash after the new, shorter line is written but before the old tail is cut off
+        let line = format!("{} {}\n", hex::encode(seed), 4096);
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file.write_all(line.as_bytes()).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().len() > line.len());
+        assert_eq!(Coordinator::read_state(&mut file).unwrap(), Some((seed, 4096)));
+
+        // A finished rewrite leaves exactly one line, and the file is never found empty
+        Coordinator::write_state(&mut file, &seed, 8192).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), format!("{} 8192\n", hex::encode(seed)));
+        drop(file);
+        assert_eq!(Coordinator::open(&path, false).unwrap().seed, seed);
+    }
+
+    #[test]
+    fn grouping_only_inserts_separators() {
+        let address = format!("0xcafe0000deadbeef{}", "1".repeat(48));
+        let grouped = group_digits(&address, 4, " ");
+        assert!(grouped.starts_with("0x cafe 0000 dead beef 1111 "), "{}", grouped);
+        assert_eq!(grouped.split(' ').count(), 1 + 16);
+        assert_eq!(grouped.replace(' ', ""), address);
+
+        assert_eq!(group_digits("0xabcdef", 4, "_"), "0x_abcd_ef");
+        assert_eq!(group_digits("abcdef", 2, "-"), "ab-cd-ef");
+        // With --no-0x there is no prefix to set apart
+        assert_eq!(group_digits("abcdef", 8, " "), "abcdef");
+    }
+
+    #[test]
+    fn base64_keys_decode_to_the_key_and_verify() {
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        let encoded = format_private_key(&key, KeyFormat::Base64, HexCase::Lower, true);
+        assert_eq!(encoded, "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAE=");
+        assert_eq!(BASE64_STANDARD.decode(&encoded).unwrap(), key);
+        let address = "0x09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db";
+        assert!(verify_key_address_pair_with_hash(&encoded, address, AddressHash::Sha256));
+        assert_eq!(format_private_key(&key, KeyFormat::Hex, HexCase::Lower, true), format!("0x{}", hex::encode(key)));
+    }
+
+    #[test]
+    fn modulo_matches_the_remainder_of_the_whole_address() {
+        let odd = "0x09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db";
+        let even = "0x0f00659c4fc61f786e6bfdff507c2ef99057a1062bd94da830bc90e194a2918c";
+        let even_last_byte = SearchCriteria::Modulo { divisor: 2, remainder: 0 };
+        assert!(even_last_byte.matches(even, false));
+        assert!(!even_last_byte.matches(odd, false));
+        assert!(SearchCriteria::Modulo { divisor: 16, remainder: 0xb }.matches(odd, false));
+        assert!(SearchCriteria::Modulo { divisor: 16, remainder: 0xc }.matches(even, false));
+        assert!(!SearchCriteria::Modulo { divisor: 16, remainder: 0xc }.matches(odd, false));
+
+        // Divisors that aren't powers of two read every nibble
+        for address in [odd, even] {
+            let value = BigUint::parse_bytes(&address.as_bytes()[2..], 16).unwrap();
+            for divisor in [2, 3, 7, 16, 1000, u64::MAX] {
+                let expected = (&value % divisor).to_u64_digits().first().copied().unwrap_or(0);
+                assert_eq!(address_modulo(address, divisor), expected, "{} mod {}", address, divisor);
+            }
+        }
+
+        assert!(SearchCriteria::Modulo { divisor: 0, remainder: 0 }.validate(false).is_err());
+        assert!(SearchCriteria::Modulo { divisor: 16, remainder: 16 }.validate(false).is_err());
+        assert!(SearchCriteria::Modulo { divisor: 16, remainder: 15 }.validate(false).is_ok());
+    }
+
+    #[test]
+    fn resuming_never_moves_the_shared_counter_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state");
+        let next_free = || std::fs::read_to_string(&path).unwrap().split_whitespace().nth(1).unwrap().parse::<u64>().unwrap();
+
+        let first = Coordinator::open(&path, false).unwrap();
+        let mut claimed = 0..0;
+        first.next_key(&mut claimed).unwrap();
+        assert_eq!(next_free(), COORDINATOR_BATCH);
+
+        // Going back to counter 5 replays the keys up to the next free counter in this run
+        // only, while another process keeps claiming fresh batches past them
+        let resumed = Coordinator::open(&path, true).unwrap();
+        resumed.resume_at(5).unwrap();
+        assert_eq!(next_free(), COORDINATOR_BATCH);
+        let mut resumed_claims = 0..0;
+        assert_eq!(resumed.next_key(&mut resumed_claims).unwrap(), resumed.key_at(5));
+        let other = Coordinator::open(&path, false).unwrap();
+        let mut other_claims = 0..0;
+        assert_eq!(other.next_key(&mut other_claims).unwrap(), other.key_at(COORDINATOR_BATCH));
+        let replayed: Vec<[u8; 32]> = (6..COORDINATOR_BATCH).map(|_| resumed.next_key(&mut resumed_claims).unwrap()).collect();
+        assert_eq!(replayed, (6..COORDINATOR_BATCH).map(|counter| resumed.key_at(counter)).collect::<Vec<_>>());
+        // Past the replay the resumed run claims after the other process, and an interrupted
+        // replay gives nothing back
+        assert_eq!(resumed.next_key(&mut resumed_claims).unwrap(), resumed.key_at(2 * COORDINATOR_BATCH));
+        resumed.give_back().unwrap();
+        assert!(next_free() >= 2 * COORDINATOR_BATCH);
+
+        // A counter past the next free one skips ahead, e.g. to a shard boundary
+        let shard = Coordinator::open(&path, true).unwrap();
+        shard.resume_at(1_000_000).unwrap();
+        assert_eq!(next_free(), 1_000_000);
+        let mut claimed = 0..0;
+        assert_eq!(shard.next_key(&mut claimed).unwrap(), shard.key_at(1_000_000));
+    }
+
+    #[test]
+    fn resuming_from_a_key_continues_the_same_sequence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state");
+
+        // One uninterrupted run of twenty keys
+        let straight = Coordinator::open(&path, true).unwrap();
+        let mut claimed = 0..0;
+        let sequence: Vec<[u8; 32]> = (0..20).map(|_| straight.next_key(&mut claimed).unwrap()).collect();
+        straight.give_back().unwrap();
+        drop(straight);
+
+        // Resuming after the tenth key hands out the last ten again, in the same order
+        let resumed = Coordinator::open(&path, true).unwrap();
+        assert_eq!(resumed.resume_after(&sequence[9]).unwrap(), 9);
+        let mut claimed = 0..0;
+        let rest: Vec<[u8; 32]> = (0..10).map(|_| resumed.next_key(&mut claimed).unwrap()).collect();
+        assert_eq!(rest, sequence[10..]);
+        // and then goes on with keys no run has checked
+        assert_eq!(resumed.next_key(&mut claimed).unwrap(), resumed.key_at(20));
+
+        // Only keys the file has handed out can be resumed from
+        assert!(resumed.resume_after(&[0xab; 32]).is_err());
+        assert!(Coordinator::check(&path, Some(&[0xab; 32])).is_err());
+        assert!(Coordinator::check(&path, Some(&sequence[0])).is_ok());
+
+        let key = format!("{:0>64}", "1");
+        assert_eq!(parse_keyspace_key(&key).unwrap()[31], 1);
+        assert_eq!(parse_keyspace_key(&format!("0x{}", key)), parse_keyspace_key(&key));
+        for malformed in ["", "0x", "1", "zz".repeat(32).as_str(), &"1".repeat(66)] {
+            assert!(parse_keyspace_key(malformed).is_err(), "{:?}", malformed);
+        }
+    }
+
+    #[tokio::test]
+    async fn every_match_found_before_the_stop_is_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        // Every address leaves remainder 0 mod 1, so all eight workers find a match on every
+        // key and keep racing to fill the last places
+        for round in 0..5 {
+            let log = dir.path().join(format!("audit-{}.log", round));
+            let options = options_for(&[
+                "--no-progress", "--threads", "8", "--count", "40",
+                "--audit-log", log.to_str().unwrap(), "modulo", "1", "0",
+            ]);
+            let run = search_vanity_address(SearchCriteria::Modulo { divisor: 1, remainder: 0 }, &options, false, Arc::default()).await;
+            assert_eq!(run.matches.len(), 40);
+
+            let contents = std::fs::read_to_string(&log).unwrap();
+            let mut logged: Vec<&str> = contents
+                .lines()
+                .map(|line| line.split("address=").nth(1).unwrap().split('\t').next().unwrap())
+                .collect();
+            let mut found: Vec<String> = run.matches.iter().map(|found| prefixed_hex(&found.address)).collect();
+            found.sort_unstable();
+            logged.sort_unstable();
+            assert_eq!(found, logged, "round {}", round);
+        }
+    }
+
+    #[test]
+    fn key_counts_get_separators_then_exponents() {
+        let cases = [
+            (0.0, "0"),
+            (999.0, "999"),
+            (1_000.0, "1,000"),
+            (65_536.0, "65,536"),
+            (1_234_567_890.0, "1,234,567,890"),
+            (999_999_999_999_999.0, "999,999,999,999,999"),
+            (1e15, "1.0e15"),
+            (2.5e30, "2.5e30"),
+        ];
+        for (keys, expected) in cases {
+            assert_eq!(describe_keys(keys), expected);
+        }
+        assert_eq!(HumanCount(1_234_567).to_string(), "1,234,567");
+    }
+
+    #[test]
+    fn result_width_follows_the_terminal_within_the_clamps() {
+        assert_eq!(result_wrap_width(None), Some(RESULT_WRAP_DEFAULT));
+        // Too narrow for even the smallest box
+        assert_eq!(result_wrap_width(Some(0)), None);
+        assert_eq!(result_wrap_width(Some(RESULT_WRAP_MIN + RESULT_BOX_BORDER - 1)), None);
+        // In between, the box fills the terminal
+        assert_eq!(result_wrap_width(Some(RESULT_WRAP_MIN + RESULT_BOX_BORDER)), Some(RESULT_WRAP_MIN));
+        assert_eq!(result_wrap_width(Some(40)), Some(36));
+        assert_eq!(result_wrap_width(Some(60)), Some(56));
+        // Wide terminals stop at one line per address
+        assert_eq!(result_wrap_width(Some(RESULT_WRAP_MAX + RESULT_BOX_BORDER)), Some(RESULT_WRAP_MAX));
+        assert_eq!(result_wrap_width(Some(300)), Some(RESULT_WRAP_MAX));
+        assert_eq!(RESULT_WRAP_MAX, "0x".len() + ADDRESS_HEX_LEN);
+    }
+
+    #[test]
+    fn patterns_outside_printable_ascii_are_rejected() {
+        assert_eq!(
+            validate_pattern("café", "prefix").unwrap_err(),
+            "Pattern character 4 is U+00E9, which is not printable ASCII and never appears in an address"
+        );
+        assert!(validate_pattern("ab\u{1b}[2J", "suffix").unwrap_err().contains("U+001B"));
+        assert!(validate_decimal("12\u{663}").unwrap_err().contains("U+0663"));
+        assert!(validate_ascii("cafe").is_ok());
+        // Printable non-hex characters only get the hex warning, not this error
+        assert!(validate_ascii("xyz!").is_ok());
+    }
+
+    #[test]
+    fn minimize_keeps_the_smallest_address_checked() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(189);
+        let keys: Vec<[u8; 32]> = (0..300)
+            .map(|_| {
+                let mut key = [0u8; 32];
+                rng.fill_bytes(&mut key);
+                key
+            })
+            .collect();
+        let deriver = EcDeriver::new();
+        let (Reverse(smallest), key) = best_of(keys.iter().copied(), &deriver, |address| Reverse(*address)).unwrap();
+
+        let addresses: Vec<[u8; 32]> = keys.iter().map(|key| deriver.address(key).unwrap()).collect();
+        assert_eq!(Some(&smallest), addresses.iter().min());
+        assert_eq!(deriver.address(&key), Some(smallest));
+        // A byte comparison is the same order as the hex digits
+        assert!(addresses.iter().all(|address| hex::encode(smallest) <= hex::encode(address)));
+
+        assert!(best_of(std::iter::empty(), &deriver, |address| Reverse(*address)).is_none());
+        // Keys without an address are skipped
+        assert!(best_of(std::iter::once([0u8; 32]), &deriver, |address| Reverse(*address)).is_none());
+    }
+
+    #[test]
+    fn infeasible_counts_are_capped_to_what_a_day_fits() {
+        let day = FEASIBLE_SECS;
+        // 100 matches of a million keys each at 1,000 keys/s take about 28 hours
+        assert_eq!(feasible_count(1e6, 100, 1_000.0), Some((day * 1_000.0 / 1e6) as usize));
+        assert_eq!(feasible_count(1e6, 86, 1_000.0), None);
+        // A pattern where even one match takes longer still asks for one
+        assert_eq!(feasible_count(16f64.powi(12), 1000, 1_000.0), Some(1));
+
+        // A ten-digit prefix is out of reach of any machine, so --auto-cap asks for one match
+        let mut options = options_for(&["--count", "1000", "--threads", "1", "prefix", "0000000000"]);
+        let cmd = Command::Prefix { pattern: "0000000000".to_string() };
+        check_feasibility(&cmd, &mut options, false);
+        assert_eq!(options.count, 1000);
+        check_feasibility(&cmd, &mut options, true);
+        assert_eq!(options.count, 1);
+
+        // Counts that fit are left alone
+        let mut options = options_for(&["--count", "1000", "--threads", "1", "prefix", "0"]);
+        check_feasibility(&Command::Prefix { pattern: "0".to_string() }, &mut options, true);
+        assert_eq!(options.count, 1000);
+    }
+
+    #[test]
+    fn dates_become_the_prefix_pattern() {
+        let options = options_for(&["prefix", "0"]);
+        let prefix = |date: Option<&str>| match resolve_date(Command::Date { date: date.map(str::to_string) }, &options) {
+            Ok(Command::Prefix { pattern }) => Ok(pattern),
+            Ok(other) => panic!("{:?} resolved to {:?}", date, other),
+            Err(e) => Err(e),
+        };
+        assert_eq!(prefix(Some("20240101")).unwrap(), "20240101");
+        assert_eq!(prefix(Some("2024-12-31")).unwrap(), "20241231");
+        for invalid in ["20241301", "20240230", "2024011", "202401011", "2024Jan1", ""] {
+            assert_eq!(prefix(Some(invalid)).unwrap_err(), format!("'{}' is not a date in the form YYYYMMDD", invalid));
+        }
+
+        let today = prefix(None).unwrap();
+        assert_eq!(today.len(), 8);
+        assert!(chrono::NaiveDate::parse_from_str(&today, "%Y%m%d").is_ok());
+        assert!(is_valid_hex_pattern(&today));
+
+        // Other commands pass through untouched
+        assert!(matches!(resolve_date(Command::Suffix { pattern: "ab".to_string() }, &options), Ok(Command::Suffix { .. })));
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_search_keeps_its_matches_and_the_next_one_runs() {
+        let options = options_for(&["--no-progress", "--threads", "2", "--count", "1000000", "prefix", "0"]);
+        let criteria = SearchCriteria::Pattern { pattern: "0".to_string(), position: "prefix".to_string() };
+
+        // Ctrl+C at the prompt sets the flag of the running search
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let interrupt = {
+            let cancelled = cancelled.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(500));
+                cancelled.store(true, Ordering::SeqCst);
+            })
+        };
+        let start = Instant::now();
+        let run = search_vanity_address(criteria.clone(), &options, false, cancelled).await;
+        interrupt.join().unwrap();
+        assert!(start.elapsed() < Duration::from_secs(30));
+        assert!(!run.matches.is_empty() && run.matches.len() < 1_000_000);
+        assert!(run.results.iter().all(|found| found.address.starts_with("0x0")));
+
+        // The prompt's next search gets a fresh flag and runs to its count
+        let options = options_for(&["--no-progress", "--threads", "2", "--count", "3", "prefix", "0"]);
+        let run = search_vanity_address(criteria, &options, false, Arc::default()).await;
+        assert_eq!(run.matches.len(), 3);
+    }
+
+    #[test]
+    fn verify_on_find_catches_a_broken_derivation() {
+        let mut key = [0u8; 32];
+        key[31] = 1;
+        let address = |deriver: EcDeriver| format_address(&deriver.address(&key).unwrap(), false);
+        assert_eq!(check_found(&key, &address(EcDeriver::new()), AddressHash::Sha256), Ok(()));
+        assert_eq!(check_found(&key, &address(EcDeriver::with_hash(AddressHash::Blake2b)), AddressHash::Blake2b), Ok(()));
+
+        // A worker hashing with Keccak-256 where SHA-256 was asked for
+        let wrong_hash = address(EcDeriver::with_hash(AddressHash::Keccak256));
+        assert_eq!(
+            check_found(&key, &wrong_hash, AddressHash::Sha256).unwrap_err(),
+            format!("the key of match {} derives a different address", wrong_hash)
+        );
+        // One flipped bit in the derived address
+        let mut flipped = EcDeriver::new().address(&key).unwrap();
+        flipped[31] ^= 1;
+        assert!(check_found(&key, &format_address(&flipped, false), AddressHash::Sha256).is_err());
+    }
+
+    #[test]
+    fn surprise_keeps_the_highest_scoring_address() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(202);
+        let keys: Vec<[u8; 32]> = (0..500)
+            .map(|_| {
+                let mut key = [0u8; 32];
+                rng.fill_bytes(&mut key);
+                key
+            })
+            .collect();
+        let deriver = EcDeriver::new();
+        let ((score, Reverse(champion)), key) = best_of(keys.iter().copied(), &deriver, surprise_rank).unwrap();
+        assert_eq!(deriver.address(&key), Some(champion));
+
+        let addresses: Vec<[u8; 32]> = keys.iter().map(|key| deriver.address(key).unwrap()).collect();
+        let best = addresses.iter().map(|address| surprise_score(address).total()).max().unwrap();
+        assert_eq!(score, best);
+        assert_eq!(surprise_score(&champion).total(), best);
+        // Ties go to the smallest address
+        let tied = addresses.iter().filter(|address| surprise_score(address).total() == best).min().unwrap();
+        assert_eq!(&champion, tied);
+    }
+
+    #[test]
+    fn sequences_match_ascending_and_descending_runs() {
+        use SequenceDirection::{Asc, Desc};
+
+        // 64 digits: `run` at `offset`, zeros everywhere else
+        let address = |run: &str, offset: usize| format!("0x{}{}{}", "0".repeat(offset), run, "0".repeat(64 - offset - run.len()));
+        let sequence = |direction, length, position: &str| SearchCriteria::Sequence { direction, length, position: position.to_string() };
+
+        let ascending_start = address("0123456789", 0);
+        assert!(sequence(Asc, 10, "prefix").matches(&ascending_start, false));
+        assert!(!sequence(Asc, 11, "prefix").matches(&ascending_start, false));
+        assert!(!sequence(Desc, 3, "prefix").matches(&ascending_start, false));
+        assert_eq!(sequence(Asc, 4, "prefix").describe_match(&ascending_start).unwrap(), "0123 at nibble 0");
+
+        let descending_start = address("fedcba", 0);
+        assert!(sequence(Desc, 6, "prefix").matches(&descending_start, false));
+        assert!(!sequence(Asc, 2, "prefix").matches(&descending_start, false));
+
+        // Anywhere finds the run in the middle, which a prefix search must not
+        let ascending_middle = address("3456", 20);
+        assert!(sequence(Asc, 4, "anywhere").matches(&ascending_middle, false));
+        assert!(sequence(Asc, 4, "contains").matches(&ascending_middle, false));
+        assert!(!sequence(Asc, 4, "prefix").matches(&ascending_middle, false));
+        assert_eq!(sequence(Asc, 4, "anywhere").describe_match(&ascending_middle).unwrap(), "3456 at nibble 20");
+
+        let descending_middle = address("9876", 30);
+        assert!(sequence(Desc, 4, "anywhere").matches(&descending_middle, false));
+        assert!(!sequence(Desc, 5, "anywhere").matches(&descending_middle, false));
+        assert!(!sequence(Desc, 4, "prefix").matches(&descending_middle, false));
+
+        // Runs stop at f going up and at 0 going down rather than wrapping around
+        assert!(!sequence(Asc, 3, "anywhere").matches(&address("ef01", 10), false));
+        assert!(!sequence(Desc, 3, "anywhere").matches(&address("10fe", 10), false));
+
+        assert!(sequence(Asc, 1, "prefix").validate(false).is_err());
+        assert!(sequence(Asc, 17, "prefix").validate(false).is_err());
+        assert!(sequence(Desc, 16, "anywhere").validate(false).is_ok());
+    }
+
+#[test]
+fn dual_matches_the_bech32_form_of_its_network() {
+    let address = format!("0x{}", "5a".repeat(32));
+    let bytes = [0x5au8; 32];
+    let data = &to_bech32_for_network(&bytes, Network::Mainnet)[5..9];
+    let dual = |network| SearchCriteria::Dual { hex: "5a".to_string(), bech32: data.to_string(), network };
+
+    assert!(dual(Network::Mainnet).matches(&address, false));
+    assert!(dual(Network::Testnet).matches(&address, false));
+    assert_eq!(
+        dual(Network::Testnet).describe_match(&address),
+        Some(to_bech32_for_network(&bytes, Network::Testnet))
+    );
+    assert!(dual(Network::Testnet).pattern_label().contains("tfuel1"));
+}
+
+#[tokio::test]
+async fn a_large_count_keeps_only_the_newest_results_in_full() {
+    let options = options_for(&["--no-progress", "--threads", "4", "--count", "50", "--output-format", "line", "modulo", "1", "0"]);
+    let run = search_vanity_address(SearchCriteria::Modulo { divisor: 1, remainder: 0 }, &options, false, Arc::default()).await;
+    assert_eq!(run.matches.len(), 50);
+    assert_eq!(run.results.len(), DISPLAY_TAIL);
+
+    let addresses: HashSet<[u8; 32]> = run.matches.iter().map(|found| found.address).collect();
+    assert_eq!(addresses.len(), 50);
+    // The kept results are the newest matches, in the order they were found
+    let newest: Vec<String> = run.matches[50 - DISPLAY_TAIL..].iter().map(|found| prefixed_hex(&found.address)).collect();
+    let kept: Vec<&str> = run.results.iter().map(|found| found.address.as_str()).collect();
+    assert_eq!(kept, newest);
+}
+
+}
+
+#[cfg(all(test, feature = "thermal"))]
+mod thermal_tests {
+    use super::*;
+
+    #[test]
+    fn thermal_throttle_pauses_above_the_limit_and_resumes_below_the_hysteresis() {
+        // A mocked sensor: heats past the limit, hovers inside the hysteresis band, cools down
+        let readings = [Some(70.0), Some(80.0), Some(81.0), None, Some(78.0), Some(76.0), Some(74.9), Some(80.0), Some(80.5)];
+        let expected = [false, false, true, true, true, true, false, false, true];
+        let mut throttled = false;
+        for (reading, expected) in readings.into_iter().zip(expected) {
+            throttled = thermal_throttle(throttled, reading, 80.0);
+            assert_eq!(throttled, expected, "after {:?}", reading);
+        }
+    }
+
+    #[test]
+    fn watch_temperature_holds_workers_back_while_hot() {
+        let throttled = Arc::new(AtomicBool::new(false));
+        let searching = Arc::new(AtomicBool::new(true));
+        let watcher = watch_temperature(80.0, || Some(95.0), throttled.clone(), searching.clone());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !throttled.load(Ordering::SeqCst) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(throttled.load(Ordering::SeqCst));
+        thread::sleep(Duration::from_millis(200));
+        searching.store(false, Ordering::SeqCst);
+        assert!(watcher.join().unwrap() >= Duration::from_millis(200));
+    }
+}
+
+#[cfg(all(test, feature = "network"))]
+mod network_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // A Fuel node as the GraphQL client sees it, holding 42 of one asset for every owner
+    fn funded_node(request: &serde_json::Value) -> serde_json::Value {
+        let query = request["query"].as_str().unwrap_or_default();
+        if query.contains("nodeInfo") {
+            return serde_json::json!({ "data": { "nodeInfo": {
+                "utxoValidation": true,
+                "vmBacktrace": false,
+                "maxTx": "4064",
+                "maxGas": "30000000000",
+                "maxSize": "131072000",
+                "maxDepth": "32",
+                "nodeVersion": "0.41.7",
+                "indexation": { "balances": false, "coinsToSpend": false, "assetMetadata": false },
+                "txPoolStats": { "txCount": "0", "totalGas": "0", "totalSize": "0" },
+            } } });
+        }
+        let owner = request["variables"]["filter"]["owner"].clone();
+        serde_json::json!({ "data": { "balances": {
+            "edges": [{
+                "cursor": "0",
+                "node": { "owner": owner, "amount": "42", "assetId": format!("0x{}", "f8".repeat(32)) },
+            }],
+            "pageInfo": { "endCursor": null, "hasNextPage": false, "hasPreviousPage": false, "startCursor": null },
+        } } })
+    }
+
+    #[tokio::test]
+    async fn balance_check_reports_a_mocked_rpc_response() {
+        let (url, received) = mock_server(200, funded_node);
+        let options = super::tests::options_for(&["--theme", "mono", "--check-balance", &url, "prefix", "09c0"]);
+        let address = [0x09u8; 32];
+        let matches = [MatchRecord { address, attempts: 1, found_after: Duration::ZERO }];
+
+        let mut lines = Vec::new();
+        check_balances_with(&matches, &options, |line| lines.push(line.to_string())).await;
+        assert_eq!(
+            lines,
+            vec![
+                format!("⚠️  {} already holds 1 asset(s) on this network!", prefixed_hex(&address)),
+                format!("   {}: 42", "f8".repeat(32)),
+            ]
+        );
+        // The balances were asked for the address of the match
+        let queried: Vec<serde_json::Value> = received.try_iter().collect();
+        assert!(queried.iter().any(|request| request["variables"]["filter"]["owner"] == prefixed_hex(&address)), "{:?}", queried);
+
+        // A node that can't be reached skips the check instead of failing the run
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+        let options = super::tests::options_for(&["--theme", "mono", "--check-balance", &url, "prefix", "09c0"]);
+        let mut lines = Vec::new();
+        check_balances_with(&matches, &options, |line| lines.push(line.to_string())).await;
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with(&format!("⚠️  Balance check skipped, could not connect to {}", url)), "{}", lines[0]);
+    }
+
+    #[tokio::test]
+    async fn balance_check_retries_until_the_rpc_answers() {
+        let theme = Theme::new(ThemeName::Mono);
+        // A mocked RPC that fails twice before answering
+        let flaky = |calls: &std::cell::Cell<u32>| {
+            calls.set(calls.get() + 1);
+            let attempt = calls.get();
+            async move {
+                if attempt <= 2 {
+                    Err(format!("attempt {} timed out", attempt))
+                } else {
+                    Ok(HashMap::from([("0xf8f8".to_string(), 7u128)]))
+                }
+            }
+        };
+
+        let calls = std::cell::Cell::new(0);
+        let outcome = with_backoff(3, || flaky(&calls)).await;
+        assert_eq!(calls.get(), 3);
+        assert_eq!(balance_report("0xabc", outcome, &theme)[1], "   0xf8f8: 7");
+
+        // Out of retries, the last failure is reported instead of ending the run
+        let calls = std::cell::Cell::new(0);
+        let outcome = with_backoff(1, || flaky(&calls)).await;
+        assert_eq!(calls.get(), 2);
+        assert_eq!(balance_report("0xabc", outcome, &theme), vec!["⚠️  Balance check failed for 0xabc: attempt 2 timed out".to_string()]);
+    }
+
+    // A one-thread HTTP server answering every request with `status` and the JSON `answer`
+    // gives for its body, handing over each body
+    fn mock_server(status: u16, answer: fn(&serde_json::Value) -> serde_json::Value) -> (String, std::sync::mpsc::Receiver<serde_json::Value>) {
+        use std::io::{BufRead, BufReader, Read};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (bodies, received) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let body = serde_json::from_slice(&body).unwrap();
+                let answer = answer(&body).to_string();
+                let _ = bodies.send(body);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status, answer.len(), answer
+                );
+            }
+        });
+        (url, received)
+    }
+
+    fn found(address: &str, attempts: usize) -> FoundAddress {
+        FoundAddress {
+            address: address.to_string(),
+            private_key: format!("0x{:0>64}", "1"),
+            public_key: None,
+            attempts,
+            found_after: Duration::ZERO,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn webhook_posts_each_match_without_the_key() {
+        let theme = Theme::new(ThemeName::Mono);
+        let (url, received) = mock_server(200, |_| serde_json::Value::Null);
+        let webhook = Webhook::start(&url, false, &theme).unwrap();
+        webhook.post(&found("0x09c0b2d1", 1234), "09c0", "prefix");
+        webhook.post(&found("0x09c0ffff", 5678), "09c0", "prefix");
+        webhook.wait_delivered();
+
+        let first = received.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(first["address"], "0x09c0b2d1");
+        assert_eq!(first["pattern"], "09c0");
+        assert_eq!(first["position"], "prefix");
+        assert_eq!(first["attempts"], 1234);
+        assert!(chrono::DateTime::parse_from_rfc3339(first["timestamp"].as_str().unwrap()).is_ok());
+        assert!(first.get("private_key").is_none());
+        assert_eq!(received.recv_timeout(Duration::from_secs(5)).unwrap()["address"], "0x09c0ffff");
+
+        let (url, received) = mock_server(200, |_| serde_json::Value::Null);
+        let webhook = Webhook::start(&url, true, &theme).unwrap();
+        webhook.post(&found("0x09c0b2d1", 1), "09c0", "prefix");
+        webhook.wait_delivered();
+        assert_eq!(received.recv_timeout(Duration::from_secs(5)).unwrap()["private_key"], format!("0x{:0>64}", "1"));
+    }
+
+    #[test]
+    fn failed_webhook_deliveries_are_retried_then_dropped() {
+        let (url, received) = mock_server(500, |_| serde_json::Value::Null);
+        let webhook = Webhook::start(&url, false, &Theme::new(ThemeName::Mono)).unwrap();
+        webhook.post(&found("0x09c0b2d1", 1), "09c0", "prefix");
+        webhook.wait_delivered();
+        assert_eq!(webhook.pending.load(Ordering::SeqCst), 0);
+        assert_eq!(received.try_iter().count(), 1 + WEBHOOK_RETRIES as usize);
+
+        assert!(Webhook::start("not a url", false, &Theme::new(ThemeName::Mono)).is_err());
+    }
+}
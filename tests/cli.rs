@@ -0,0 +1,997 @@
+// End-to-end checks of the binary. Every run gets an empty working directory and HOME, so
+// a fuel-vanity.toml or FUEL_VANITY_* variable on the machine can't change the outcome.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+// The binary in `dir` with none of the environment that changes its behaviour
+fn binary(dir: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_fuel-vanity-generator"));
+    command.current_dir(dir).env("HOME", dir).env("XDG_CONFIG_HOME", dir);
+    for var in ["FUEL_VANITY_PATTERN", "FUEL_VANITY_POSITION", "FUEL_VANITY_COUNT", "NO_COLOR", "TMUX", "STY", "SSH_TTY"] {
+        command.env_remove(var);
+    }
+    command
+}
+
+fn run(dir: &Path, args: &[&str], env: &[(&str, &str)]) -> Output {
+    binary(dir)
+        .arg("--i-understand")
+        .args(args)
+        .envs(env.iter().copied())
+        .output()
+        .expect("failed to run the binary")
+}
+
+fn json(output: &Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).expect("stdout is not JSON")
+}
+
+#[test]
+fn env_vars_drive_the_search() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(
+        dir.path(),
+        &["--output-format", "json"],
+        &[("FUEL_VANITY_PATTERN", "0"), ("FUEL_VANITY_POSITION", "suffix"), ("FUEL_VANITY_COUNT", "2")],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report = json(&output);
+    assert_eq!(report["meta"]["pattern"], "0");
+    assert_eq!(report["meta"]["position"], "suffix");
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result["address"].as_str().unwrap().ends_with('0')));
+}
+
+#[test]
+fn cli_args_override_env_vars() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(
+        dir.path(),
+        &["--output-format", "json", "--count", "1", "prefix", "1"],
+        &[("FUEL_VANITY_PATTERN", "0"), ("FUEL_VANITY_COUNT", "3")],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report = json(&output);
+    assert_eq!(report["meta"]["pattern"], "1");
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0]["address"].as_str().unwrap().starts_with("0x1"));
+}
+
+#[test]
+fn zero_count_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--count", "0", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(3));
+    let output = run(dir.path(), &["prefix", "0"], &[("FUEL_VANITY_COUNT", "0")]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("must be at least 1"));
+}
+
+#[test]
+fn audit_log_never_holds_private_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "json", "--count", "3", "--audit-log", "audit.log", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let report = json(&output);
+    let log = std::fs::read_to_string(dir.path().join("audit.log")).unwrap();
+    assert_eq!(log.lines().count(), 3);
+    for result in report["results"].as_array().unwrap() {
+        let key = result["private_key"].as_str().unwrap();
+        assert!(log.contains(result["address"].as_str().unwrap()));
+        assert!(!log.to_lowercase().contains(&key.trim_start_matches("0x").to_lowercase()));
+    }
+}
+
+#[test]
+fn no_progress_still_shows_every_result() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--no-progress", "--count", "2", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(stdout.matches("Private Key").count(), 2);
+    assert!(!stderr.contains("Searched"), "{}", stderr);
+}
+
+#[test]
+fn over_length_suffix_exits_before_searching() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["suffix", &"0".repeat(70)], &[]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("at most 64 characters"));
+}
+
+#[test]
+fn every_theme_shows_results_and_mono_has_no_escapes() {
+    let dir = tempfile::tempdir().unwrap();
+    for theme in ["green", "blue", "mono", "high-contrast"] {
+        let output = run(dir.path(), &["--theme", theme, "--count", "1", "prefix", "0"], &[]);
+        assert_eq!(output.status.code(), Some(0), "{}", theme);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("Private Key"), "{}", theme);
+        assert_eq!(stdout.contains('\x1b'), theme != "mono", "{}", theme);
+    }
+}
+
+// Key 1, whose Fuel address is 0x09c0b2d1...
+const KEY_ONE: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+#[test]
+fn exit_codes_tell_found_from_incomplete_from_invalid() {
+    let dir = tempfile::tempdir().unwrap();
+    let found = run(dir.path(), &["--test-derivation", KEY_ONE, "prefix", "09c0"], &[]);
+    assert_eq!(found.status.code(), Some(0));
+    // The only key checked doesn't match, so the run ends with no match
+    let incomplete = run(dir.path(), &["--test-derivation", KEY_ONE, "prefix", "ffff"], &[]);
+    assert_eq!(incomplete.status.code(), Some(2));
+    let invalid = run(dir.path(), &["edges", "g", "4"], &[]);
+    assert_eq!(invalid.status.code(), Some(3));
+    let usage = run(dir.path(), &["--no-such-flag"], &[]);
+    assert_eq!(usage.status.code(), Some(3));
+}
+
+#[test]
+fn uppercase_display_still_verifies() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--hex-case", "upper", "--output-format", "json", "--count", "2", "prefix", "a"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    for result in json(&output)["results"].as_array().unwrap() {
+        let (address, key) = (result["address"].as_str().unwrap(), result["private_key"].as_str().unwrap());
+        assert!(address.starts_with("0xA"), "{}", address);
+        assert_eq!(address[2..], address[2..].to_uppercase());
+        assert_eq!(key[2..], key[2..].to_uppercase());
+        assert!(fuel_vanity_generator::verify_key_address_pair(key, address));
+    }
+}
+
+#[test]
+fn stats_prints_the_environment_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "--hash", "keccak256", "stats"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for field in ["Version: ", "CPU cores: ", "Hash: Keccak-256", "Address: ", "Rate (1 core): "] {
+        assert!(stdout.contains(field), "missing {:?} in {}", field, stdout);
+    }
+
+    let output = run(dir.path(), &["--output-format", "json", "stats"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let report = json(&output);
+    assert_eq!(report["meta"]["command"], "stats");
+    assert_eq!(report["stats"]["version"], env!("CARGO_PKG_VERSION"));
+    assert!(report["stats"]["cpu_cores"].as_u64().unwrap() >= 1);
+    assert!(report["stats"]["hash"].as_str().unwrap().starts_with("SHA-256"));
+    assert!(report["stats"]["keys_per_sec_one_core"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn shared_terminal_warning_until_acknowledged() {
+    let dir = tempfile::tempdir().unwrap();
+    let search = ["--count", "1", "prefix", "0"];
+    for (var, name) in [("TMUX", "tmux"), ("STY", "screen"), ("SSH_TTY", "an SSH session")] {
+        let warned = binary(dir.path()).args(search).env(var, "1").output().unwrap();
+        assert_eq!(warned.status.code(), Some(0));
+        let stderr = String::from_utf8_lossy(&warned.stderr);
+        assert!(stderr.contains(&format!("Running inside {}", name)), "{}", stderr);
+    }
+
+    let acknowledged = run(dir.path(), &search, &[("TMUX", "1")]);
+    assert!(!String::from_utf8_lossy(&acknowledged.stderr).contains("Running inside"));
+    let local = binary(dir.path()).args(search).output().unwrap();
+    assert!(!String::from_utf8_lossy(&local.stderr).contains("Running inside"));
+}
+
+#[test]
+fn binary_records_read_back_as_verified_pairs() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "binary", "--count", "3", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(output.stdout.len(), 3 * 64);
+    for record in output.stdout.chunks_exact(64) {
+        let (address, key) = record.split_at(32);
+        let (address, key) = (format!("0x{}", hex::encode(address)), format!("0x{}", hex::encode(key)));
+        assert!(address.starts_with("0x0"));
+        assert!(fuel_vanity_generator::verify_key_address_pair(&key, &address));
+    }
+}
+
+#[test]
+fn many_threads_never_exceed_or_repeat_the_count() {
+    let dir = tempfile::tempdir().unwrap();
+    for _ in 0..3 {
+        let output = run(dir.path(), &["--threads", "16", "--count", "40", "--output-format", "json", "prefix", "0"], &[]);
+        assert_eq!(output.status.code(), Some(0));
+        let results = json(&output)["results"].as_array().unwrap().clone();
+        let addresses: std::collections::HashSet<&str> = results.iter().map(|result| result["address"].as_str().unwrap()).collect();
+        assert_eq!(results.len(), 40);
+        assert_eq!(addresses.len(), 40);
+    }
+}
+
+#[test]
+fn format_preview_prints_an_example_without_searching() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "--format-preview", "prefix", "cafe"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("0xcafe{}", "0".repeat(60))), "{}", stdout);
+    assert!(!stdout.contains("Private Key"));
+}
+
+#[test]
+fn binary_records_stream_while_the_search_runs() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    // Far more matches than the test waits for, so anything read arrived mid-search
+    let mut child = binary(dir.path())
+        .args(["--i-understand", "--no-progress", "--output-format", "binary", "--count", "1000000", "prefix", "0"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut records = [0u8; 5 * 64];
+    child.stdout.take().unwrap().read_exact(&mut records).unwrap();
+    assert!(child.try_wait().unwrap().is_none(), "the search already ended");
+    child.kill().unwrap();
+    child.wait().unwrap();
+    for record in records.chunks_exact(64) {
+        assert_eq!(record[0] >> 4, 0);
+    }
+}
+
+#[test]
+fn dual_matches_satisfy_both_encodings() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "json", "--count", "2", "dual", "0", "qq"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    for result in json(&output)["results"].as_array().unwrap() {
+        let address = result["address"].as_str().unwrap();
+        assert!(address.starts_with("0x0"), "{}", address);
+
+        // Re-encode independently rather than trusting the detail column
+        let bytes: [u8; 32] = hex::decode(&address[2..]).unwrap().try_into().unwrap();
+        let bech32 = fuel_vanity_generator::to_bech32(&bytes);
+        assert!(bech32.starts_with("fuel1qq"), "{}", bech32);
+        assert_eq!(result["detail"], bech32.as_str());
+        assert!(fuel_vanity_generator::verify_key_address_pair(result["private_key"].as_str().unwrap(), address));
+    }
+}
+
+#[test]
+fn sample_prints_exactly_that_many_verifying_pairs() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "json", "sample", "3"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let results = json(&output)["results"].as_array().unwrap().clone();
+    assert_eq!(results.len(), 3);
+    for result in results {
+        let key = result["private_key"].as_str().unwrap();
+        assert!(fuel_vanity_generator::verify_key_address_pair(key, result["address"].as_str().unwrap()));
+    }
+}
+
+#[test]
+fn closed_stdout_ends_the_run_without_a_panic() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    for format in ["text", "csv", "binary"] {
+        // Far more output than a pipe buffers, so the writes outlive the reader
+        let mut child = binary(dir.path())
+            .args(["--i-understand", "--output-format", format, "sample", "5000"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut first = Vec::new();
+        BufReader::new(child.stdout.take().unwrap()).read_until(b'\n', &mut first).unwrap();
+        let output = child.wait_with_output().unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!stderr.contains("panicked"), "{}: {}", format, stderr);
+        assert_eq!(output.status.code(), Some(0), "{}", format);
+    }
+}
+
+#[test]
+fn json_meta_describes_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(
+        dir.path(),
+        &["--output-format", "json", "--threads", "2", "--count", "3", "--case-sensitive", "--hash", "keccak256", "suffix", "A"],
+        &[],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report = json(&output);
+    let meta = &report["meta"];
+    assert_eq!(meta["schema_version"], 1);
+    assert_eq!(meta["pattern"], "A");
+    assert_eq!(meta["position"], "suffix");
+    assert_eq!(meta["case_sensitive"], true);
+    assert_eq!(meta["hash_algo"], "keccak256");
+    assert_eq!(meta["format"], "mixed-case hex");
+    assert_eq!(meta["threads"], 2);
+    assert!(meta["seed"].is_null());
+    // Every match was checked, so the attempts can't be fewer than the results
+    assert!(meta["attempts"].as_u64().unwrap() >= 3);
+    assert!(meta["elapsed_secs"].as_f64().unwrap() > 0.0);
+    assert_eq!(report["results"].as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn milestones_are_logged_once_per_interval() {
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    // A pattern the run won't find before it is stopped, logged to a pipe rather than a terminal
+    let mut child = binary(dir.path())
+        .args(["--i-understand", "--milestone", "1000", "prefix", "0000000"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::thread::sleep(std::time::Duration::from_secs(3));
+    child.kill().unwrap();
+    let stderr = String::from_utf8(child.wait_with_output().unwrap().stderr).unwrap();
+
+    let searched: Vec<u64> = stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("Searched ")?.split(' ').next()?.replace(',', "").parse().ok())
+        .collect();
+    assert!(searched.len() >= 2, "{}", stderr);
+    // Each line is past the next multiple of the interval, and no interval is logged twice
+    assert!(searched[0] >= 1000);
+    assert!(searched.windows(2).all(|pair| pair[1] / 1000 > pair[0] / 1000), "{:?}", searched);
+}
+
+#[test]
+fn small_counts_stay_exact_under_many_threads() {
+    let dir = tempfile::tempdir().unwrap();
+    // With a one-digit prefix nearly every worker finds a match at once, so a count that
+    // isn't capped atomically overshoots most easily here
+    for count in ["1", "5"] {
+        for _ in 0..5 {
+            let output = run(dir.path(), &["--threads", "32", "--count", count, "--output-format", "json", "prefix", "a"], &[]);
+            assert_eq!(output.status.code(), Some(0));
+            let results = json(&output)["results"].as_array().unwrap().len();
+            assert_eq!(results.to_string(), count);
+        }
+    }
+}
+
+#[test]
+fn line_output_parses_back_into_verifying_pairs() {
+    let dir = tempfile::tempdir().unwrap();
+    for (delimiter, extra) in [(":", &[][..]), (";", &["--delimiter", ";"][..])] {
+        let mut args = vec!["--output-format", "line", "--count", "3"];
+        args.extend_from_slice(extra);
+        args.extend(["prefix", "0"]);
+        let output = run(dir.path(), &args, &[]);
+        assert_eq!(output.status.code(), Some(0));
+
+        // Nothing but the result lines reaches stdout
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines.len(), 3, "{}", stdout);
+        for line in lines {
+            let (address, key) = line.split_once(delimiter).unwrap();
+            assert!(address.starts_with("0x0"));
+            assert!(fuel_vanity_generator::verify_key_address_pair(key, address));
+        }
+    }
+}
+
+#[test]
+fn dual_scheme_matches_hold_for_both_addresses() {
+    use sha3::{Digest, Keccak256};
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "json", "--count", "2", "dual-scheme", "a"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    for result in json(&output)["results"].as_array().unwrap() {
+        let (address, key) = (result["address"].as_str().unwrap(), result["private_key"].as_str().unwrap());
+        assert!(address.starts_with("0xa"), "{}", address);
+        assert!(fuel_vanity_generator::verify_key_address_pair(key, address));
+
+        // The Ethereum address is the last 20 bytes of the Keccak-256 of the same public key
+        let secret = secp256k1::SecretKey::from_slice(&hex::decode(&key[2..]).unwrap()).unwrap();
+        let public = secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::new(), &secret);
+        let ethereum = format!("0x{}", hex::encode(&Keccak256::digest(&public.serialize_uncompressed()[1..])[12..]));
+        assert!(ethereum.starts_with("0xa"), "{}", ethereum);
+        assert_eq!(result["detail"], format!("Ethereum address {}", ethereum));
+    }
+}
+
+#[test]
+fn fixed_key_results_match_the_golden_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "--test-derivation", KEY_ONE, "prefix", "09c0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    // Everything display_results prints, from the summary line on; the banner above it is left out
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let results = &stdout[stdout.find("✅ Found").expect("no results in the output")..];
+    assert_eq!(results, include_str!("golden/key_one_results.txt"));
+}
+
+#[test]
+fn public_key_appears_only_with_the_flag_in_every_format() {
+    // The public key of key 1 is the secp256k1 generator point
+    const PUBLIC_KEY_ONE: &str = "0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+    let dir = tempfile::tempdir().unwrap();
+    for format in ["text", "json", "csv", "line"] {
+        for flag in [false, true] {
+            let mut args = vec!["--theme", "mono", "--output-format", format, "--test-derivation", KEY_ONE];
+            if flag {
+                args.push("--output-public-key");
+            }
+            args.extend(["prefix", "09"]);
+            let output = run(dir.path(), &args, &[]);
+            assert_eq!(output.status.code(), Some(0));
+            let stdout = String::from_utf8(output.stdout).unwrap();
+
+            let shown = match format {
+                // The text boxes wrap the key over several lines
+                "text" => stdout.contains("Public Key:") && stdout.contains("a8fd17b448a68554199c47d08ffb10d4b8"),
+                "json" => {
+                    let result = &serde_json::from_str::<serde_json::Value>(&stdout).unwrap()["results"][0];
+                    assert_eq!(result.get("public_key").is_some(), stdout.contains(PUBLIC_KEY_ONE));
+                    result.get("public_key").is_some()
+                }
+                "csv" => {
+                    let header = stdout.lines().next().unwrap();
+                    assert_eq!(header.contains("public_key"), stdout.contains(PUBLIC_KEY_ONE));
+                    header.contains("public_key")
+                }
+                _ => stdout.trim_end().rsplit(':').next() == Some(PUBLIC_KEY_ONE),
+            };
+            assert_eq!(shown, flag, "{} with --output-public-key {}: {}", format, flag, stdout);
+            assert_eq!(stdout.contains("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28"), flag, "{}", format);
+        }
+    }
+}
+
+#[test]
+fn climb_only_reports_longer_prefixes() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = binary(dir.path())
+        .args(["--i-understand", "--output-format", "json", "--threads", "4", "climb", "0"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    // A climb runs until stopped; the first three steps take a few thousand keys between them
+    let steps: Vec<serde_json::Value> = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .take(3)
+        .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+        .collect();
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let mut prefix = "0".to_string();
+    for step in &steps {
+        let address = step["address"].as_str().unwrap();
+        assert!(address[2..].starts_with(&prefix), "{} doesn't extend {}", address, prefix);
+        assert_eq!(step["prefix_len"], prefix.len());
+        assert!(fuel_vanity_generator::verify_key_address_pair(step["private_key"].as_str().unwrap(), address));
+        prefix = address[2..prefix.len() + 3].to_string();
+    }
+    assert_eq!(prefix.len(), 4);
+}
+
+#[test]
+fn csv_output_reads_back_row_by_row() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "csv", "--output-public-key", "--count", "3", "suffix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let mut reader = csv::Reader::from_reader(&output.stdout[..]);
+    assert_eq!(reader.headers().unwrap(), vec!["address", "private_key", "public_key", "pattern", "attempts"]);
+
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 3);
+    for row in &rows {
+        assert!(row[0].ends_with('0'));
+        assert!(fuel_vanity_generator::verify_key_address_pair(&row[1], &row[0]));
+        assert_eq!(row[2].len(), 2 + 128);
+        assert_eq!(&row[3], "0");
+        assert!(row[4].parse::<u64>().unwrap() >= 1);
+    }
+}
+
+#[test]
+fn latency_stats_summarise_every_match() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--latency-stats", "--count", "6", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let line = stderr.lines().find(|line| line.contains("Time to find")).expect("no latency line");
+    assert!(line.contains("over 6 matches"), "{}", line);
+
+    // min <= median <= p90 <= max
+    let seconds: Vec<f64> = ["min ", "median ", "p90 ", "max "]
+        .iter()
+        .map(|name| line.split(name).nth(1).unwrap().split('s').next().unwrap().parse().unwrap())
+        .collect();
+    assert!(seconds.windows(2).all(|pair| pair[0] <= pair[1]), "{}", line);
+}
+
+#[test]
+fn impossible_case_sensitive_pattern_exits_before_searching() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--case-sensitive", "prefix", "0a"], &[]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("'0a' can never match case-sensitively"));
+}
+
+#[test]
+fn dry_validate_checks_without_searching_or_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_log = dir.path().join("audit.log");
+    let audit_log = audit_log.to_str().unwrap();
+
+    let valid = run(dir.path(), &["--dry-validate", "--audit-log", audit_log, "prefix", "cafe"], &[]);
+    assert_eq!(valid.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&valid.stderr).contains("Configuration is valid"));
+    assert!(valid.stdout.is_empty());
+    // The audit log it would write is checked, not created
+    assert!(!dir.path().join("audit.log").exists());
+
+    let invalid = run(dir.path(), &["--dry-validate", "prefix", "xyz"], &[]);
+    assert_eq!(invalid.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&invalid.stderr).contains("'xyz' must use only hexadecimal characters"));
+
+    let unwritable = run(dir.path(), &["--dry-validate", "--audit-log", "missing/audit.log", "prefix", "0"], &[]);
+    assert_eq!(unwritable.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&unwritable.stderr).contains("the directory doesn't exist"));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn checksum_word_matches_are_in_the_checksum_casing() {
+    use sha3::{Digest, Keccak256};
+
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "json", "--count", "2", "checksum-word", "Ab"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    for result in json(&output)["results"].as_array().unwrap() {
+        // EIP-55 over the 64 digits: a letter is uppercase where its nibble of the hash is 8 or more
+        let digits = result["address"].as_str().unwrap().trim_start_matches("0x").to_lowercase();
+        let hash = Keccak256::digest(digits.as_bytes());
+        let checksummed: String = digits
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if (hash[i / 2] >> (4 - 4 * (i % 2))) & 0xf >= 8 { c.to_ascii_uppercase() } else { c })
+            .collect();
+        assert!(checksummed.contains("Ab"), "{}", checksummed);
+        assert_eq!(result["detail"], format!("checksum 0x{}", checksummed));
+    }
+}
+
+#[test]
+fn debug_match_explains_the_first_rejections() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "--debug-match", "--threads", "1", "--count", "1", "prefix", "7"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let candidates: Vec<&str> = stderr.lines().filter(|line| line.contains("Candidate ")).collect();
+    // Only the first few candidates are shown, fewer only if one of them already matched
+    assert!(candidates.len() == 5 || candidates.last().is_some_and(|line| line.ends_with(" - matches")), "{}", stderr);
+
+    for line in candidates {
+        let (address, verdict) = line.split_once(": ").unwrap().1.split_once(" - ").unwrap();
+        let first = address.chars().nth(2).unwrap();
+        if first == '7' {
+            assert_eq!(verdict, "matches");
+        } else {
+            assert_eq!(verdict, format!("nibble 0 is '{}', the pattern wants '7'", first));
+        }
+    }
+}
+
+#[test]
+fn zero_threads_still_searches() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "json", "--threads", "0", "--count", "2", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let report = json(&output);
+    assert!(report["meta"]["threads"].as_u64().unwrap() >= 1);
+    assert_eq!(report["results"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn grouping_changes_only_the_display() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "--group", "4", "--audit-log", "audit.log", "--count", "1", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    // The audit log keeps the address as found, the display splits it up
+    let log = std::fs::read_to_string(dir.path().join("audit.log")).unwrap();
+    let address = log.split('\t').find_map(|field| field.strip_prefix("address=")).unwrap();
+    assert_eq!(address.len(), 66);
+    let grouped = format!("0x {} {} {}", &address[2..6], &address[6..10], &address[10..14]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains(&grouped), "{}", grouped);
+}
+
+#[test]
+fn passphrase_and_counter_rebuild_the_same_key() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let rebuild = || {
+        let mut child = binary(dir.path())
+            .args(["--i-understand", "--theme", "mono", "from-passphrase", "0", "--counter", "11"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to run the binary");
+        child.stdin.take().unwrap().write_all(b"correct horse battery staple\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.status.code(), Some(0));
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let first = rebuild();
+    let flat: String = first.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+    assert!(flat.contains("0f00659c4fc61f786e6bfdff507c2ef99057a1062bd94da830bc90e194a2918c"));
+    assert!(flat.contains("37716c233f927e6abdfc76fd5b9fbc4a1f9cdb4bf1d30ffc0a257b0716c28641"));
+    assert!(first.contains("--counter 11"));
+    assert_eq!(rebuild(), first);
+}
+
+#[test]
+fn verify_input_reports_the_line_of_each_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let address = "0x09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db";
+    let pairs = format!("0x{:0>64},{address}\n0x{:0>64},{address}\n", "1", "2");
+    std::fs::write(dir.path().join("pairs.csv"), pairs).unwrap();
+
+    let output = run(dir.path(), &["--theme", "mono", "verify", "--input", "pairs.csv"], &[]);
+    assert_eq!(output.status.code(), Some(6));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!("Line 2: the key doesn't derive {}", address)));
+    assert!(!stdout.contains("Line 1"));
+    assert!(stdout.contains("1 of 2 pairs valid"));
+
+    std::fs::write(dir.path().join("valid.csv"), format!("0x{:0>64},{address}\n", "1")).unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "verify", "--input", "valid.csv"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("1 of 1 pairs valid"));
+}
+
+#[test]
+fn prompt_prints_the_estimate_before_a_long_search() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = binary(dir.path())
+        .args(["--i-understand", "--theme", "mono", "--no-progress"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to run the binary");
+    child.stdin.take().unwrap().write_all(b"prefix 0000000000\n").unwrap();
+
+    // The search never ends by itself, so lines are read on their own thread and the
+    // binary is killed once the search has started
+    let (lines, received) = mpsc::channel();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    std::thread::spawn(move || stdout.lines().map_while(Result::ok).try_for_each(|line| lines.send(line)));
+    let mut seen = Vec::new();
+    while let Ok(line) = received.recv_timeout(Duration::from_secs(60)) {
+        let started = line.contains("VANITY ADDRESS SEARCH");
+        seen.push(line);
+        if started {
+            break;
+        }
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(seen.last().is_some_and(|line| line.contains("VANITY ADDRESS SEARCH")), "{:#?}", seen);
+    let estimates: Vec<&String> = seen.iter().filter(|line| line.contains("keys per match")).collect();
+    assert_eq!(estimates.len(), 1, "{:#?}", seen);
+    assert!(estimates[0].contains("About 1,099,511,627,776 keys per match"));
+    assert!(estimates[0].contains("Ctrl+C stops it"));
+}
+
+#[test]
+fn no_0x_strips_every_prefix_and_still_verifies() {
+    let dir = tempfile::tempdir().unwrap();
+    for format in ["text", "json", "line", "csv"] {
+        let output = run(
+            dir.path(),
+            &["--no-0x", "--theme", "mono", "--output-public-key", "--output-format", format, "--count", "2", "prefix", "0"],
+            &[],
+        );
+        assert_eq!(output.status.code(), Some(0));
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(!stdout.contains("0x"), "{} output:\n{}", format, stdout);
+    }
+
+    // The stripped pairs verify, both through the library and through verify --input
+    let output = run(dir.path(), &["--no-0x", "--output-format", "line", "--count", "3", "prefix", "0"], &[]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut pairs = String::new();
+    for line in stdout.lines() {
+        let (address, key) = line.split_once(':').unwrap();
+        assert!(address.starts_with('0') && address.len() == 64);
+        assert!(fuel_vanity_generator::verify_key_address_pair(key, address));
+        pairs.push_str(&format!("{},{}\n", key, address));
+    }
+    std::fs::write(dir.path().join("pairs.csv"), pairs).unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "verify", "--input", "pairs.csv"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("3 of 3 pairs valid"));
+}
+
+#[test]
+fn non_ascii_patterns_exit_before_searching() {
+    let dir = tempfile::tempdir().unwrap();
+    for (args, message) in [
+        (["prefix", "café"], "Pattern character 4 is U+00E9"),
+        (["suffix", "ab\u{1b}[2J"], "Pattern character 3 is U+001B"),
+    ] {
+        let output = run(dir.path(), &[&["--theme", "mono"][..], &args[..]].concat(), &[]);
+        assert_eq!(output.status.code(), Some(3));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(message), "{}", stderr);
+        // The pattern is never echoed back, so the escape can't reach the terminal
+        let everything = [output.stdout, stderr.into_bytes()].concat();
+        assert!(!String::from_utf8(everything).unwrap().contains(args[1]));
+    }
+}
+
+#[test]
+fn count_distribution_records_every_match() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(
+        dir.path(),
+        &["--no-progress", "--output-format", "line", "--threads", "4", "--count", "25", "--count-distribution", "dist.csv", "prefix", "0"],
+        &[],
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let contents = std::fs::read_to_string(dir.path().join("dist.csv")).unwrap();
+    let mut lines = contents.lines();
+    assert_eq!(lines.next(), Some("match,attempts,gap"));
+    let rows: Vec<Vec<u64>> = lines.map(|line| line.split(',').map(|field| field.parse().unwrap()).collect()).collect();
+    assert_eq!(rows.len(), 25);
+    let mut previous = 0;
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(row[0], i as u64 + 1);
+        assert!(row[1] > 0, "match {} after {} attempts", row[0], row[1]);
+        assert_eq!(row[2], row[1] - previous);
+        previous = row[1];
+    }
+}
+
+#[test]
+fn config_file_sets_defaults_that_flags_override() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("fuel-vanity.toml"), "output-format = \"json\"\ncount = 2\nno_progress = true\n").unwrap();
+
+    let output = run(dir.path(), &["prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(json(&output)["results"].as_array().unwrap().len(), 2);
+
+    // A typed flag wins over the config file, and the rest of the file still applies
+    let output = run(dir.path(), &["--count", "1", "prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(json(&output)["results"].as_array().unwrap().len(), 1);
+
+    // The config directory is used when the working directory has no file
+    let elsewhere = tempfile::tempdir().unwrap();
+    let config_home = dir.path().to_str().unwrap();
+    let output = run(elsewhere.path(), &["prefix", "0"], &[("XDG_CONFIG_HOME", config_home)]);
+    assert_eq!(json(&output)["results"].as_array().unwrap().len(), 2);
+
+    std::fs::write(dir.path().join("fuel-vanity.toml"), "bogus = 1\n").unwrap();
+    let output = run(dir.path(), &["prefix", "0"], &[]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8(output.stderr).unwrap().contains("unknown option 'bogus'"));
+}
+
+#[test]
+fn show_hash_prints_the_known_vector_of_key_one() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = format!("0x{:0>64}", "1");
+    let public_key = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                      483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+    let sha256 = "09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db";
+
+    let output = run(dir.path(), &["--theme", "mono", "--show-hash", "--test-derivation", &key, "prefix", "0"], &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(&format!("Public key (x and y): {}", public_key)), "{}", stderr);
+    assert!(stderr.contains(&format!("SHA-256: {}", sha256)), "{}", stderr);
+    assert!(!stderr.contains("Keccak-256"));
+
+    // An Ethereum-style pair also shows the Keccak-256 hash its last 20 bytes come from
+    let pairs = format!("{key},0x{sha256}\n{key},0x7e5f4552091a69125d5dfcb7b8c2659029395bdf\n");
+    std::fs::write(dir.path().join("pairs.csv"), pairs).unwrap();
+    let output = run(dir.path(), &["--theme", "mono", "--show-hash", "verify", "--input", "pairs.csv"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches(&format!("Public key (x and y): {}", public_key)).count(), 2, "{}", stderr);
+    let keccak = stderr.lines().find_map(|line| line.strip_prefix("🧮 Keccak-256: ")).unwrap();
+    assert_eq!(keccak.len(), 64);
+    assert!(keccak.ends_with("7e5f4552091a69125d5dfcb7b8c2659029395bdf"));
+
+    // Without the flag nothing is traced
+    let output = run(dir.path(), &["--theme", "mono", "--test-derivation", &key, "prefix", "0"], &[]);
+    assert!(!String::from_utf8(output.stderr).unwrap().contains("🧮"));
+}
+
+#[test]
+fn probe_matches_about_one_in_sixteen_for_one_digit() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--output-format", "json", "--threads", "4", "probe", "--attempts", "16000", "0"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    let report = json(&output);
+    assert_eq!(report["meta"]["attempts"], 16000);
+    assert_eq!(report["expected_matches"], 1000.0);
+    // Binomial with a standard deviation of about 31, so this is over six of them
+    let matches = report["matches"].as_u64().unwrap();
+    assert!((800..=1200).contains(&matches), "{} matches", matches);
+}
+
+#[test]
+fn picking_result_two_exports_only_that_one() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = binary(dir.path())
+        .args(["--i-understand", "--theme", "mono", "--no-progress", "--pick", "--count", "3"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the binary");
+    child.stdin.take().unwrap().write_all(b"prefix 0\n2 wallet.txt\nexit\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let listed: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('#'))
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .collect();
+    assert_eq!(listed.len(), 3, "{}", stdout);
+
+    let saved = std::fs::read_to_string(dir.path().join("wallet.txt")).unwrap();
+    let lines: Vec<&str> = saved.lines().collect();
+    assert_eq!(lines.len(), 1);
+    let (key, address) = lines[0].split_once(',').unwrap();
+    assert_eq!(address, listed[1]);
+    assert!(fuel_vanity_generator::verify_key_address_pair(key, address));
+    // The saved key is never printed, and no other key is either
+    assert!(!stdout.contains(key));
+    assert!(!stdout.contains("Private Key"));
+}
+
+#[test]
+fn verify_at_the_prompt_checks_a_file() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    let address = "0x09c0b2d1a486c439a87bcba6b46a7a1a23f3897cc83a94521a96da5c23bc58db";
+    std::fs::write(dir.path().join("pairs.csv"), format!("0x{:0>64},{address}\n0x{:0>64},{address}\n", "1", "2")).unwrap();
+    let mut child = binary(dir.path())
+        .args(["--i-understand", "--theme", "mono"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the binary");
+    child.stdin.take().unwrap().write_all(b"verify pairs.csv\nexit\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    // A failing file is reported without leaving the prompt
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Line 2: the key doesn't derive"), "{}", stdout);
+    assert!(stdout.contains("1 of 2 pairs valid"));
+    assert!(!stdout.contains("Unknown command"));
+    assert!(stdout.contains("Thank you for using"));
+}
+
+#[test]
+fn dual_on_testnet_matches_the_tfuel_form() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = run(dir.path(), &["--network", "testnet", "--output-format", "json", "dual", "0", "qq"], &[]);
+    assert_eq!(output.status.code(), Some(0));
+    for result in json(&output)["results"].as_array().unwrap() {
+        let address = result["address"].as_str().unwrap();
+        let bytes: [u8; 32] = hex::decode(&address[2..]).unwrap().try_into().unwrap();
+        let bech32 = fuel_vanity_generator::to_bech32_for_network(&bytes, fuel_vanity_generator::Network::Testnet);
+        assert!(bech32.starts_with("tfuel1qq"), "{}", bech32);
+        assert_eq!(result["detail"], bech32.as_str());
+    }
+
+    let output = run(dir.path(), &["--network", "devnet", "dual", "0", "qq"], &[]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn text_line_and_json_results_stream_while_the_search_runs() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let dir = tempfile::tempdir().unwrap();
+    // Far more matches than the test waits for, so anything read arrived mid-search
+    for format in ["text", "line", "json"] {
+        let mut child = binary(dir.path())
+            .args(["--i-understand", "--no-progress", "--theme", "mono", "--output-format", format, "--count", "1000000", "prefix", "0"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let mut lines = BufReader::new(child.stdout.take().unwrap()).lines().map(|line| line.unwrap());
+        let addresses: Vec<String> = match format {
+            "text" => lines
+                .filter(|line| line.starts_with('#'))
+                .take(3)
+                .map(|line| line.split_whitespace().nth(1).unwrap().to_string())
+                .collect(),
+            "line" => lines.take(3).map(|line| line.split(':').next().unwrap().to_string()).collect(),
+            _ => {
+                assert_eq!(lines.next().as_deref(), Some("{"));
+                assert_eq!(lines.next().as_deref(), Some("  \"results\": ["));
+                lines
+                    .filter_map(|line| line.trim().strip_prefix("\"address\": \"").map(|rest| rest.trim_end_matches("\",").to_string()))
+                    .take(3)
+                    .collect()
+            }
+        };
+        assert!(child.try_wait().unwrap().is_none(), "{}: the search already ended", format);
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert_eq!(addresses.len(), 3, "{}", format);
+        for address in addresses {
+            assert!(address.starts_with("0x0") && address.len() == 66, "{}: {}", format, address);
+        }
+    }
+}
+
+#[test]
+fn state_file_runs_a_resumable_search_over_the_chacha20_keystream() {
+    use rand::{RngCore, SeedableRng};
+
+    let dir = tempfile::tempdir().unwrap();
+    let keys = |output: Output| -> Vec<String> {
+        assert_eq!(output.status.code(), Some(0));
+        String::from_utf8(output.stdout).unwrap().lines().map(|line| line.split(':').nth(1).unwrap()[2..].to_string()).collect()
+    };
+    let args = ["--state", "keyspace", "--output-format", "line", "--count", "2", "prefix", "0"];
+    let first = keys(run(dir.path(), &args, &[]));
+    let second = keys(run(dir.path(), &args, &[]));
+
+    // Every key is a 32-byte block of the keystream keyed by the file's seed, and the second
+    // run picks up after the last key the first one checked
+    let state = std::fs::read_to_string(dir.path().join("keyspace")).unwrap();
+    let seed: [u8; 32] = hex::decode(state.split_whitespace().next().unwrap()).unwrap().try_into().unwrap();
+    let mut keystream = rand_chacha::ChaCha20Rng::from_seed(seed);
+    let blocks: Vec<String> = (0..4096)
+        .map(|_| {
+            let mut key = [0u8; 32];
+            keystream.fill_bytes(&mut key);
+            hex::encode(key)
+        })
+        .collect();
+    let counters: Vec<usize> = first.iter().chain(&second).map(|key| blocks.iter().position(|block| block == key).unwrap()).collect();
+    assert!(counters.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", counters);
+}